@@ -1,9 +1,9 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::Criterion;
 use ordered_float::OrderedFloat;
 use rand::prelude::ThreadRng;
 use rand::Rng;
 
-use triangles::prelude::{Polygon2d, StaticPoint2d, StaticTriangle2d, Triangle2d};
+use triangles::prelude::{Polygon2d, StaticPoint2d, StaticTriangle2d, Triangle2d, TriangleIndex2d};
 
 fn create_triangles(count: usize, random: &mut ThreadRng) -> Vec<StaticTriangle2d<StaticPoint2d>> {
     let mut ret = Vec::with_capacity(count);
@@ -37,6 +37,15 @@ fn polygon_contains(triangles: &[StaticTriangle2d<StaticPoint2d>], points: &[Sta
     }
 }
 
+fn indexed_contains(
+    index: &TriangleIndex2d<StaticPoint2d, StaticTriangle2d<StaticPoint2d>>,
+    points: &[StaticPoint2d],
+) {
+    for point in points {
+        index.containing(point);
+    }
+}
+
 pub fn append_benches(c: &mut Criterion) {
     let mut random = rand::thread_rng();
     let triangles = create_triangles(50, &mut random);
@@ -47,4 +56,12 @@ pub fn append_benches(c: &mut Criterion) {
     c.bench_function("500 polygon_contains_pt", |b| {
         b.iter(|| polygon_contains(&triangles, &points))
     });
+    let many_triangles = create_triangles(5000, &mut random);
+    let index = TriangleIndex2d::new(many_triangles.clone());
+    c.bench_function("5000 triangle_contains_pt", |b| {
+        b.iter(|| triangle_contains(&many_triangles, &points))
+    });
+    c.bench_function("5000 indexed contains_pt", |b| {
+        b.iter(|| indexed_contains(&index, &points))
+    });
 }
@@ -0,0 +1,683 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::geometry2d::point::{Point2d, StaticPoint2d};
+use crate::geometry2d::polygon::with_holes::TriangulationFailed;
+use crate::geometry2d::triangle::static_triangle::StaticTriangle2d;
+use crate::geometry3d::plane::projection::PlaneProjection;
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::geometry3d::Vector3d;
+use crate::primitives::Number;
+
+/// Counter-clockwise triangles over a list of points
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelaunayTriangulation {
+    points: Vec<StaticPoint2d>,
+    triangles: Vec<[usize; 3]>,
+}
+
+impl DelaunayTriangulation {
+    pub fn points(&self) -> &[StaticPoint2d] {
+        &self.points
+    }
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+    pub fn static_triangles(&self) -> impl Iterator<Item = StaticTriangle2d<StaticPoint2d>> + '_ {
+        self.triangles.iter().map(|[p1, p2, p3]| {
+            StaticTriangle2d::new(self.points[*p1], self.points[*p2], self.points[*p3])
+        })
+    }
+    /// triangles in 3D on the plane of the projection, unused points are left out
+    pub fn lift(
+        &self,
+        projection: &PlaneProjection,
+        height: Number,
+    ) -> IndexedTriangleList<Vector3d> {
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        let mut new_idx = vec![None; self.points.len()];
+        let mut triangles = Vec::with_capacity(self.triangles.len());
+        for corners in self.triangles.iter() {
+            triangles.push(corners.map(|p| {
+                *new_idx[p].get_or_insert_with(|| {
+                    builder.append_point(projection.lift_point(&self.points[p], height))
+                })
+            }));
+        }
+        for [p1, p2, p3] in triangles {
+            builder.append_indexed_triangle(p1, p2, p3);
+        }
+        builder.build()
+    }
+}
+
+/// Constrained Delaunay triangulation of the area inside the rings by the even-odd rule, every
+/// ring edge is an edge of the result. Without rings the convex hull of the Steiner points is
+/// triangulated.
+///
+/// The points of the result start with the ring points in order, followed by the Steiner points.
+/// Where ring edges cross each other, the crossing points are appended at the end. Duplicated
+/// points are kept in the list but not referenced by any triangle.
+pub fn constrained_delaunay<P: Point2d>(
+    rings: &[Vec<P>],
+    steiner_points: &[StaticPoint2d],
+) -> Result<DelaunayTriangulation, TriangulationFailed> {
+    let points: Vec<StaticPoint2d> = rings
+        .iter()
+        .flatten()
+        .map(|p| p.coordinates())
+        .chain(steiner_points.iter().copied())
+        .collect();
+    let mut mesh = Mesh::new(points)?;
+    let mut start = 0;
+    for ring in rings {
+        for idx in 0..ring.len() {
+            mesh.insert_constraint(start + idx, start + (idx + 1) % ring.len())?;
+        }
+        start += ring.len();
+    }
+    Ok(mesh.finish(!rings.is_empty()))
+}
+
+pub(crate) enum Location {
+    Inside(usize),
+    /// triangle and index of the edge starting at that corner
+    OnEdge(usize, usize),
+    Vertex(usize),
+}
+
+/// Triangle mesh inside a large enclosing triangle, which is removed by [`Mesh::finish`]
+pub(crate) struct Mesh {
+    pub(crate) points: Vec<StaticPoint2d>,
+    pub(crate) triangles: Vec<[usize; 3]>,
+    /// triangle on the left side of every directed edge
+    pub(crate) edges: HashMap<(usize, usize), usize>,
+    /// constrained edges with the smaller index first
+    pub(crate) constraints: HashSet<(usize, usize)>,
+    /// vertex in the mesh for every point, duplicated points refer to the first one
+    alias: Vec<usize>,
+    input_count: usize,
+    pub(crate) tolerance: f64,
+    last: usize,
+}
+
+/// what the segment of a new constraint runs into
+enum SegmentWalk {
+    /// a vertex on the segment
+    Vertex(usize),
+    /// a constrained edge crossing the segment
+    Constraint(usize, usize),
+    /// the crossed edges, ordered from the start to the end of the segment
+    Crossed(Vec<(usize, usize)>),
+}
+
+pub(crate) fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+impl Mesh {
+    pub(crate) fn new(mut points: Vec<StaticPoint2d>) -> Result<Self, TriangulationFailed> {
+        let input_count = points.len();
+        let (min, max) = points.iter().fold(
+            ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]),
+            |(min, max), p| {
+                (
+                    [min[0].min(p.x.0), min[1].min(p.y.0)],
+                    [max[0].max(p.x.0), max[1].max(p.y.0)],
+                )
+            },
+        );
+        let size = (max[0] - min[0]).max(max[1] - min[1]);
+        if !size.is_finite() || size <= 0.0 {
+            return Err(TriangulationFailed(
+                "Not enough distinct points".to_string(),
+            ));
+        }
+        let center = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+        // far away, so all hull edges of the input are found
+        let radius = size * 100.0;
+        for angle in [90.0f64, 210.0, 330.0] {
+            let angle = angle.to_radians();
+            points.push(
+                (
+                    center[0] + radius * angle.cos(),
+                    center[1] + radius * angle.sin(),
+                )
+                    .into(),
+            );
+        }
+        let mut mesh = Self {
+            points,
+            triangles: vec![],
+            edges: HashMap::new(),
+            constraints: HashSet::new(),
+            alias: (0..input_count + 3).collect(),
+            input_count,
+            tolerance: size * 1e-10,
+            last: 0,
+        };
+        mesh.push([input_count, input_count + 1, input_count + 2]);
+        for idx in 0..input_count {
+            mesh.alias[idx] = mesh.insert(idx)?;
+        }
+        Ok(mesh)
+    }
+
+    fn is_super_vertex(&self, idx: usize) -> bool {
+        (self.input_count..self.input_count + 3).contains(&idx)
+    }
+
+    fn set(&mut self, triangle: usize, corners: [usize; 3]) {
+        let old = self.triangles[triangle];
+        for i in 0..3 {
+            let edge = (old[i], old[(i + 1) % 3]);
+            if self.edges.get(&edge) == Some(&triangle) {
+                self.edges.remove(&edge);
+            }
+        }
+        self.triangles[triangle] = corners;
+        for i in 0..3 {
+            self.edges
+                .insert((corners[i], corners[(i + 1) % 3]), triangle);
+        }
+    }
+
+    fn push(&mut self, corners: [usize; 3]) -> usize {
+        self.triangles.push(corners);
+        let triangle = self.triangles.len() - 1;
+        for i in 0..3 {
+            self.edges
+                .insert((corners[i], corners[(i + 1) % 3]), triangle);
+        }
+        triangle
+    }
+
+    /// the corner of the triangle which is not part of the edge
+    pub(crate) fn third(&self, triangle: usize, a: usize, b: usize) -> usize {
+        self.triangles[triangle]
+            .into_iter()
+            .find(|p| *p != a && *p != b)
+            .expect("Degenerated triangle")
+    }
+
+    /// distance of the point left of the line from a to b
+    pub(crate) fn side(&self, a: usize, b: usize, p: StaticPoint2d) -> f64 {
+        let (a, b) = (self.points[a], self.points[b]);
+        let direction = b - a;
+        let offset = p - a;
+        (direction.x * offset.y - direction.y * offset.x).0 / direction.magnitude().0
+    }
+
+    /// positive if `d` lies inside the circumcircle of the counter-clockwise triangle
+    fn in_circle(&self, a: usize, b: usize, c: usize, d: usize) -> f64 {
+        let d = self.points[d];
+        let [(ax, ay), (bx, by), (cx, cy)] = [a, b, c].map(|p| {
+            let p = self.points[p];
+            ((p.x - d.x).0, (p.y - d.y).0)
+        });
+        (ax * ax + ay * ay) * (bx * cy - cx * by)
+            + (bx * bx + by * by) * (cx * ay - ax * cy)
+            + (cx * cx + cy * cy) * (ax * by - bx * ay)
+    }
+
+    fn classify(&self, triangle: usize, p: StaticPoint2d) -> Location {
+        let corners = self.triangles[triangle];
+        if let Some(vertex) = corners
+            .into_iter()
+            .find(|v| (self.points[*v] - p).magnitude().0 <= self.tolerance)
+        {
+            return Location::Vertex(vertex);
+        }
+        (0..3)
+            .find(|i| self.side(corners[*i], corners[(i + 1) % 3], p).abs() <= self.tolerance)
+            .map_or(Location::Inside(triangle), |i| {
+                Location::OnEdge(triangle, i)
+            })
+    }
+
+    pub(crate) fn locate(&self, p: StaticPoint2d) -> Option<Location> {
+        self.find_triangle(p).map(|t| self.classify(t, p))
+    }
+
+    /// a triangle containing the point on its inside or border
+    fn find_triangle(&self, p: StaticPoint2d) -> Option<usize> {
+        let inside = |triangle: usize| {
+            let corners = self.triangles[triangle];
+            (0..3).find(|i| self.side(corners[*i], corners[(i + 1) % 3], p) < -self.tolerance)
+        };
+        // walk towards the point, constrained meshes may let the walk run in circles
+        let mut triangle = self.last.min(self.triangles.len() - 1);
+        for _ in 0..self.triangles.len() {
+            match inside(triangle) {
+                None => return Some(triangle),
+                Some(i) => {
+                    let corners = self.triangles[triangle];
+                    match self.edges.get(&(corners[(i + 1) % 3], corners[i])) {
+                        Some(next) => triangle = *next,
+                        None => return None,
+                    }
+                }
+            }
+        }
+        (0..self.triangles.len()).find(|t| inside(*t).is_none())
+    }
+
+    /// adds a point to the mesh and returns its vertex, which is an existing one for duplicates
+    pub(crate) fn add_point(&mut self, p: StaticPoint2d) -> Result<usize, TriangulationFailed> {
+        self.points.push(p);
+        let idx = self.points.len() - 1;
+        let vertex = self.insert(idx)?;
+        self.alias.push(vertex);
+        Ok(vertex)
+    }
+
+    fn insert(&mut self, idx: usize) -> Result<usize, TriangulationFailed> {
+        let p = self.points[idx];
+        let location = self
+            .locate(p)
+            .ok_or_else(|| TriangulationFailed(format!("Point {p:?} not found in mesh")))?;
+        let mut stack = match location {
+            Location::Vertex(vertex) => return Ok(vertex),
+            Location::Inside(triangle) => {
+                let [a, b, c] = self.triangles[triangle];
+                self.set(triangle, [a, b, idx]);
+                self.push([b, c, idx]);
+                self.push([c, a, idx]);
+                self.last = triangle;
+                vec![(a, b), (b, c), (c, a)]
+            }
+            Location::OnEdge(triangle, i) => {
+                let corners = self.triangles[triangle];
+                let (a, b, c) = (corners[i], corners[(i + 1) % 3], corners[(i + 2) % 3]);
+                let other = self.edges.get(&(b, a)).copied();
+                self.set(triangle, [a, idx, c]);
+                self.push([idx, b, c]);
+                self.last = triangle;
+                let mut stack = vec![(b, c), (c, a)];
+                if let Some(other) = other {
+                    let d = self.third(other, b, a);
+                    self.set(other, [b, idx, d]);
+                    self.push([idx, a, d]);
+                    stack.extend([(a, d), (d, b)]);
+                }
+                if self.constraints.remove(&edge_key(a, b)) {
+                    self.constraints.insert(edge_key(a, idx));
+                    self.constraints.insert(edge_key(idx, b));
+                }
+                stack
+            }
+        };
+        // flip the edges opposite of the new point until all are locally Delaunay
+        while let Some((u, v)) = stack.pop() {
+            if self.constraints.contains(&edge_key(u, v)) {
+                continue;
+            }
+            let Some(other) = self.edges.get(&(v, u)).copied() else {
+                continue;
+            };
+            let w = self.third(other, v, u);
+            if self.in_circle(u, v, idx, w) > 0.0 {
+                self.flip(u, v);
+                stack.extend([(u, w), (w, v)]);
+            }
+        }
+        Ok(idx)
+    }
+
+    /// replaces the edge by the other diagonal of the surrounding quad
+    fn flip(&mut self, u: usize, v: usize) -> (usize, usize) {
+        let first = self.edges[&(u, v)];
+        let second = self.edges[&(v, u)];
+        let c = self.third(first, u, v);
+        let d = self.third(second, v, u);
+        self.set(first, [c, u, d]);
+        self.set(second, [d, v, c]);
+        (c, d)
+    }
+
+    fn crosses(&self, a: usize, b: usize, u: usize, v: usize) -> bool {
+        let (pa, pb, pu, pv) = (
+            self.points[a],
+            self.points[b],
+            self.points[u],
+            self.points[v],
+        );
+        let tolerance = self.tolerance;
+        let opposite =
+            |x: f64, y: f64| (x > tolerance && y < -tolerance) || (x < -tolerance && y > tolerance);
+        opposite(self.side(a, b, pu), self.side(a, b, pv))
+            && opposite(self.side(u, v, pa), self.side(u, v, pb))
+    }
+
+    fn is_delaunay(&self, u: usize, v: usize) -> bool {
+        match (self.edges.get(&(u, v)), self.edges.get(&(v, u))) {
+            (Some(first), Some(second)) => {
+                let c = self.third(*first, u, v);
+                let d = self.third(*second, v, u);
+                self.in_circle(u, v, c, d) <= 0.0
+            }
+            _ => true,
+        }
+    }
+
+    /// Makes the segment between the two points an edge of the mesh. Vertices on the segment
+    /// split it, crossed constrained edges are split at the crossing point.
+    pub(crate) fn insert_constraint(
+        &mut self,
+        a: usize,
+        b: usize,
+    ) -> Result<(), TriangulationFailed> {
+        let (a, b) = (self.alias[a], self.alias[b]);
+        if a == b {
+            return Ok(());
+        }
+        if self.edges.contains_key(&(a, b)) || self.edges.contains_key(&(b, a)) {
+            self.constraints.insert(edge_key(a, b));
+            return Ok(());
+        }
+        let (pa, pb) = (self.points[a], self.points[b]);
+        let mut crossing: VecDeque<(usize, usize)> = match self.walk_segment(a, b)? {
+            SegmentWalk::Vertex(v) => {
+                self.insert_constraint(a, v)?;
+                return self.insert_constraint(v, b);
+            }
+            SegmentWalk::Crossed(edges) => edges.into(),
+            SegmentWalk::Constraint(u, v) => {
+                // two constraints cross each other, both are split at the crossing point
+                let (pu, pv) = (self.points[u], self.points[v]);
+                let along = self.side(u, v, pa) / (self.side(u, v, pa) - self.side(u, v, pb));
+                let crossing_point = pa + (pb - pa) * Number::from(along);
+                let vertex = self.add_point(crossing_point)?;
+                if vertex == a || vertex == b || vertex == u || vertex == v {
+                    return Err(TriangulationFailed(format!(
+                    "Constraints {pa:?}-{pb:?} and {pu:?}-{pv:?} cross too close to an end point"
+                )));
+                }
+                self.insert_constraint(a, vertex)?;
+                return self.insert_constraint(vertex, b);
+            }
+        };
+
+        // flip the crossed edges away, convex quads first
+        let mut new_edges = Vec::new();
+        let mut remaining_attempts = (crossing.len() + 1) * (crossing.len() + 1) * 4;
+        while let Some((u, v)) = crossing.pop_front() {
+            remaining_attempts = remaining_attempts.checked_sub(1).ok_or_else(|| {
+                TriangulationFailed(format!("Constraint {pa:?}-{pb:?} could not be inserted"))
+            })?;
+            let c = self.third(self.edges[&(u, v)], u, v);
+            let d = self.third(self.edges[&(v, u)], v, u);
+            if self.crosses(c, d, u, v) {
+                let (c, d) = self.flip(u, v);
+                if self.crosses(a, b, c, d) {
+                    crossing.push_back((c, d));
+                } else {
+                    new_edges.push((c, d));
+                }
+            } else {
+                crossing.push_back((u, v));
+            }
+        }
+        self.constraints.insert(edge_key(a, b));
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for edge in new_edges.iter_mut() {
+                let (u, v) = *edge;
+                if edge_key(u, v) != edge_key(a, b) && !self.is_delaunay(u, v) {
+                    *edge = self.flip(u, v);
+                    changed = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Walks from `a` through the triangles along the segment to `b` and collects the crossed
+    /// edges, stops at the first vertex on the segment or the first crossed constraint.
+    fn walk_segment(&self, a: usize, b: usize) -> Result<SegmentWalk, TriangulationFailed> {
+        let (pa, pb) = (self.points[a], self.points[b]);
+        let not_found = || TriangulationFailed(format!("Segment {pa:?}-{pb:?} not found in mesh"));
+        let ahead = |p: usize| (self.points[p] - pa).dot(pb - pa).0 > 0.0;
+        let start = self
+            .find_triangle(pa)
+            .filter(|t| self.triangles[*t].contains(&a))
+            .ok_or_else(not_found)?;
+
+        // turn around a until the opposite edge crosses the segment
+        let mut triangle = start;
+        let mut first_edge = None;
+        for _ in 0..self.triangles.len() {
+            let corners = self.triangles[triangle];
+            let i = corners.iter().position(|p| *p == a).ok_or_else(not_found)?;
+            let (u, v) = (corners[(i + 1) % 3], corners[(i + 2) % 3]);
+            let side_u = self.side(a, b, self.points[u]);
+            if side_u.abs() <= self.tolerance && ahead(u) {
+                return Ok(SegmentWalk::Vertex(u));
+            }
+            if side_u < -self.tolerance && self.side(a, b, self.points[v]) > self.tolerance {
+                first_edge = Some((u, v));
+                break;
+            }
+            triangle = *self.edges.get(&(a, v)).ok_or_else(not_found)?;
+            if triangle == start {
+                break;
+            }
+        }
+
+        // u stays right and v left of the segment
+        let (mut u, mut v) = first_edge.ok_or_else(not_found)?;
+        let mut crossed = Vec::new();
+        for _ in 0..self.triangles.len() {
+            if self.constraints.contains(&edge_key(u, v)) {
+                return Ok(SegmentWalk::Constraint(u, v));
+            }
+            crossed.push((u, v));
+            let next = *self.edges.get(&(v, u)).ok_or_else(not_found)?;
+            let w = self.third(next, v, u);
+            if w == b {
+                return Ok(SegmentWalk::Crossed(crossed));
+            }
+            let side_w = self.side(a, b, self.points[w]);
+            if side_w.abs() <= self.tolerance {
+                return Ok(SegmentWalk::Vertex(w));
+            }
+            if side_w > 0.0 {
+                v = w;
+            } else {
+                u = w;
+            }
+        }
+        Err(not_found())
+    }
+
+    /// Triangles which are not part of the enclosing triangle. With `by_constraints` only the
+    /// triangles which are inside an odd number of constrained rings count.
+    pub(crate) fn inside(&self, by_constraints: bool) -> Vec<bool> {
+        let mut depth = vec![None; self.triangles.len()];
+        let mut queue = VecDeque::new();
+        for (idx, corners) in self.triangles.iter().enumerate() {
+            if corners.iter().any(|p| self.is_super_vertex(*p)) {
+                depth[idx] = Some(0);
+                queue.push_back(idx);
+            }
+        }
+        if by_constraints {
+            while let Some(triangle) = queue.pop_front() {
+                let corners = self.triangles[triangle];
+                let current = depth[triangle].expect("Queued triangles have a depth");
+                for i in 0..3 {
+                    let (u, v) = (corners[i], corners[(i + 1) % 3]);
+                    let Some(next) = self.edges.get(&(v, u)).copied() else {
+                        continue;
+                    };
+                    if depth[next].is_some() {
+                        continue;
+                    }
+                    if self.constraints.contains(&edge_key(u, v)) {
+                        depth[next] = Some(current + 1);
+                        queue.push_back(next);
+                    } else {
+                        depth[next] = Some(current);
+                        queue.push_front(next);
+                    }
+                }
+            }
+        }
+        self.triangles
+            .iter()
+            .zip(depth)
+            .map(|(corners, depth)| {
+                !corners.iter().any(|p| self.is_super_vertex(*p))
+                    && (!by_constraints || depth.unwrap_or(0) % 2 == 1)
+            })
+            .collect()
+    }
+
+    /// removes the enclosing triangle and all triangles which are not [`Mesh::inside`]
+    pub(crate) fn finish(self, by_constraints: bool) -> DelaunayTriangulation {
+        let first_super = self.input_count;
+        let new_idx = |p: usize| if p > first_super { p - 3 } else { p };
+        let triangles = self
+            .triangles
+            .iter()
+            .zip(self.inside(by_constraints))
+            .filter(|(_, inside)| *inside)
+            .map(|(corners, _)| corners.map(new_idx))
+            .collect();
+        let mut points = self.points;
+        points.drain(first_super..first_super + 3);
+        DelaunayTriangulation { points, triangles }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, SeedableRng};
+
+    use crate::geometry2d::delaunay::constrained_delaunay;
+    use crate::geometry2d::point::StaticPoint2d;
+    use crate::geometry2d::polygon::Polygon2d;
+    use crate::prelude::Triangle2d;
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<StaticPoint2d> {
+        vec![
+            (x, y).into(),
+            (x + size, y).into(),
+            (x + size, y + size).into(),
+            (x, y + size).into(),
+        ]
+    }
+
+    fn circumcircle_is_empty(triangle: [StaticPoint2d; 3], points: &[StaticPoint2d]) -> bool {
+        let [a, b, c] = triangle.map(|p| (p.x.0, p.y.0));
+        let d = 2.0 * (a.0 * (b.1 - c.1) + b.0 * (c.1 - a.1) + c.0 * (a.1 - b.1));
+        let square = |p: (f64, f64)| p.0 * p.0 + p.1 * p.1;
+        let center = (
+            (square(a) * (b.1 - c.1) + square(b) * (c.1 - a.1) + square(c) * (a.1 - b.1)) / d,
+            (square(a) * (c.0 - b.0) + square(b) * (a.0 - c.0) + square(c) * (b.0 - a.0)) / d,
+        );
+        let radius = (a.0 - center.0).hypot(a.1 - center.1);
+        points
+            .iter()
+            .all(|p| (p.x.0 - center.0).hypot(p.y.0 - center.1) >= radius - 1e-9)
+    }
+
+    #[test]
+    fn test_point_cloud() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(40);
+        let points: Vec<StaticPoint2d> = (0..300)
+            .map(|_| (rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0)).into())
+            .collect();
+        let triangulation =
+            constrained_delaunay::<StaticPoint2d>(&[], &points).expect("Triangulation failed");
+        // n points with h on the hull give 2n - 2 - h triangles
+        let hull = crate::geometry2d::hull::convex_hull_2d(points.iter().copied());
+        assert_eq!(2 * 300 - 2 - hull.len(), triangulation.triangles().len());
+        for triangle in triangulation.static_triangles() {
+            assert!(triangle.area().0 > 0.0);
+            assert!(circumcircle_is_empty(
+                [triangle.p1, triangle.p2, triangle.p3],
+                &points
+            ));
+        }
+    }
+
+    #[test]
+    fn test_polygon_with_hole() {
+        let outer = square(0.0, 0.0, 4.0);
+        let hole = square(1.0, 1.0, 2.0);
+        let steiner: Vec<StaticPoint2d> = vec![(0.5, 0.5).into(), (2.0, 2.0).into()];
+        let triangulation =
+            constrained_delaunay(&[outer.clone(), hole.clone()], &steiner).expect("Failed");
+        assert_eq!(10, triangulation.points().len());
+        let area: f64 = triangulation.static_triangles().map(|t| t.area().0).sum();
+        assert!((area - 12.0).abs() < 1e-9);
+        // the Steiner point in the hole is dropped, the other one is used
+        let used = |idx: usize| {
+            triangulation
+                .triangles()
+                .iter()
+                .flatten()
+                .any(|p| *p == idx)
+        };
+        assert!(used(8));
+        assert!(!used(9));
+        // every ring edge is kept
+        for (start, ring) in [(0, &outer), (4, &hole)] {
+            for idx in 0..ring.len() {
+                let (a, b) = (start + idx, start + (idx + 1) % ring.len());
+                assert!(triangulation
+                    .triangles()
+                    .iter()
+                    .any(|t| t.contains(&a) && t.contains(&b)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_constraints_against_delaunay() {
+        // a thin comb which the unconstrained triangulation would cut through
+        let comb: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (10.0, 0.0).into(),
+            (10.0, 3.0).into(),
+            (9.0, 3.0).into(),
+            (9.0, 0.2).into(),
+            (5.5, 0.2).into(),
+            (5.5, 3.0).into(),
+            (4.5, 3.0).into(),
+            (4.5, 0.2).into(),
+            (1.0, 0.2).into(),
+            (1.0, 3.0).into(),
+            (0.0, 3.0).into(),
+        ];
+        let triangulation =
+            constrained_delaunay(std::slice::from_ref(&comb), &[]).expect("Triangulation failed");
+        assert_eq!(comb.len() - 2, triangulation.triangles().len());
+        let area: f64 = triangulation.static_triangles().map(|t| t.area().0).sum();
+        assert!((area - comb.signed_area().0).abs() < 1e-9);
+
+        // crossing rings are split where they cross
+        let crossing = constrained_delaunay(&[square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0)], &[])
+            .expect("Triangulation failed");
+        assert_eq!(10, crossing.points().len());
+        let area: f64 = crossing.static_triangles().map(|t| t.area().0).sum();
+        assert!((area - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_points_on_constraints() {
+        // Steiner points on the ring split its sides
+        let steiner: Vec<StaticPoint2d> =
+            vec![(2.0, 0.0).into(), (4.0, 3.0).into(), (2.0, 2.0).into()];
+        let triangulation =
+            constrained_delaunay(&[square(0.0, 0.0, 4.0)], &steiner).expect("Failed");
+        assert_eq!(6, triangulation.triangles().len());
+        let area: f64 = triangulation.static_triangles().map(|t| t.area().0).sum();
+        assert!((area - 16.0).abs() < 1e-9);
+        for triangle in triangulation.static_triangles() {
+            assert!(triangle.area().0 > 0.0);
+        }
+    }
+}
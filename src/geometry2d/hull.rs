@@ -0,0 +1,92 @@
+use num_traits::Zero;
+
+use crate::geometry2d::point::Point2d;
+use crate::primitives::Number;
+
+/// Counter-clockwise convex hull by the monotone chain algorithm, starting at the lowest left
+/// point. Points on the hull edges are left out, collinear or single points give a hull with
+/// less than three corners.
+pub fn convex_hull_2d<P: Point2d, I: IntoIterator<Item = P>>(points: I) -> Vec<P> {
+    let mut points: Vec<_> = points.into_iter().collect();
+    points.sort_by_key(|p| {
+        let c = p.coordinates();
+        (c.x, c.y)
+    });
+    points.dedup_by_key(|p| p.coordinates());
+    if points.len() < 3 {
+        return points;
+    }
+    let turns_left = |a: &P, b: &P, c: &P| {
+        let (a, b, c) = (a.coordinates(), b.coordinates(), c.coordinates());
+        (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x) > Number::zero()
+    };
+    let mut hull: Vec<P> = Vec::with_capacity(points.len() + 1);
+    // lower chain from left to right, then upper chain back
+    for candidates in [
+        points.iter().collect::<Vec<_>>(),
+        points.iter().rev().collect(),
+    ] {
+        let chain_start = hull.len();
+        for p in candidates {
+            while hull.len() >= chain_start + 2
+                && !turns_left(&hull[hull.len() - 2], &hull[hull.len() - 1], p)
+            {
+                hull.pop();
+            }
+            hull.push(p.clone());
+        }
+        // the last point is the first one of the next chain
+        hull.pop();
+    }
+    hull
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry2d::hull::convex_hull_2d;
+    use crate::geometry2d::point::StaticPoint2d;
+    use crate::geometry2d::polygon::cut::PointPolygonRelationship;
+    use crate::geometry2d::polygon::{Orientation, Polygon2d};
+
+    #[test]
+    fn test_hull() {
+        let mut points: Vec<StaticPoint2d> = Vec::new();
+        for x in 0..5 {
+            for y in 0..4 {
+                points.push((x as f64, y as f64).into());
+            }
+        }
+        points.push((2.0, 5.0).into());
+        let hull = convex_hull_2d(points.iter().copied());
+        let expected: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (4.0, 0.0).into(),
+            (4.0, 3.0).into(),
+            (2.0, 5.0).into(),
+            (0.0, 3.0).into(),
+        ];
+        assert_eq!(expected, hull);
+        assert_eq!(Orientation::CounterClockwise, hull.orientation());
+        assert!(hull.is_convex());
+        for p in points.iter() {
+            assert_ne!(PointPolygonRelationship::Outside, hull.point_position(p));
+        }
+    }
+
+    #[test]
+    fn test_degenerated() {
+        let line: Vec<StaticPoint2d> =
+            vec![(0.0, 0.0).into(), (2.0, 2.0).into(), (1.0, 1.0).into()];
+        let hull = convex_hull_2d(line);
+        assert_eq!(
+            vec![
+                StaticPoint2d::from((0.0, 0.0)),
+                StaticPoint2d::from((2.0, 2.0))
+            ],
+            hull
+        );
+        let single = convex_hull_2d([StaticPoint2d::from((1.0, 1.0)); 3]);
+        assert_eq!(1, single.len());
+        assert!(convex_hull_2d(Vec::<StaticPoint2d>::new()).is_empty());
+    }
+}
@@ -3,7 +3,7 @@ use std::{
     fmt::{Debug, Formatter},
 };
 
-use num_traits::Zero;
+use num_traits::{One, Zero};
 use ordered_float::OrderedFloat;
 
 use crate::geometry2d::vector::Vector2d;
@@ -56,7 +56,7 @@ pub trait Line2d<Pt: Point2d>: Sized + Debug {
                 LineIntersection::None
             }
         } else {
-            LineIntersection::None
+            collinear_intersection(self, other)
         }
     }
     fn y_cross_side<Pt2: Point2d>(&self, p: &Pt2) -> HitSide {
@@ -89,6 +89,42 @@ pub trait Line2d<Pt: Point2d>: Sized + Debug {
         f.write_fmt(format_args!("({:?})-({:?})", line.p1(), line.p2()))
     }*/
 }
+fn collinear_intersection<Pt: Point2d, L1: Line2d<Pt>, Pt2: Point2d, L2: Line2d<Pt2>>(
+    line: &L1,
+    other: &L2,
+) -> LineIntersection {
+    let p1 = line.p1().coordinates();
+    let v1 = line.direction();
+    let length = v1.dot(v1);
+    if length == 0.0 || other.p1() == other.p2() || line.side_of_pt(other.p1()) != SideOfLine::Hit {
+        return LineIntersection::None;
+    }
+    let along = |p: StaticPoint2d| (p - p1).dot(v1) / length;
+    let start = along(other.p1().coordinates());
+    let end = along(other.p2().coordinates());
+    let low = start.min(end).max(Number::zero());
+    let high = start.max(end).min(Number::one());
+    let other_pos = |pos: Number| {
+        if pos == start {
+            Number::zero()
+        } else if pos == end {
+            Number::one()
+        } else {
+            (pos - start) / (end - start)
+        }
+    };
+    match low.cmp(&high) {
+        Ordering::Greater => LineIntersection::None,
+        Ordering::Equal => LineIntersection::Point {
+            my_pos: low,
+            other_pos: other_pos(low),
+        },
+        Ordering::Less => LineIntersection::Overlap {
+            my_range: (low, high),
+            other_range: (other_pos(low), other_pos(high)),
+        },
+    }
+}
 #[inline]
 fn same_height_as_endpoint_cases(own_x_compare: Ordering, other_y_compare: Ordering) -> HitSide {
     match (own_x_compare, other_y_compare) {
@@ -112,17 +148,39 @@ pub enum HitSide {
     RightBottom,
 }
 
+#[derive(Debug, PartialEq, Copy, Clone)]
 pub enum LineIntersection {
-    Point { my_pos: Number, other_pos: Number },
+    Point {
+        my_pos: Number,
+        other_pos: Number,
+    },
+    /// collinear lines sharing a part, `my_range` is ascending and `other_range` holds the
+    /// positions of the same two points on the other line
+    Overlap {
+        my_range: (Number, Number),
+        other_range: (Number, Number),
+    },
     None,
 }
 
+impl LineIntersection {
+    /// the crossing point or both ends of the overlap as pairs of (my_pos, other_pos)
+    pub fn positions(&self) -> Vec<(Number, Number)> {
+        match self {
+            LineIntersection::Point { my_pos, other_pos } => vec![(*my_pos, *other_pos)],
+            LineIntersection::Overlap {
+                my_range,
+                other_range,
+            } => vec![(my_range.0, other_range.0), (my_range.1, other_range.1)],
+            LineIntersection::None => Vec::new(),
+        }
+    }
+}
+
+/// the crossing point, or the start of an overlap on the first line
 impl From<LineIntersection> for Option<(Number, Number)> {
     fn from(val: LineIntersection) -> Self {
-        match val {
-            LineIntersection::Point { my_pos, other_pos } => Some((my_pos, other_pos)),
-            LineIntersection::None => None,
-        }
+        val.positions().first().copied()
     }
 }
 
@@ -205,8 +263,45 @@ impl<Pt: Point2d> Line2d<Pt> for (&Pt, &Pt) {
 
 #[cfg(test)]
 mod test {
-    use crate::geometry2d::line::{HitSide, Line2d, StaticLine2d};
+    use crate::geometry2d::line::{HitSide, Line2d, LineIntersection, StaticLine2d};
     use crate::prelude::StaticPoint2d;
+    use crate::primitives::Number;
+
+    #[test]
+    fn test_intersect_collinear() {
+        let line = StaticLine2d::<StaticPoint2d>::new((0.0, 0.0).into(), (4.0, 0.0).into());
+        let reversed = StaticLine2d::<StaticPoint2d>::new((5.0, 0.0).into(), (1.0, 0.0).into());
+        assert_eq!(
+            LineIntersection::Overlap {
+                my_range: (0.25.into(), 1.0.into()),
+                other_range: (1.0.into(), 0.25.into())
+            },
+            line.intersect(&reversed)
+        );
+        assert_eq!(
+            LineIntersection::Overlap {
+                my_range: (0.25.into(), 1.0.into()),
+                other_range: (1.0.into(), 0.25.into())
+            },
+            reversed.intersect(&line)
+        );
+        let touching = StaticLine2d::<StaticPoint2d>::new((4.0, 0.0).into(), (6.0, 0.0).into());
+        assert_eq!(
+            LineIntersection::Point {
+                my_pos: 1.0.into(),
+                other_pos: 0.0.into()
+            },
+            line.intersect(&touching)
+        );
+        let apart = StaticLine2d::<StaticPoint2d>::new((5.0, 0.0).into(), (6.0, 0.0).into());
+        assert_eq!(LineIntersection::None, line.intersect(&apart));
+        let first: Option<(Number, Number)> = line.intersect(&reversed).into();
+        assert_eq!(Some((0.25.into(), 1.0.into())), first);
+        let first: Option<(Number, Number)> = line.intersect(&apart).into();
+        assert_eq!(None, first);
+        let parallel = StaticLine2d::<StaticPoint2d>::new((0.0, 1.0).into(), (4.0, 1.0).into());
+        assert_eq!(LineIntersection::None, line.intersect(&parallel));
+    }
 
     #[test]
     fn test_point_line_positive() {
@@ -1,6 +1,11 @@
+pub mod delaunay;
+pub mod hull;
 pub mod line;
 pub mod point;
 pub mod polygon;
+pub mod refine;
+pub mod rtree;
+pub mod sweep;
 #[cfg(test)]
 mod test;
 pub mod triangle;
@@ -46,6 +46,25 @@ impl BoundingBox2dValues {
             max_y,
         }
     }
+    pub fn contains(&self, p: &StaticPoint2d) -> bool {
+        self.min_x <= p.x && p.x <= self.max_x && self.min_y <= p.y && p.y <= self.max_y
+    }
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min_x <= other.max_x
+            && other.min_x <= self.max_x
+            && self.min_y <= other.max_y
+            && other.min_y <= self.max_y
+    }
+    /// squared distance from the point to the box, zero inside
+    pub fn distance_squared(&self, p: &StaticPoint2d) -> Number {
+        let dx = (self.min_x - p.x)
+            .max(p.x - self.max_x)
+            .max(Number::from(0.0));
+        let dy = (self.min_y - p.y)
+            .max(p.y - self.max_y)
+            .max(Number::from(0.0));
+        dx * dx + dy * dy
+    }
     pub fn expand(&self, s: Number) -> Self {
         let expand_horizontal = self.width() * s / 2.0;
         let expand_vertical = self.height() * s / 2.0;
@@ -15,11 +15,11 @@ pub struct StaticPoint2d {
     pub y: Number,
 }
 
-impl Into<Vector2d> for StaticPoint2d {
-    fn into(self) -> Vector2d {
+impl From<StaticPoint2d> for Vector2d {
+    fn from(value: StaticPoint2d) -> Self {
         Vector2d {
-            x: self.x,
-            y: self.y,
+            x: value.x,
+            y: value.y,
         }
     }
 }
@@ -0,0 +1,384 @@
+use std::collections::{BTreeMap, HashSet};
+
+use num_traits::{One, Zero};
+
+use crate::geometry2d::line::{Line2d, SideOfLine};
+use crate::geometry2d::point::{Point2d, StaticPoint2d};
+use crate::geometry2d::polygon::cut::PointPolygonRelationship;
+use crate::geometry2d::polygon::Polygon2d;
+use crate::primitives::Number;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BooleanOperation {
+    Union,
+    Intersection,
+    /// the first polygon without the second one
+    Difference,
+    Xor,
+}
+
+type PointKey = (Number, Number);
+
+/// Clips two simple polygons against each other.
+///
+/// Both outlines are split at all their crossings, every piece is classified against the other
+/// polygon and the selected pieces are linked to closed rings again. Outer rings of the result
+/// are counter-clockwise, holes are clockwise.
+pub fn boolean_operation<P1, P2, A, B>(
+    first: &A,
+    second: &B,
+    operation: BooleanOperation,
+) -> Vec<Vec<StaticPoint2d>>
+where
+    P1: Point2d,
+    P2: Point2d,
+    A: Polygon2d<P1>,
+    B: Polygon2d<P2>,
+{
+    let first = normalized_ring(first);
+    let second = normalized_ring(second);
+    if first.len() < 3 || second.len() < 3 {
+        // one side is empty
+        let remaining = match operation {
+            BooleanOperation::Intersection => vec![],
+            BooleanOperation::Difference => vec![first],
+            BooleanOperation::Union | BooleanOperation::Xor => vec![first, second],
+        };
+        return remaining.into_iter().filter(|r| r.len() >= 3).collect();
+    }
+    let (first_edges, second_edges) = split_edges(&first, &second);
+    let first_set: HashSet<_> = first_edges.iter().map(|(s, e)| (key(s), key(e))).collect();
+    let second_set: HashSet<_> = second_edges.iter().map(|(s, e)| (key(s), key(e))).collect();
+
+    let mut selected = Vec::new();
+    for (start, end) in first_edges.iter() {
+        match classify(start, end, &second, &second_set) {
+            EdgeClass::Outside => match operation {
+                BooleanOperation::Union | BooleanOperation::Difference | BooleanOperation::Xor => {
+                    selected.push((*start, *end))
+                }
+                BooleanOperation::Intersection => {}
+            },
+            EdgeClass::Inside => match operation {
+                BooleanOperation::Intersection => selected.push((*start, *end)),
+                BooleanOperation::Xor => selected.push((*end, *start)),
+                BooleanOperation::Union | BooleanOperation::Difference => {}
+            },
+            EdgeClass::SharedSameDirection => match operation {
+                BooleanOperation::Union | BooleanOperation::Intersection => {
+                    selected.push((*start, *end))
+                }
+                BooleanOperation::Difference | BooleanOperation::Xor => {}
+            },
+            EdgeClass::SharedOppositeDirection => match operation {
+                BooleanOperation::Difference => selected.push((*start, *end)),
+                BooleanOperation::Union
+                | BooleanOperation::Intersection
+                | BooleanOperation::Xor => {}
+            },
+        }
+    }
+    for (start, end) in second_edges.iter() {
+        // shared edges are already decided by the first polygon
+        match classify(start, end, &first, &first_set) {
+            EdgeClass::Outside => match operation {
+                BooleanOperation::Union | BooleanOperation::Xor => selected.push((*start, *end)),
+                BooleanOperation::Intersection | BooleanOperation::Difference => {}
+            },
+            EdgeClass::Inside => match operation {
+                BooleanOperation::Intersection => selected.push((*start, *end)),
+                BooleanOperation::Difference | BooleanOperation::Xor => {
+                    selected.push((*end, *start))
+                }
+                BooleanOperation::Union => {}
+            },
+            EdgeClass::SharedSameDirection | EdgeClass::SharedOppositeDirection => {}
+        }
+    }
+    link_rings(selected)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum EdgeClass {
+    Inside,
+    Outside,
+    SharedSameDirection,
+    SharedOppositeDirection,
+}
+
+fn key(p: &StaticPoint2d) -> PointKey {
+    (p.x, p.y)
+}
+
+fn classify(
+    start: &StaticPoint2d,
+    end: &StaticPoint2d,
+    other: &Vec<StaticPoint2d>,
+    other_edges: &HashSet<(PointKey, PointKey)>,
+) -> EdgeClass {
+    if other_edges.contains(&(key(start), key(end))) {
+        return EdgeClass::SharedSameDirection;
+    }
+    if other_edges.contains(&(key(end), key(start))) {
+        return EdgeClass::SharedOppositeDirection;
+    }
+    let middle = (start, end).pt_along(0.5.into());
+    match other.point_position(&middle) {
+        PointPolygonRelationship::Inside => EdgeClass::Inside,
+        // touching without sharing the exact edge is a rounding artefact
+        PointPolygonRelationship::Outside | PointPolygonRelationship::OnEdge => EdgeClass::Outside,
+    }
+}
+
+/// counter-clockwise ring without repeated points
+pub(crate) fn normalized_ring<P: Point2d, Poly: Polygon2d<P>>(
+    polygon: &Poly,
+) -> Vec<StaticPoint2d> {
+    let mut ring: Vec<_> = polygon.points().map(|p| p.coordinates()).collect();
+    ring.dedup();
+    while ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    if ring.signed_area() < Number::zero() {
+        ring.reverse();
+    }
+    ring
+}
+
+pub(crate) type Edges = Vec<(StaticPoint2d, StaticPoint2d)>;
+
+/// splits the edges of both rings at every point where they touch each other
+fn split_edges(first: &[StaticPoint2d], second: &[StaticPoint2d]) -> (Edges, Edges) {
+    let mut first_splits = vec![Vec::new(); first.len()];
+    let mut second_splits = vec![Vec::new(); second.len()];
+    for (i, a1) in first.iter().enumerate() {
+        let a2 = &first[(i + 1) % first.len()];
+        let line_a = (a1, a2);
+        for (j, b1) in second.iter().enumerate() {
+            let b2 = &second[(j + 1) % second.len()];
+            let line_b = (b1, b2);
+            // collinear edges are split at both ends of their overlap
+            for (my_pos, other_pos) in line_a.intersect(&line_b).positions() {
+                // reuse existing corners, so both sides get exactly the same point
+                let point = if other_pos == Number::zero() {
+                    *b1
+                } else if other_pos == Number::one() {
+                    *b2
+                } else if my_pos == Number::zero() {
+                    *a1
+                } else if my_pos == Number::one() {
+                    *a2
+                } else {
+                    line_a.pt_along(my_pos)
+                };
+                if my_pos > Number::zero() && my_pos < Number::one() {
+                    first_splits[i].push((my_pos, point));
+                }
+                if other_pos > Number::zero() && other_pos < Number::one() {
+                    second_splits[j].push((other_pos, point));
+                }
+            }
+        }
+    }
+    (
+        collect_edges(first, first_splits),
+        collect_edges(second, second_splits),
+    )
+}
+
+fn collect_edges(ring: &[StaticPoint2d], mut splits: Vec<Vec<(Number, StaticPoint2d)>>) -> Edges {
+    let mut edges = Vec::with_capacity(ring.len());
+    for (idx, start) in ring.iter().enumerate() {
+        let end = ring[(idx + 1) % ring.len()];
+        let edge_splits = &mut splits[idx];
+        edge_splits.sort_by_key(|(t, _)| *t);
+        let mut last = *start;
+        for (_, p) in edge_splits.iter().chain([(Number::zero(), end)].iter()) {
+            if *p != last {
+                edges.push((last, *p));
+                last = *p;
+            }
+        }
+    }
+    edges
+}
+
+/// Links directed edges to closed rings. Where several edges leave a point, the one with the
+/// sharpest left turn is taken, so rings touching at a point are kept apart.
+pub(crate) fn link_rings(edges: Edges) -> Vec<Vec<StaticPoint2d>> {
+    let mut outgoing = BTreeMap::<PointKey, Vec<usize>>::new();
+    for (idx, (start, _)) in edges.iter().enumerate() {
+        outgoing.entry(key(start)).or_default().push(idx);
+    }
+    let mut used = vec![false; edges.len()];
+    let mut rings = Vec::new();
+    for first_edge in 0..edges.len() {
+        if used[first_edge] {
+            continue;
+        }
+        used[first_edge] = true;
+        let ring_start = edges[first_edge].0;
+        let mut ring = vec![ring_start];
+        let mut current = first_edge;
+        let mut closed = false;
+        loop {
+            let (start, end) = edges[current];
+            if end == ring_start {
+                closed = true;
+                break;
+            }
+            let incoming = end - start;
+            let next = outgoing
+                .get(&key(&end))
+                .into_iter()
+                .flatten()
+                .filter(|idx| !used[**idx])
+                .max_by(|a, b| {
+                    let turn = |idx: usize| {
+                        if edges[idx].1 == start {
+                            // going back is the last option
+                            return f64::NEG_INFINITY;
+                        }
+                        let outgoing = edges[idx].1 - edges[idx].0;
+                        let cross = incoming.x * outgoing.y - incoming.y * outgoing.x;
+                        let dot = incoming.dot(outgoing);
+                        cross.0.atan2(dot.0)
+                    };
+                    turn(**a).total_cmp(&turn(**b))
+                })
+                .copied();
+            let Some(next) = next else {
+                break;
+            };
+            used[next] = true;
+            ring.push(end);
+            current = next;
+        }
+        if closed {
+            remove_collinear_points(&mut ring);
+            if ring.len() >= 3 && ring.signed_area() != Number::zero() {
+                rings.push(ring);
+            }
+        }
+    }
+    rings
+}
+
+fn remove_collinear_points(ring: &mut Vec<StaticPoint2d>) {
+    let mut idx = 0;
+    while ring.len() >= 3 && idx < ring.len() {
+        let previous = ring[(idx + ring.len() - 1) % ring.len()];
+        let next = ring[(idx + 1) % ring.len()];
+        if (&previous, &next).side_of_pt(&ring[idx]) == SideOfLine::Hit {
+            ring.remove(idx);
+            idx = idx.saturating_sub(1);
+        } else {
+            idx += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry2d::point::StaticPoint2d;
+    use crate::geometry2d::polygon::boolean::BooleanOperation;
+    use crate::geometry2d::polygon::Polygon2d;
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<StaticPoint2d> {
+        vec![
+            (x, y).into(),
+            (x + size, y).into(),
+            (x + size, y + size).into(),
+            (x, y + size).into(),
+        ]
+    }
+
+    fn areas(rings: &[Vec<StaticPoint2d>]) -> Vec<f64> {
+        let mut areas: Vec<_> = rings.iter().map(|r| r.signed_area().0).collect();
+        areas.sort_by(|a, b| b.total_cmp(a));
+        areas
+    }
+
+    #[test]
+    fn test_overlapping_squares() {
+        let a = square(0.0, 0.0, 2.0);
+        let b = square(1.0, 1.0, 2.0);
+        assert_eq!(vec![7.0], areas(&a.union(&b)));
+        assert_eq!(8, a.union(&b)[0].len());
+        assert_eq!(vec![1.0], areas(&a.intersection(&b)));
+        assert_eq!(vec![3.0], areas(&a.difference(&b)));
+        assert_eq!(vec![3.0, 3.0], areas(&a.xor(&b)));
+    }
+
+    #[test]
+    fn test_hole_and_disjoint() {
+        let outer = square(0.0, 0.0, 4.0);
+        let inner = square(1.0, 1.0, 1.0);
+        assert_eq!(vec![16.0, -1.0], areas(&outer.difference(&inner)));
+        assert!(inner.difference(&outer).is_empty());
+        assert_eq!(vec![16.0], areas(&outer.union(&inner)));
+        assert_eq!(vec![1.0], areas(&inner.intersection(&outer)));
+
+        let far = square(10.0, 0.0, 1.0);
+        assert_eq!(vec![16.0, 1.0], areas(&outer.union(&far)));
+        assert!(outer.intersection(&far).is_empty());
+        assert_eq!(vec![16.0], areas(&outer.difference(&far)));
+    }
+
+    #[test]
+    fn test_shared_edges() {
+        let left = square(0.0, 0.0, 1.0);
+        let right = square(1.0, 0.0, 1.0);
+        let union = left.union(&right);
+        assert_eq!(vec![2.0], areas(&union));
+        assert_eq!(4, union[0].len());
+        assert!(left.intersection(&right).is_empty());
+        assert_eq!(vec![1.0], areas(&left.difference(&right)));
+
+        // clockwise input, partly shared edge
+        let mut shifted = square(1.0, 0.5, 1.0);
+        shifted.reverse();
+        assert_eq!(vec![2.0], areas(&left.union(&shifted)));
+        assert_eq!(
+            vec![1.0],
+            areas(&left.boolean(&shifted, BooleanOperation::Difference))
+        );
+
+        assert_eq!(vec![1.0], areas(&left.union(&left)));
+        assert!(left.difference(&left).is_empty());
+        assert!(left.xor(&left).is_empty());
+    }
+
+    #[test]
+    fn test_concave() {
+        let u_shape: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (3.0, 0.0).into(),
+            (3.0, 3.0).into(),
+            (2.0, 3.0).into(),
+            (2.0, 1.0).into(),
+            (1.0, 1.0).into(),
+            (1.0, 3.0).into(),
+            (0.0, 3.0).into(),
+        ];
+        let bar: Vec<StaticPoint2d> = vec![
+            (-1.0, 2.0).into(),
+            (4.0, 2.0).into(),
+            (4.0, 2.5).into(),
+            (-1.0, 2.5).into(),
+        ];
+        assert_eq!(vec![0.5, 0.5], areas(&u_shape.intersection(&bar)));
+        // the bar closes the gap of the U
+        assert_eq!(vec![9.5, -1.0], areas(&u_shape.union(&bar)));
+        assert_eq!(vec![5.0, 0.5, 0.5], areas(&u_shape.difference(&bar)));
+    }
+
+    #[test]
+    fn test_touching_corner() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(1.0, 1.0, 1.0);
+        let union = a.union(&b);
+        assert_eq!(vec![1.0, 1.0], areas(&union));
+        assert!(union.iter().all(|r| r.len() == 4));
+    }
+}
@@ -5,6 +5,10 @@ use crate::primitives::Number;
 pub enum PolygonPath {
     Enclosed,
     CutSegments(Vec<CutSegment>),
+    /// the polygon touches a corner or runs along a side of the triangle
+    Touching,
+    /// the polygon intersects itself and cannot cut, the triangle stays as it is
+    NotSimple,
     None,
 }
 
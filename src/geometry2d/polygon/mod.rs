@@ -1,13 +1,20 @@
+use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::slice::Iter;
 
+use num_traits::Zero;
+
 use crate::geometry2d::line::{HitSide, Line2d, ReferenceLine2d};
+use crate::geometry2d::point::StaticPoint2d;
+use crate::geometry2d::polygon::boolean::{boolean_operation, BooleanOperation};
 use crate::geometry2d::polygon::cut::{PointPolygonRelationship, PointRange, PointRangeIterator};
-use crate::geometry2d::polygon::AnyPolygonPointIter::StaticTrianglePolygon;
+use crate::geometry2d::polygon::offset::{offset_polygon, JoinType};
+use crate::geometry2d::sweep::{segment_intersections, SegmentIntersection};
 use crate::geometry2d::triangle::static_triangle::StaticTriangle2d;
 use crate::geometry2d::triangle::TrianglePointIterator;
-use crate::prelude::{BoundingBox2d, Point2d, Triangle2d};
+use crate::prelude::{BoundingBox2d, Point2d};
+use crate::primitives::{Float, Number};
 
 pub trait Polygon2d<P: Point2d>: Sized + Clone + PartialEq + Debug {
     type PointIter<'a>: Iterator<Item = &'a P> + Clone
@@ -16,7 +23,7 @@ pub trait Polygon2d<P: Point2d>: Sized + Clone + PartialEq + Debug {
         P: 'a;
     fn points(&self) -> Self::PointIter<'_>;
     fn point_count(&self) -> usize;
-    fn points_of_range(&self, range: &PointRange) -> PolygonRangeIterator<Self, P> {
+    fn points_of_range(&self, range: &PointRange) -> PolygonRangeIterator<'_, Self, P> {
         PolygonRangeIterator {
             phantom: Default::default(),
             polygon: self,
@@ -24,7 +31,7 @@ pub trait Polygon2d<P: Point2d>: Sized + Clone + PartialEq + Debug {
         }
     }
     fn get_point(&self, idx: usize) -> Option<&P>;
-    fn lines(&self) -> PolygonLineIterator<Self::PointIter<'_>, P> {
+    fn lines(&self) -> PolygonLineIterator<'_, Self::PointIter<'_>, P> {
         PolygonLineIterator::new(self.points())
     }
     fn point_position<Pt: Point2d>(&self, p: &Pt) -> PointPolygonRelationship {
@@ -55,6 +62,164 @@ pub trait Polygon2d<P: Point2d>: Sized + Clone + PartialEq + Debug {
         }
     }
 
+    /// counts how often the outline turns counter-clockwise around the point
+    fn winding_number<Pt: Point2d>(&self, p: &Pt) -> i32 {
+        let point = p.coordinates();
+        let mut winding = 0;
+        for line in self.lines() {
+            let start = line.p1().coordinates();
+            let end = line.p2().coordinates();
+            let side =
+                (end.x - start.x) * (point.y - start.y) - (point.x - start.x) * (end.y - start.y);
+            if start.y <= point.y {
+                if end.y > point.y && side > Number::zero() {
+                    winding += 1;
+                }
+            } else if end.y <= point.y && side < Number::zero() {
+                winding -= 1;
+            }
+        }
+        winding
+    }
+    /// like [`Polygon2d::point_position`], but self-intersecting outlines follow the fill rule
+    fn point_position_with_rule<Pt: Point2d>(
+        &self,
+        p: &Pt,
+        fill_rule: FillRule,
+    ) -> PointPolygonRelationship {
+        let position = self.point_position(p);
+        match fill_rule {
+            FillRule::NonZero if position != PointPolygonRelationship::OnEdge => {
+                if self.winding_number(p) != 0 {
+                    PointPolygonRelationship::Inside
+                } else {
+                    PointPolygonRelationship::Outside
+                }
+            }
+            FillRule::EvenOdd | FillRule::NonZero => position,
+        }
+    }
+
+    /// positive for counter-clockwise outlines
+    fn signed_area(&self) -> Number {
+        let mut area = Number::zero();
+        for line in self.lines() {
+            let p1 = line.p1().coordinates();
+            let p2 = line.p2().coordinates();
+            area += p1.x * p2.y - p2.x * p1.y;
+        }
+        area / 2.0
+    }
+    fn orientation(&self) -> Orientation {
+        match self.signed_area().cmp(&Number::zero()) {
+            Ordering::Less => Orientation::Clockwise,
+            Ordering::Equal => Orientation::Degenerated,
+            Ordering::Greater => Orientation::CounterClockwise,
+        }
+    }
+    /// the same outline in the opposite direction
+    fn reversed(&self) -> Vec<P> {
+        let mut points: Vec<_> = self.points().cloned().collect();
+        points.reverse();
+        points
+    }
+    /// center of the area, the average of the points for degenerated polygons
+    fn centroid(&self) -> StaticPoint2d {
+        let area = self.signed_area();
+        if area == Number::zero() {
+            let count = Number::from(self.point_count().max(1) as Float);
+            let (x, y) = self
+                .points()
+                .fold((Number::zero(), Number::zero()), |(x, y), p| {
+                    let p = p.coordinates();
+                    (x + p.x, y + p.y)
+                });
+            return StaticPoint2d {
+                x: x / count,
+                y: y / count,
+            };
+        }
+        let (mut x, mut y) = (Number::zero(), Number::zero());
+        for line in self.lines() {
+            let p1 = line.p1().coordinates();
+            let p2 = line.p2().coordinates();
+            let cross = p1.x * p2.y - p2.x * p1.y;
+            x += (p1.x + p2.x) * cross;
+            y += (p1.y + p2.y) * cross;
+        }
+        StaticPoint2d {
+            x: x / (area * 6.0),
+            y: y / (area * 6.0),
+        }
+    }
+    fn perimeter(&self) -> Number {
+        self.lines()
+            .map(|line| Number::from(line.direction().magnitude().0))
+            .sum()
+    }
+    /// All corners turn the same way and the outline goes around only once, straight corners are
+    /// allowed.
+    fn is_convex(&self) -> bool {
+        if self.point_count() < 3 {
+            return false;
+        }
+        let directions: Vec<_> = self.lines().map(|line| line.direction()).collect();
+        let mut total_turn = 0.0;
+        let mut turn_sign = Number::zero();
+        for (idx, incoming) in directions.iter().enumerate() {
+            let outgoing = directions[(idx + 1) % directions.len()];
+            let cross = incoming.x * outgoing.y - incoming.y * outgoing.x;
+            let dot = incoming.dot(outgoing);
+            if cross == Number::zero() {
+                if dot <= Number::zero() {
+                    // spike or repeated point
+                    return false;
+                }
+                continue;
+            }
+            if turn_sign != Number::zero()
+                && (cross > Number::zero()) != (turn_sign > Number::zero())
+            {
+                return false;
+            }
+            turn_sign = cross;
+            total_turn += cross.0.atan2(dot.0);
+        }
+        (total_turn.abs() - std::f64::consts::TAU).abs() < 1e-6
+    }
+    /// No edge touches another one except its neighbors at the shared corners
+    fn is_simple(&self) -> bool {
+        self.point_count() >= 3
+            && self.lines().all(|line| line.p1() != line.p2())
+            && self.self_intersections().is_empty()
+    }
+    /// Intersections between the sides, see [`segment_intersections`]. Side `i` runs from point
+    /// `i` to the next one, neighbors meeting at their shared corner are not reported.
+    fn self_intersections(&self) -> Vec<SegmentIntersection> {
+        let lines: Vec<_> = self.lines().collect();
+        let count = lines.len();
+        segment_intersections(&lines)
+            .into_iter()
+            .filter(|intersection| match intersection {
+                SegmentIntersection::Point {
+                    first,
+                    second,
+                    point,
+                } => {
+                    let corner = if *second == first + 1 {
+                        lines[*second].p1()
+                    } else if *first == 0 && *second == count - 1 {
+                        lines[*first].p1()
+                    } else {
+                        return true;
+                    };
+                    corner.coordinates() != *point
+                }
+                SegmentIntersection::Overlap { .. } => true,
+            })
+            .collect()
+    }
+
     fn bbox(&self) -> BoundingBox2d {
         let mut ret: BoundingBox2d = Default::default();
         for p in self.points() {
@@ -62,6 +227,55 @@ pub trait Polygon2d<P: Point2d>: Sized + Clone + PartialEq + Debug {
         }
         ret
     }
+
+    /// See [`boolean_operation`], outer rings are counter-clockwise and holes clockwise
+    fn boolean<P2: Point2d, Other: Polygon2d<P2>>(
+        &self,
+        other: &Other,
+        operation: BooleanOperation,
+    ) -> Vec<Vec<StaticPoint2d>> {
+        boolean_operation(self, other, operation)
+    }
+    fn union<P2: Point2d, Other: Polygon2d<P2>>(&self, other: &Other) -> Vec<Vec<StaticPoint2d>> {
+        self.boolean(other, BooleanOperation::Union)
+    }
+    fn intersection<P2: Point2d, Other: Polygon2d<P2>>(
+        &self,
+        other: &Other,
+    ) -> Vec<Vec<StaticPoint2d>> {
+        self.boolean(other, BooleanOperation::Intersection)
+    }
+    fn difference<P2: Point2d, Other: Polygon2d<P2>>(
+        &self,
+        other: &Other,
+    ) -> Vec<Vec<StaticPoint2d>> {
+        self.boolean(other, BooleanOperation::Difference)
+    }
+    fn xor<P2: Point2d, Other: Polygon2d<P2>>(&self, other: &Other) -> Vec<Vec<StaticPoint2d>> {
+        self.boolean(other, BooleanOperation::Xor)
+    }
+    /// See [`offset_polygon`], positive distances grow the polygon
+    fn offset(&self, distance: Number, join_type: JoinType) -> Vec<Vec<StaticPoint2d>> {
+        offset_polygon(self, distance, join_type)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    /// the outline has no area
+    Degenerated,
+}
+
+/// Decides which parts of overlapping or nested outlines are filled
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum FillRule {
+    /// inside if a ray from the point crosses the outlines an odd number of times
+    #[default]
+    EvenOdd,
+    /// inside if the outlines wind around the point at all
+    NonZero,
 }
 
 pub struct PolygonRangeIterator<'a, Poly: Polygon2d<Pt>, Pt: Point2d> {
@@ -135,10 +349,16 @@ impl<'a, I: Iterator<Item = &'a Pt> + Clone, Pt: Point2d> PolygonLineIterator<'a
     }
 }
 
+pub mod boolean;
 pub mod cut;
+pub mod offset;
+pub mod with_holes;
 
 impl<Pt: Point2d> Polygon2d<Pt> for Vec<Pt> {
-    type PointIter<'a>    = Iter<'a, Pt> where Self: 'a;
+    type PointIter<'a>
+        = Iter<'a, Pt>
+    where
+        Self: 'a;
 
     fn points(&self) -> Self::PointIter<'_> {
         self.iter()
@@ -197,7 +417,139 @@ impl<'a, Pt: Point2d> Iterator for AnyPolygonPointIter<'a, Pt> {
 mod test {
     use crate::geometry2d::point::StaticPoint2d;
     use crate::geometry2d::polygon::cut::{PointPolygonRelationship, PointRange};
-    use crate::geometry2d::polygon::Polygon2d;
+    use crate::geometry2d::polygon::{Orientation, Polygon2d};
+    use crate::geometry2d::sweep::SegmentIntersection;
+
+    fn l_shape() -> Vec<StaticPoint2d> {
+        vec![
+            (0.0, 0.0).into(),
+            (2.0, 0.0).into(),
+            (2.0, 1.0).into(),
+            (1.0, 1.0).into(),
+            (1.0, 2.0).into(),
+            (0.0, 2.0).into(),
+        ]
+    }
+
+    #[test]
+    fn test_properties() {
+        let polygon = l_shape();
+        assert_eq!(3.0, polygon.signed_area().0);
+        assert_eq!(Orientation::CounterClockwise, polygon.orientation());
+        let reversed = polygon.reversed();
+        assert_eq!(-3.0, reversed.signed_area().0);
+        assert_eq!(Orientation::Clockwise, reversed.orientation());
+        assert_eq!(8.0, polygon.perimeter().0);
+        // unit squares at (0.5,0.5), (1.5,0.5) and (0.5,1.5)
+        let centroid = polygon.centroid();
+        assert!((centroid.x.0 - 2.5 / 3.0).abs() < 1e-12);
+        assert!((centroid.y.0 - 2.5 / 3.0).abs() < 1e-12);
+        assert_eq!(centroid, reversed.centroid());
+
+        let line: Vec<StaticPoint2d> =
+            vec![(0.0, 0.0).into(), (2.0, 0.0).into(), (1.0, 0.0).into()];
+        assert_eq!(Orientation::Degenerated, line.orientation());
+        assert_eq!(StaticPoint2d::from((1.0, 0.0)), line.centroid());
+    }
+
+    #[test]
+    fn test_convex_and_simple() {
+        let polygon = l_shape();
+        assert!(!polygon.is_convex());
+        assert!(polygon.is_simple());
+
+        let square: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (1.0, 0.0).into(),
+            (2.0, 0.0).into(),
+            (2.0, 2.0).into(),
+            (0.0, 2.0).into(),
+        ];
+        assert!(square.is_convex());
+        assert!(square.reversed().is_convex());
+        assert!(square.is_simple());
+
+        let bow_tie: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (1.0, 1.0).into(),
+            (1.0, 0.0).into(),
+            (0.0, 1.0).into(),
+        ];
+        assert!(!bow_tie.is_convex());
+        assert!(!bow_tie.is_simple());
+
+        // a pentagram turns the same way at every corner, but twice around
+        let star: Vec<StaticPoint2d> = (0..5)
+            .map(|i| {
+                let angle = std::f64::consts::TAU * (i * 2) as f64 / 5.0;
+                (angle.cos(), angle.sin()).into()
+            })
+            .collect();
+        assert!(!star.is_convex());
+        assert!(!star.is_simple());
+
+        // touches itself at a corner
+        let touching: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (2.0, 0.0).into(),
+            (1.0, 1.0).into(),
+            (2.0, 2.0).into(),
+            (0.0, 2.0).into(),
+            (1.0, 1.0).into(),
+        ];
+        assert!(!touching.is_simple());
+
+        // runs back on itself
+        let spike: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (2.0, 0.0).into(),
+            (1.0, 0.0).into(),
+            (1.0, 1.0).into(),
+        ];
+        assert!(!spike.is_simple());
+        assert!(!spike.is_convex());
+    }
+
+    #[test]
+    fn test_self_intersections() {
+        assert!(l_shape().self_intersections().is_empty());
+        let bow_tie: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (1.0, 1.0).into(),
+            (1.0, 0.0).into(),
+            (0.0, 1.0).into(),
+        ];
+        assert_eq!(
+            vec![SegmentIntersection::Point {
+                first: 0,
+                second: 2,
+                point: (0.5, 0.5).into()
+            }],
+            bow_tie.self_intersections()
+        );
+        let spike: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (2.0, 0.0).into(),
+            (1.0, 0.0).into(),
+            (1.0, 1.0).into(),
+        ];
+        assert_eq!(
+            vec![
+                SegmentIntersection::Overlap {
+                    first: 0,
+                    second: 1,
+                    start: (1.0, 0.0).into(),
+                    end: (2.0, 0.0).into()
+                },
+                SegmentIntersection::Point {
+                    first: 0,
+                    second: 2,
+                    point: (1.0, 0.0).into()
+                }
+            ],
+            spike.self_intersections()
+        );
+    }
 
     #[test]
     fn test_point_range() {
@@ -0,0 +1,299 @@
+use num_traits::{One, Zero};
+
+use crate::geometry2d::line::Line2d;
+use crate::geometry2d::point::{Point2d, StaticPoint2d};
+use crate::geometry2d::polygon::boolean::{link_rings, normalized_ring, Edges};
+use crate::geometry2d::polygon::Polygon2d;
+use crate::geometry2d::sweep::{segment_intersections, SegmentIntersection};
+use crate::geometry2d::vector::Vector2d;
+use crate::primitives::Number;
+
+/// How the offset edges are connected at convex corners
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum JoinType {
+    /// sharp corners, falls back to a square join if the tip is longer than `limit` times the
+    /// offset distance
+    Miter { limit: Number },
+    /// arcs around the corner which differ at most `tolerance` from the exact circle, a full
+    /// circle has at most [`MAX_ARC_STEPS`] segments even for a non-positive tolerance
+    Round { tolerance: Number },
+    /// corners cut off at the offset distance
+    Square,
+}
+
+/// segments of a full circle drawn by [`JoinType::Round`]
+pub const MAX_ARC_STEPS: usize = 1024;
+
+/// Moves the outline of a simple polygon by `distance`, positive values grow the polygon and
+/// negative values shrink it.
+///
+/// The raw offset outline is split where it crosses itself, only the parts with a winding number
+/// of one on their inner side are kept. The result may therefore contain several polygons, outer
+/// rings are counter-clockwise and holes clockwise.
+pub fn offset_polygon<P: Point2d, Poly: Polygon2d<P>>(
+    polygon: &Poly,
+    distance: Number,
+    join_type: JoinType,
+) -> Vec<Vec<StaticPoint2d>> {
+    let ring = normalized_ring(polygon);
+    if ring.len() < 3 {
+        return vec![];
+    }
+    if distance == Number::zero() {
+        return vec![ring];
+    }
+    let raw = raw_offset(&ring, distance, join_type);
+    let edges = split_self_intersections(&raw);
+    let size = raw
+        .iter()
+        .map(|p| p.x.abs().max(p.y.abs()))
+        .fold(0.0, f64::max);
+    let nudge = Number::from(size * 1e-9);
+
+    let kept = edges
+        .into_iter()
+        .filter(|(start, end)| {
+            let direction = *end - *start;
+            let left = Vector2d::new(-direction.y, direction.x) / direction.magnitude();
+            let probe = (start, end).pt_along(0.5.into()) + left * nudge;
+            raw.winding_number(&probe) == 1
+        })
+        .collect();
+    link_rings(kept)
+}
+
+fn raw_offset(ring: &[StaticPoint2d], distance: Number, join_type: JoinType) -> Vec<StaticPoint2d> {
+    let count = ring.len();
+    let mut raw = Vec::with_capacity(count * 2);
+    for (idx, point) in ring.iter().enumerate() {
+        let previous = ring[(idx + count - 1) % count];
+        let next = ring[(idx + 1) % count];
+        let incoming = (*point - previous).normalized();
+        let outgoing = (next - *point).normalized();
+        // outside of a counter-clockwise ring is on the right
+        let normal_in = Vector2d::new(incoming.y, -incoming.x);
+        let normal_out = Vector2d::new(outgoing.y, -outgoing.x);
+        let start = *point + normal_in * distance;
+        let end = *point + normal_out * distance;
+        let turn = incoming.x * outgoing.y - incoming.y * outgoing.x;
+        let diverging = (turn > Number::zero()) == (distance > Number::zero());
+        if turn == Number::zero() && incoming.dot(outgoing) > Number::zero() {
+            raw.push(start);
+            continue;
+        }
+        if !diverging {
+            // the loop through the original corner is removed by the winding rule
+            raw.extend([start, *point, end]);
+            continue;
+        }
+        raw.push(start);
+        let cos = normal_in.dot(normal_out);
+        match join_type {
+            JoinType::Miter { limit }
+                if cos > -Number::one()
+                    && (Number::from(2.0) / (Number::one() + cos)).sqrt() <= limit.0 =>
+            {
+                raw.push(*point + (normal_in + normal_out) * (distance / (Number::one() + cos)));
+            }
+            JoinType::Round { tolerance } => {
+                let radius = distance.abs();
+                let tolerance = tolerance.0.max(0.0);
+                let step = if tolerance < radius {
+                    2.0 * (1.0 - tolerance / radius).acos()
+                } else {
+                    std::f64::consts::FRAC_PI_2
+                };
+                let step = step.max(std::f64::consts::TAU / MAX_ARC_STEPS as f64);
+                let from = normal_in.y.0.atan2(normal_in.x.0);
+                let mut sweep = normal_out.y.0.atan2(normal_out.x.0) - from;
+                // the arc turns the same way as the ring around the corner
+                if distance > Number::zero() {
+                    while sweep <= 0.0 {
+                        sweep += std::f64::consts::TAU;
+                    }
+                } else {
+                    while sweep >= 0.0 {
+                        sweep -= std::f64::consts::TAU;
+                    }
+                }
+                let steps = (sweep.abs() / step).ceil() as usize;
+                for i in 1..steps {
+                    let angle = from + sweep * i as f64 / steps as f64;
+                    let direction = Vector2d::new(angle.cos().into(), angle.sin().into());
+                    raw.push(*point + direction * Number::from(radius));
+                }
+            }
+            JoinType::Miter { .. } | JoinType::Square => {
+                let extend = Number::from(distance.abs());
+                raw.push(start + incoming * extend);
+                raw.push(end + outgoing * -extend);
+            }
+        }
+        raw.push(end);
+    }
+    raw.dedup();
+    raw
+}
+
+/// splits every edge where it crosses or overlaps another edge of the same ring
+fn split_self_intersections(ring: &[StaticPoint2d]) -> Edges {
+    let count = ring.len();
+    let lines: Vec<_> = (0..count)
+        .map(|idx| (&ring[idx], &ring[(idx + 1) % count]))
+        .collect();
+    let mut splits = vec![Vec::new(); count];
+    for intersection in segment_intersections(&lines) {
+        let (first, second) = intersection.segments();
+        let points = match intersection {
+            SegmentIntersection::Point { point, .. } => vec![point],
+            SegmentIntersection::Overlap { start, end, .. } => vec![start, end],
+        };
+        for edge in [first, second] {
+            let (start, end) = lines[edge];
+            let direction = *end - *start;
+            for point in points.iter() {
+                let position = (*point - *start).dot(direction) / direction.magnitude_squared();
+                if position > Number::zero() && position < Number::one() {
+                    splits[edge].push((position, *point));
+                }
+            }
+        }
+    }
+    let mut edges = Vec::with_capacity(count);
+    for (idx, start) in ring.iter().enumerate() {
+        let end = ring[(idx + 1) % count];
+        let edge_splits = &mut splits[idx];
+        edge_splits.sort_by_key(|(t, _)| *t);
+        let mut last = *start;
+        for p in edge_splits.iter().map(|(_, p)| *p).chain([end]) {
+            if p != last {
+                edges.push((last, p));
+                last = p;
+            }
+        }
+    }
+    edges
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::geometry2d::point::StaticPoint2d;
+    use crate::geometry2d::polygon::offset::{JoinType, MAX_ARC_STEPS};
+    use crate::geometry2d::polygon::Polygon2d;
+
+    fn square(size: f64) -> Vec<StaticPoint2d> {
+        vec![
+            (0.0, 0.0).into(),
+            (size, 0.0).into(),
+            (size, size).into(),
+            (0.0, size).into(),
+        ]
+    }
+
+    fn areas(rings: &[Vec<StaticPoint2d>]) -> Vec<f64> {
+        let mut areas: Vec<_> = rings.iter().map(|r| r.signed_area().0).collect();
+        areas.sort_by(|a, b| b.total_cmp(a));
+        areas
+    }
+
+    const MITER: JoinType = JoinType::Miter {
+        limit: ordered_float::OrderedFloat(2.0),
+    };
+
+    #[test]
+    fn test_square() {
+        let square = square(2.0);
+        let grown = square.offset(1.0.into(), MITER);
+        assert_eq!(vec![16.0], areas(&grown));
+        assert_eq!(4, grown[0].len());
+        assert_eq!(
+            vec![16.0],
+            areas(&square.offset(1.0.into(), JoinType::Square))
+        );
+
+        let round = square.offset(
+            1.0.into(),
+            JoinType::Round {
+                tolerance: 0.001.into(),
+            },
+        );
+        assert_eq!(1, round.len());
+        assert!((areas(&round)[0] - (12.0 + PI)).abs() < 0.01);
+
+        let shrunk = square.offset((-0.5).into(), MITER);
+        assert_eq!(vec![1.0], areas(&shrunk));
+        // the offset does not depend on the orientation of the input
+        let mut clockwise = square.clone();
+        clockwise.reverse();
+        assert_eq!(
+            vec![1.0],
+            areas(&clockwise.offset((-0.5).into(), JoinType::Square))
+        );
+        assert!(square.offset((-1.5).into(), MITER).is_empty());
+    }
+
+    #[test]
+    fn test_round_without_tolerance() {
+        let square = square(2.0);
+        for tolerance in [0.0, -1.0, 1e-300] {
+            let round = square.offset(
+                1.0.into(),
+                JoinType::Round {
+                    tolerance: tolerance.into(),
+                },
+            );
+            assert_eq!(1, round.len());
+            // four quarter circles with the finest arcs
+            assert_eq!(4 + MAX_ARC_STEPS, round[0].len());
+            assert!((areas(&round)[0] - (12.0 + PI)).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_sharp_corner() {
+        let triangle: Vec<StaticPoint2d> =
+            vec![(0.0, 0.0).into(), (10.0, 0.0).into(), (0.0, 1.0).into()];
+        let miter = areas(&triangle.offset(
+            0.5.into(),
+            JoinType::Miter {
+                limit: 100.0.into(),
+            },
+        ));
+        let limited = areas(&triangle.offset(0.5.into(), MITER));
+        let square = areas(&triangle.offset(0.5.into(), JoinType::Square));
+        assert_eq!(1, miter.len());
+        assert!(miter[0] > limited[0]);
+        // only the sharpest corner falls back to the square join
+        assert!(limited[0] > square[0]);
+    }
+
+    #[test]
+    fn test_split_by_inset() {
+        // two squares connected by a small bridge
+        let dumbbell: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (2.0, 0.0).into(),
+            (2.0, 0.75).into(),
+            (3.0, 0.75).into(),
+            (3.0, 0.0).into(),
+            (5.0, 0.0).into(),
+            (5.0, 2.0).into(),
+            (3.0, 2.0).into(),
+            (3.0, 1.25).into(),
+            (2.0, 1.25).into(),
+            (2.0, 2.0).into(),
+            (0.0, 2.0).into(),
+        ];
+        let shrunk = areas(&dumbbell.offset((-0.3).into(), MITER));
+        assert_eq!(2, shrunk.len());
+        for area in shrunk {
+            assert!((area - 1.4 * 1.4).abs() < 1e-9);
+        }
+        let grown = dumbbell.offset(0.3.into(), MITER);
+        assert_eq!(1, grown.len());
+        // the notches above and below the bridge get narrower
+        assert!((areas(&grown)[0] - (5.6 * 2.6 - 2.0 * 0.4 * 0.75)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,263 @@
+use thiserror::Error;
+use triangulate::{formats, ListFormat, PolygonList};
+
+use crate::geometry2d::delaunay::{constrained_delaunay, DelaunayTriangulation};
+use crate::geometry2d::point::bounding_box::BoundingBox2d;
+use crate::geometry2d::point::{Point2d, StaticPoint2d};
+use crate::geometry2d::polygon::cut::PointPolygonRelationship;
+use crate::geometry2d::polygon::{FillRule, Orientation, Polygon2d};
+use crate::geometry2d::refine::{refine_delaunay, RefinementOptions};
+use crate::geometry2d::triangle::static_triangle::StaticTriangle2d;
+use crate::primitives::Number;
+
+/// An outer ring with any number of hole rings inside
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolygonWithHoles<P: Point2d> {
+    outer: Vec<P>,
+    holes: Vec<Vec<P>>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Triangulation failed: {0}")]
+pub struct TriangulationFailed(pub(crate) String);
+
+impl<P: Point2d> PolygonWithHoles<P> {
+    pub fn new(outer: Vec<P>, holes: Vec<Vec<P>>) -> Self {
+        Self { outer, holes }
+    }
+    pub fn outer(&self) -> &Vec<P> {
+        &self.outer
+    }
+    pub fn holes(&self) -> &[Vec<P>] {
+        &self.holes
+    }
+    pub fn rings(&self) -> impl Iterator<Item = &Vec<P>> {
+        [&self.outer].into_iter().chain(self.holes.iter())
+    }
+
+    /// The rings are combined by the fill rule, so with [`FillRule::NonZero`] the holes have to
+    /// run the opposite way of the outer ring.
+    pub fn point_position<Pt: Point2d>(
+        &self,
+        p: &Pt,
+        fill_rule: FillRule,
+    ) -> PointPolygonRelationship {
+        let mut inside_count = 0;
+        let mut winding = 0;
+        for ring in self.rings() {
+            match ring.point_position(p) {
+                PointPolygonRelationship::OnEdge => return PointPolygonRelationship::OnEdge,
+                PointPolygonRelationship::Inside => inside_count += 1,
+                PointPolygonRelationship::Outside => {}
+            }
+            if fill_rule == FillRule::NonZero {
+                winding += ring.winding_number(p);
+            }
+        }
+        let inside = match fill_rule {
+            FillRule::EvenOdd => inside_count % 2 == 1,
+            FillRule::NonZero => winding != 0,
+        };
+        if inside {
+            PointPolygonRelationship::Inside
+        } else {
+            PointPolygonRelationship::Outside
+        }
+    }
+
+    /// area of the outer ring without the holes, independent of the ring orientation
+    pub fn area(&self) -> Number {
+        let area = |ring: &Vec<P>| Number::from(ring.signed_area().abs());
+        self.holes
+            .iter()
+            .fold(area(&self.outer), |sum, hole| sum - area(hole))
+    }
+
+    pub fn bbox(&self) -> BoundingBox2d {
+        self.outer.bbox()
+    }
+
+    /// counter-clockwise triangles covering the polygon without the holes
+    pub fn triangulate(&self) -> Result<Vec<StaticTriangle2d<P>>, TriangulationFailed> {
+        let rings: Vec<Vec<StaticPoint2d>> = self
+            .rings()
+            .map(|ring| ring.iter().map(|p| p.coordinates()).collect())
+            .collect();
+        let mut indices = Vec::<[usize; 2]>::new();
+        rings
+            .triangulate(formats::IndexedListFormat::new(&mut indices).into_fan_format())
+            .map_err(|e| TriangulationFailed(e.to_string()))?;
+        let point = |[ring, idx]: [usize; 2]| {
+            if ring == 0 {
+                self.outer[idx].clone()
+            } else {
+                self.holes[ring - 1][idx].clone()
+            }
+        };
+        Ok(indices
+            .chunks_exact(3)
+            .map(|corners| {
+                let triangle = StaticTriangle2d {
+                    p1: point(corners[0]),
+                    p2: point(corners[1]),
+                    p3: point(corners[2]),
+                };
+                if triangle.signed_area() < Number::from(0.0) {
+                    StaticTriangle2d {
+                        p1: triangle.p1,
+                        p2: triangle.p3,
+                        p3: triangle.p2,
+                    }
+                } else {
+                    triangle
+                }
+            })
+            .collect())
+    }
+
+    /// Constrained Delaunay triangulation with additional points inside, see
+    /// [`constrained_delaunay`]
+    pub fn triangulate_delaunay(
+        &self,
+        steiner_points: &[StaticPoint2d],
+    ) -> Result<DelaunayTriangulation, TriangulationFailed> {
+        let rings: Vec<Vec<P>> = self.rings().cloned().collect();
+        constrained_delaunay(&rings, steiner_points)
+    }
+
+    /// Delaunay refinement for well shaped triangles, see [`refine_delaunay`]
+    pub fn refine(
+        &self,
+        options: &RefinementOptions,
+    ) -> Result<DelaunayTriangulation, TriangulationFailed> {
+        let rings: Vec<Vec<P>> = self.rings().cloned().collect();
+        refine_delaunay(&rings, options)
+    }
+}
+
+impl PolygonWithHoles<StaticPoint2d> {
+    /// Groups rings as returned by the boolean operations or the offset: counter-clockwise rings
+    /// are outer rings, clockwise rings become holes of the smallest outer ring around them.
+    pub fn from_rings(rings: Vec<Vec<StaticPoint2d>>) -> Vec<Self> {
+        let (outers, holes): (Vec<_>, Vec<_>) = rings
+            .into_iter()
+            .partition(|ring| ring.orientation() == Orientation::CounterClockwise);
+        let mut result: Vec<_> = outers.into_iter().map(|o| Self::new(o, vec![])).collect();
+        for hole in holes {
+            let parent = result
+                .iter_mut()
+                .filter(|polygon| {
+                    hole.iter().any(|p| {
+                        polygon.outer.point_position(p) == PointPolygonRelationship::Inside
+                    })
+                })
+                .min_by_key(|polygon| polygon.outer.signed_area());
+            if let Some(parent) = parent {
+                parent.holes.push(hole);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry2d::point::StaticPoint2d;
+    use crate::geometry2d::polygon::cut::PointPolygonRelationship;
+    use crate::geometry2d::polygon::with_holes::PolygonWithHoles;
+    use crate::geometry2d::polygon::{FillRule, Polygon2d};
+    use crate::prelude::Triangle2d;
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<StaticPoint2d> {
+        vec![
+            (x, y).into(),
+            (x + size, y).into(),
+            (x + size, y + size).into(),
+            (x, y + size).into(),
+        ]
+    }
+
+    fn reversed(mut ring: Vec<StaticPoint2d>) -> Vec<StaticPoint2d> {
+        ring.reverse();
+        ring
+    }
+
+    #[test]
+    fn test_containment() {
+        let frame =
+            PolygonWithHoles::new(square(0.0, 0.0, 4.0), vec![reversed(square(1.0, 1.0, 2.0))]);
+        for rule in [FillRule::EvenOdd, FillRule::NonZero] {
+            let position =
+                |x: f64, y: f64| frame.point_position::<StaticPoint2d>(&(x, y).into(), rule);
+            assert_eq!(PointPolygonRelationship::Inside, position(0.5, 0.5));
+            assert_eq!(PointPolygonRelationship::Outside, position(2.0, 2.0));
+            assert_eq!(PointPolygonRelationship::OnEdge, position(1.0, 2.0));
+            assert_eq!(PointPolygonRelationship::Outside, position(5.0, 2.0));
+        }
+        assert_eq!(12.0, frame.area().0);
+
+        // a hole in the same direction as the outer ring is filled again by the non-zero rule
+        let same_direction =
+            PolygonWithHoles::new(square(0.0, 0.0, 4.0), vec![square(1.0, 1.0, 2.0)]);
+        let center: StaticPoint2d = (2.0, 2.0).into();
+        assert_eq!(
+            PointPolygonRelationship::Outside,
+            same_direction.point_position(&center, FillRule::EvenOdd)
+        );
+        assert_eq!(
+            PointPolygonRelationship::Inside,
+            same_direction.point_position(&center, FillRule::NonZero)
+        );
+    }
+
+    #[test]
+    fn test_self_overlapping_ring() {
+        // the ring runs twice around the inner square
+        let ring: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (3.0, 0.0).into(),
+            (3.0, 3.0).into(),
+            (1.0, 3.0).into(),
+            (1.0, 1.0).into(),
+            (2.0, 1.0).into(),
+            (2.0, 2.0).into(),
+            (0.0, 2.0).into(),
+        ];
+        let point: StaticPoint2d = (1.5, 1.5).into();
+        assert_eq!(2, ring.winding_number(&point));
+        assert_eq!(
+            PointPolygonRelationship::Outside,
+            ring.point_position_with_rule(&point, FillRule::EvenOdd)
+        );
+        assert_eq!(
+            PointPolygonRelationship::Inside,
+            ring.point_position_with_rule(&point, FillRule::NonZero)
+        );
+    }
+
+    #[test]
+    fn test_triangulate_and_group() {
+        let rings = square(0.0, 0.0, 4.0).difference(&square(1.0, 1.0, 2.0));
+        let mut with_far = rings.clone();
+        with_far.push(square(10.0, 0.0, 1.0));
+        let polygons = PolygonWithHoles::from_rings(with_far);
+        assert_eq!(2, polygons.len());
+        let frame = polygons
+            .iter()
+            .find(|p| !p.holes().is_empty())
+            .expect("Missing hole");
+        assert_eq!(12.0, frame.area().0);
+
+        let triangles = frame.triangulate().expect("Triangulation failed");
+        assert_eq!(8, triangles.len());
+        let area: f64 = triangles.iter().map(|t| t.area().0).sum();
+        assert!((area - 12.0).abs() < 1e-9);
+        assert!(triangles.iter().all(|t| t.area().0 > 0.0));
+        let delaunay = frame
+            .triangulate_delaunay(&[(0.5, 2.0).into()])
+            .expect("Triangulation failed");
+        assert_eq!(10, delaunay.triangles().len());
+        let bbox = frame.bbox();
+        assert_eq!(frame.outer().bbox(), bbox);
+    }
+}
@@ -0,0 +1,293 @@
+use crate::geometry2d::delaunay::{edge_key, DelaunayTriangulation, Mesh};
+use crate::geometry2d::point::{Point2d, StaticPoint2d};
+use crate::geometry2d::polygon::with_holes::TriangulationFailed;
+use crate::primitives::Number;
+
+/// Quality constraints for [`refine_delaunay`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RefinementOptions {
+    min_angle: Number,
+    max_area: Option<Number>,
+    max_points: usize,
+}
+
+impl Default for RefinementOptions {
+    fn default() -> Self {
+        Self::new(20.0.into())
+    }
+}
+
+impl RefinementOptions {
+    /// Smallest angle in degrees, the refinement is only guaranteed to finish up to about 20
+    /// degrees and gets unstable above 33 degrees.
+    pub fn new(min_angle: Number) -> Self {
+        Self {
+            min_angle,
+            max_area: None,
+            max_points: 100_000,
+        }
+    }
+    pub fn with_max_area(self, max_area: Number) -> Self {
+        Self {
+            max_area: Some(max_area),
+            ..self
+        }
+    }
+    /// the refinement fails if it needs to add more points
+    pub fn with_max_points(self, max_points: usize) -> Self {
+        Self { max_points, ..self }
+    }
+    pub fn min_angle(&self) -> Number {
+        self.min_angle
+    }
+    pub fn max_area(&self) -> Option<Number> {
+        self.max_area
+    }
+    pub fn max_points(&self) -> usize {
+        self.max_points
+    }
+}
+
+/// Ruppert's Delaunay refinement of the area inside the rings by the even-odd rule.
+///
+/// Points are added at the circumcenters of triangles with a too small angle or a too large
+/// area. If such a point would come close to a ring edge, the edge is split in the middle
+/// instead. Small angles between two ring edges remain.
+pub fn refine_delaunay<P: Point2d>(
+    rings: &[Vec<P>],
+    options: &RefinementOptions,
+) -> Result<DelaunayTriangulation, TriangulationFailed> {
+    let points: Vec<StaticPoint2d> = rings.iter().flatten().map(|p| p.coordinates()).collect();
+    let mut mesh = Mesh::new(points)?;
+    let mut start = 0;
+    for ring in rings {
+        for idx in 0..ring.len() {
+            mesh.insert_constraint(start + idx, start + (idx + 1) % ring.len())?;
+        }
+        start += ring.len();
+    }
+    let point_limit = mesh.points.len() + options.max_points;
+    let mut refinement = Refinement {
+        mesh,
+        min_angle: options.min_angle.to_radians(),
+        max_area: options.max_area.map(|area| area.0),
+        point_limit,
+    };
+    refinement.run()?;
+    Ok(refinement.mesh.finish(true))
+}
+
+struct Refinement {
+    mesh: Mesh,
+    min_angle: f64,
+    max_area: Option<f64>,
+    point_limit: usize,
+}
+
+impl Refinement {
+    fn run(&mut self) -> Result<(), TriangulationFailed> {
+        loop {
+            self.split_encroached_segments()?;
+            let inside = self.mesh.inside(true);
+            let bad: Vec<[usize; 3]> = self
+                .mesh
+                .triangles
+                .iter()
+                .zip(inside)
+                .filter(|(corners, inside)| *inside && self.is_bad(**corners))
+                .map(|(corners, _)| *corners)
+                .collect();
+            if bad.is_empty() {
+                return Ok(());
+            }
+            for corners in bad {
+                // earlier insertions may have removed it already
+                if self.mesh.edges.get(&(corners[0], corners[1]))
+                    != self.mesh.edges.get(&(corners[1], corners[2]))
+                    || !self.mesh.edges.contains_key(&(corners[0], corners[1]))
+                {
+                    continue;
+                }
+                let center = self.circumcenter(corners);
+                let encroached: Vec<(usize, usize)> = self
+                    .mesh
+                    .constraints
+                    .iter()
+                    .filter(|(a, b)| self.encroaches(*a, *b, center))
+                    .copied()
+                    .collect();
+                if encroached.is_empty() {
+                    self.add_point(center)?;
+                } else {
+                    for (a, b) in encroached {
+                        self.split_segment(a, b)?;
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_point(&mut self, p: StaticPoint2d) -> Result<usize, TriangulationFailed> {
+        if self.mesh.points.len() >= self.point_limit {
+            return Err(TriangulationFailed(
+                "Refinement needs too many points".to_string(),
+            ));
+        }
+        self.mesh.add_point(p)
+    }
+
+    fn split_segment(&mut self, a: usize, b: usize) -> Result<(), TriangulationFailed> {
+        if !self.mesh.constraints.contains(&edge_key(a, b)) {
+            return Ok(());
+        }
+        let (pa, pb) = (self.mesh.points[a], self.mesh.points[b]);
+        self.add_point(pa + (pb - pa) * Number::from(0.5))?;
+        Ok(())
+    }
+
+    /// the point lies inside the circle with the segment as diameter
+    fn encroaches(&self, a: usize, b: usize, p: StaticPoint2d) -> bool {
+        let (pa, pb) = (self.mesh.points[a], self.mesh.points[b]);
+        let tolerance = self.mesh.tolerance;
+        (pa - p).magnitude().0 > tolerance
+            && (pb - p).magnitude().0 > tolerance
+            && (pa - p).dot(pb - p).0 < 0.0
+    }
+
+    /// splits segments until no vertex of a neighbor triangle lies in their diametral circle
+    fn split_encroached_segments(&mut self) -> Result<(), TriangulationFailed> {
+        loop {
+            let encroached: Vec<(usize, usize)> = self
+                .mesh
+                .constraints
+                .iter()
+                .filter(|(a, b)| {
+                    [(*a, *b), (*b, *a)].into_iter().any(|(u, v)| {
+                        self.mesh.edges.get(&(u, v)).is_some_and(|triangle| {
+                            let p = self.mesh.points[self.mesh.third(*triangle, u, v)];
+                            self.encroaches(*a, *b, p)
+                        })
+                    })
+                })
+                .copied()
+                .collect();
+            if encroached.is_empty() {
+                return Ok(());
+            }
+            for (a, b) in encroached {
+                self.split_segment(a, b)?;
+            }
+        }
+    }
+
+    fn is_bad(&self, corners: [usize; 3]) -> bool {
+        let [a, b, c] = corners.map(|p| self.mesh.points[p]);
+        let area = (b - a).x * (c - a).y - (b - a).y * (c - a).x;
+        if self
+            .max_area
+            .is_some_and(|max_area| area.0 / 2.0 > max_area)
+        {
+            return true;
+        }
+        // the smallest angle lies opposite of the shortest side
+        let lengths = [
+            (b - c).magnitude(),
+            (c - a).magnitude(),
+            (a - b).magnitude(),
+        ];
+        let (shortest, length) = lengths
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, length)| **length)
+            .expect("Three sides");
+        let angle = (area.0 / (lengths[(shortest + 1) % 3] * lengths[(shortest + 2) % 3]).0).asin();
+        if angle >= self.min_angle || length.0 <= self.mesh.tolerance {
+            return false;
+        }
+        // small angles between two input edges can not be removed
+        let corner = corners[shortest];
+        let others = [corners[(shortest + 1) % 3], corners[(shortest + 2) % 3]];
+        !others
+            .iter()
+            .all(|other| self.mesh.constraints.contains(&edge_key(corner, *other)))
+    }
+
+    fn circumcenter(&self, corners: [usize; 3]) -> StaticPoint2d {
+        let [a, b, c] = corners.map(|p| self.mesh.points[p]);
+        let (b, c) = (b - a, c - a);
+        let d = (b.x * c.y - b.y * c.x) * 2.0;
+        let (b_square, c_square) = (b.magnitude_squared(), c.magnitude_squared());
+        StaticPoint2d {
+            x: a.x + (c.y * b_square - b.y * c_square) / d,
+            y: a.y + (b.x * c_square - c.x * b_square) / d,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry2d::point::StaticPoint2d;
+    use crate::geometry2d::polygon::with_holes::PolygonWithHoles;
+    use crate::geometry2d::refine::{refine_delaunay, RefinementOptions};
+    use crate::prelude::{Triangle2d, TriangulationFailed};
+
+    fn smallest_angle(points: [StaticPoint2d; 3]) -> f64 {
+        let [a, b, c] = points;
+        [(a, b, c), (b, c, a), (c, a, b)]
+            .map(|(a, b, c)| {
+                let (u, v) = (b - a, c - a);
+                (u.dot(v).0 / (u.magnitude() * v.magnitude()).0)
+                    .acos()
+                    .to_degrees()
+            })
+            .into_iter()
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    #[test]
+    fn test_refine_thin_rectangle() {
+        let rectangle: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (10.0, 0.0).into(),
+            (10.0, 1.0).into(),
+            (0.0, 1.0).into(),
+        ];
+        let options = RefinementOptions::new(25.0.into()).with_max_area(0.5.into());
+        let refined = refine_delaunay(&[rectangle], &options).expect("Refinement failed");
+        let mut area = 0.0;
+        for triangle in refined.static_triangles() {
+            assert!(triangle.area().0 > 0.0);
+            assert!(triangle.area().0 <= 0.5 + 1e-9);
+            assert!(smallest_angle([triangle.p1, triangle.p2, triangle.p3]) >= 25.0 - 1e-6);
+            area += triangle.area().0;
+        }
+        assert!((area - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_refine_with_hole() {
+        let outer: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (4.0, 0.0).into(),
+            (4.0, 4.0).into(),
+            (0.0, 4.0).into(),
+        ];
+        // a sharp spike as hole keeps its small angle
+        let hole: Vec<StaticPoint2d> =
+            vec![(1.0, 1.0).into(), (3.0, 1.2).into(), (1.0, 1.4).into()];
+        let polygon = PolygonWithHoles::new(outer, vec![hole]);
+        let refined = polygon
+            .refine(&RefinementOptions::default())
+            .expect("Refinement failed");
+        let area: f64 = refined.static_triangles().map(|t| t.area().0).sum();
+        assert!((area - (16.0 - 0.4)).abs() < 1e-9);
+        let sharp = refined
+            .static_triangles()
+            .filter(|t| smallest_angle([t.p1, t.p2, t.p3]) < 20.0 - 1e-6)
+            .count();
+        assert_eq!(0, sharp);
+
+        let limited = polygon.refine(&RefinementOptions::new(20.0.into()).with_max_points(3));
+        assert!(matches!(limited, Err(TriangulationFailed(_))));
+    }
+}
@@ -0,0 +1,305 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::marker::PhantomData;
+
+use crate::geometry2d::point::bounding_box::BoundingBox2dValues;
+use crate::geometry2d::point::{Point2d, StaticPoint2d};
+use crate::geometry2d::triangle::Triangle2d;
+use crate::primitives::Number;
+use num_traits::{One, Zero};
+
+const NODE_CAPACITY: usize = 8;
+
+/// Static R-tree over triangles, packed bottom up by sorting the bounding boxes into tiles.
+///
+/// The triangles are addressed by their index in the list the index was built from.
+#[derive(Debug, Clone)]
+pub struct TriangleIndex2d<P: Point2d, T: Triangle2d<P>> {
+    triangles: Vec<T>,
+    boxes: Vec<BoundingBox2dValues>,
+    nodes: Vec<Node>,
+    root: Option<usize>,
+    phantom: PhantomData<P>,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    bbox: BoundingBox2dValues,
+    children: Children,
+}
+
+#[derive(Debug, Clone)]
+enum Children {
+    Triangles(Vec<usize>),
+    Nodes(Vec<usize>),
+}
+
+/// queue entry of the nearest neighbor search
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Entry {
+    Node(usize),
+    Triangle(usize),
+}
+
+impl<P: Point2d, T: Triangle2d<P>> TriangleIndex2d<P, T> {
+    pub fn new(triangles: Vec<T>) -> Self {
+        let boxes: Vec<BoundingBox2dValues> = triangles.iter().map(triangle_bbox).collect();
+        let mut index = Self {
+            triangles,
+            boxes,
+            nodes: Vec::new(),
+            root: None,
+            phantom: PhantomData,
+        };
+        let mut level: Vec<(BoundingBox2dValues, usize)> =
+            index.boxes.iter().copied().zip(0..).collect();
+        let mut leaves = true;
+        while !level.is_empty() {
+            level = pack(level)
+                .into_iter()
+                .map(|group| {
+                    let bbox = group
+                        .iter()
+                        .map(|(bbox, _)| *bbox)
+                        .reduce(|a, b| union(&a, &b))
+                        .expect("Groups are not empty");
+                    let entries = group.into_iter().map(|(_, entry)| entry).collect();
+                    let children = if leaves {
+                        Children::Triangles(entries)
+                    } else {
+                        Children::Nodes(entries)
+                    };
+                    index.nodes.push(Node { bbox, children });
+                    (bbox, index.nodes.len() - 1)
+                })
+                .collect();
+            leaves = false;
+            if level.len() == 1 {
+                index.root = Some(index.nodes.len() - 1);
+                break;
+            }
+        }
+        index
+    }
+
+    pub fn triangles(&self) -> &[T] {
+        &self.triangles
+    }
+
+    /// the first triangle which contains the point, see [`Triangle2d::contains_pt`]
+    pub fn locate<Pt: Point2d>(&self, p: &Pt) -> Option<usize> {
+        self.containing(p).into_iter().next()
+    }
+
+    /// all triangles containing the point in ascending order
+    pub fn containing<Pt: Point2d>(&self, p: &Pt) -> Vec<usize> {
+        let p = p.coordinates();
+        let mut found = self.search(|bbox| bbox.contains(&p));
+        found.retain(|idx| self.triangles[*idx].contains_pt(&p));
+        found
+    }
+
+    /// all triangles whose bounding box touches the box in ascending order
+    pub fn in_box(&self, bbox: &BoundingBox2dValues) -> Vec<usize> {
+        self.search(|node_box| node_box.intersects(bbox))
+    }
+
+    /// the closest triangle and its distance to the point, zero for points inside
+    pub fn nearest<Pt: Point2d>(&self, p: &Pt) -> Option<(usize, Number)> {
+        let p = p.coordinates();
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((
+            self.nodes[self.root?].bbox.distance_squared(&p),
+            Entry::Node(self.root?),
+        )));
+        while let Some(Reverse((distance, entry))) = queue.pop() {
+            match entry {
+                Entry::Triangle(idx) => return Some((idx, distance.sqrt().into())),
+                Entry::Node(node) => match &self.nodes[node].children {
+                    Children::Triangles(triangles) => {
+                        for idx in triangles {
+                            let triangle = &self.triangles[*idx];
+                            queue.push(Reverse((
+                                triangle_distance_squared(triangle, &p),
+                                Entry::Triangle(*idx),
+                            )));
+                        }
+                    }
+                    Children::Nodes(nodes) => {
+                        for child in nodes {
+                            queue.push(Reverse((
+                                self.nodes[*child].bbox.distance_squared(&p),
+                                Entry::Node(*child),
+                            )));
+                        }
+                    }
+                },
+            }
+        }
+        None
+    }
+
+    fn search<F: Fn(&BoundingBox2dValues) -> bool>(&self, matches: F) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if !matches(&node.bbox) {
+                continue;
+            }
+            match &node.children {
+                Children::Triangles(triangles) => found.extend(
+                    triangles
+                        .iter()
+                        .filter(|idx| matches(&self.boxes[**idx]))
+                        .copied(),
+                ),
+                Children::Nodes(nodes) => stack.extend(nodes.iter().copied()),
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+}
+
+/// Sort-tile-recursive grouping of the entries into nodes
+fn pack(mut entries: Vec<(BoundingBox2dValues, usize)>) -> Vec<Vec<(BoundingBox2dValues, usize)>> {
+    let node_count = entries.len().div_ceil(NODE_CAPACITY);
+    let slice_size = (node_count as f64).sqrt().ceil() as usize * NODE_CAPACITY;
+    entries.sort_by_key(|(bbox, _)| bbox.min_x + bbox.max_x);
+    let mut groups = Vec::with_capacity(node_count);
+    for slice in entries.chunks_mut(slice_size) {
+        slice.sort_by_key(|(bbox, _)| bbox.min_y + bbox.max_y);
+        groups.extend(slice.chunks(NODE_CAPACITY).map(|group| group.to_vec()));
+    }
+    groups
+}
+
+fn union(a: &BoundingBox2dValues, b: &BoundingBox2dValues) -> BoundingBox2dValues {
+    BoundingBox2dValues::new(
+        a.min_x.min(b.min_x),
+        a.min_y.min(b.min_y),
+        a.max_x.max(b.max_x),
+        a.max_y.max(b.max_y),
+    )
+}
+
+fn triangle_bbox<P: Point2d, T: Triangle2d<P>>(triangle: &T) -> BoundingBox2dValues {
+    let [p1, p2, p3] = [triangle.p1(), triangle.p2(), triangle.p3()].map(|p| p.coordinates());
+    BoundingBox2dValues::new(
+        p1.x.min(p2.x).min(p3.x),
+        p1.y.min(p2.y).min(p3.y),
+        p1.x.max(p2.x).max(p3.x),
+        p1.y.max(p2.y).max(p3.y),
+    )
+}
+
+fn triangle_distance_squared<P: Point2d, T: Triangle2d<P>>(
+    triangle: &T,
+    p: &StaticPoint2d,
+) -> Number {
+    let corners = [triangle.p1(), triangle.p2(), triangle.p3()].map(|p| p.coordinates());
+    let sides = [0, 1, 2].map(|i| {
+        let (start, end) = (corners[i], corners[(i + 1) % 3]);
+        let direction = end - start;
+        direction.x * (p.y - start.y) - direction.y * (p.x - start.x)
+    });
+    // inside for both orientations
+    if sides.iter().all(|side| *side >= Number::zero())
+        || sides.iter().all(|side| *side <= Number::zero())
+    {
+        return Number::zero();
+    }
+    [0, 1, 2]
+        .map(|i| segment_distance_squared(corners[i], corners[(i + 1) % 3], p))
+        .into_iter()
+        .min()
+        .expect("Three sides")
+}
+
+fn segment_distance_squared(start: StaticPoint2d, end: StaticPoint2d, p: &StaticPoint2d) -> Number {
+    let direction = end - start;
+    let length = direction.magnitude_squared();
+    let along = if length == 0.0 {
+        Number::zero()
+    } else {
+        ((*p - start).dot(direction) / length).clamp(Number::zero(), Number::one())
+    };
+    (*p - (start + direction * along)).magnitude_squared()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry2d::point::bounding_box::BoundingBox2dValues;
+    use crate::geometry2d::point::StaticPoint2d;
+    use crate::geometry2d::rtree::TriangleIndex2d;
+    use crate::geometry2d::triangle::static_triangle::StaticTriangle2d;
+    use crate::geometry2d::triangle::Triangle2d;
+
+    /// two counter-clockwise triangles per cell of a grid
+    fn grid(size: usize) -> Vec<StaticTriangle2d<StaticPoint2d>> {
+        let mut triangles = Vec::new();
+        for x in 0..size {
+            for y in 0..size {
+                let (x, y) = (x as f64, y as f64);
+                triangles.push(StaticTriangle2d::new(
+                    (x, y).into(),
+                    (x + 1.0, y).into(),
+                    (x + 1.0, y + 1.0).into(),
+                ));
+                triangles.push(StaticTriangle2d::new(
+                    (x, y).into(),
+                    (x + 1.0, y + 1.0).into(),
+                    (x, y + 1.0).into(),
+                ));
+            }
+        }
+        triangles
+    }
+
+    #[test]
+    fn test_locate() {
+        let index = TriangleIndex2d::new(grid(20));
+        for (x, y) in [(0.7, 0.2), (13.2, 5.9), (19.9, 19.1), (4.5, 17.25)] {
+            let p = StaticPoint2d::from((x, y));
+            let expected = index
+                .triangles()
+                .iter()
+                .position(|triangle| triangle.contains_pt(&p));
+            assert!(expected.is_some());
+            assert_eq!(expected, index.locate(&p));
+        }
+        assert_eq!(None, index.locate(&StaticPoint2d::from((20.5, 3.0))));
+        assert!(
+            TriangleIndex2d::new(Vec::<StaticTriangle2d<StaticPoint2d>>::new())
+                .locate(&StaticPoint2d::from((0.0, 0.0)))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_box_and_nearest() {
+        let index = TriangleIndex2d::new(grid(20));
+        let found = index.in_box(&BoundingBox2dValues::new(
+            2.5.into(),
+            2.5.into(),
+            3.5.into(),
+            3.5.into(),
+        ));
+        // both triangles of the four cells around (3, 3)
+        assert_eq!(8, found.len());
+
+        let (nearest, distance) = index
+            .nearest(&StaticPoint2d::from((-3.0, 4.5)))
+            .expect("Not empty");
+        assert_eq!(3.0, distance.0);
+        let triangle = &index.triangles()[nearest];
+        assert!([triangle.p1(), triangle.p2(), triangle.p3()]
+            .iter()
+            .all(|p| p.x == 0.0 || p.x == 1.0));
+        let (_, inside) = index
+            .nearest(&StaticPoint2d::from((7.3, 8.1)))
+            .expect("Not empty");
+        assert_eq!(0.0, inside.0);
+    }
+}
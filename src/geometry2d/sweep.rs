@@ -0,0 +1,515 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound;
+use std::rc::Rc;
+
+use crate::geometry2d::line::Line2d;
+use crate::geometry2d::point::{Point2d, StaticPoint2d};
+use crate::primitives::Number;
+
+/// Intersection of two segments found by [`segment_intersections`], the ids are the indices of
+/// the segments in the input with `first < second`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SegmentIntersection {
+    Point {
+        first: usize,
+        second: usize,
+        point: StaticPoint2d,
+    },
+    /// collinear segments sharing the part from `start` to `end`
+    Overlap {
+        first: usize,
+        second: usize,
+        start: StaticPoint2d,
+        end: StaticPoint2d,
+    },
+}
+
+impl SegmentIntersection {
+    pub fn segments(&self) -> (usize, usize) {
+        match self {
+            SegmentIntersection::Point { first, second, .. }
+            | SegmentIntersection::Overlap { first, second, .. } => (*first, *second),
+        }
+    }
+}
+
+/// Finds all intersections between the segments with a Bentley-Ottmann sweep from left to right.
+///
+/// Touching end points are reported as intersections as well, collinear segments are reported
+/// once as overlap. Segments of zero length are ignored.
+pub fn segment_intersections<Pt: Point2d, L: Line2d<Pt>>(lines: &[L]) -> Vec<SegmentIntersection> {
+    let segments: Vec<[StaticPoint2d; 2]> = lines
+        .iter()
+        .map(|line| {
+            let (p1, p2) = (line.p1().coordinates(), line.p2().coordinates());
+            if key(p1) <= key(p2) {
+                [p1, p2]
+            } else {
+                [p2, p1]
+            }
+        })
+        .collect();
+    let tolerance = segments
+        .iter()
+        .flatten()
+        .map(|p| p.x.abs().max(p.y.abs()))
+        .fold(0.0, f64::max)
+        * 1e-9;
+    let mut sweep = Sweep {
+        segments,
+        tolerance,
+        events: BTreeMap::new(),
+        line: Rc::new(Cell::new(SweepLine {
+            point: (0.0, 0.0).into(),
+            right: true,
+            tolerance,
+        })),
+        status: BTreeSet::new(),
+        result: Vec::new(),
+    };
+    for (id, [start, end]) in sweep.segments.iter().enumerate() {
+        if start == end {
+            continue;
+        }
+        sweep.events.entry(key(*start)).or_default().push(id);
+        sweep.events.entry(key(*end)).or_default();
+    }
+    while let Some(((x, y), starting)) = sweep.events.pop_first() {
+        sweep.handle_event(StaticPoint2d { x, y }, starting);
+    }
+    sweep.result
+}
+
+fn key(p: StaticPoint2d) -> (Number, Number) {
+    (p.x, p.y)
+}
+
+struct Sweep {
+    /// segments with the start left of the end
+    segments: Vec<[StaticPoint2d; 2]>,
+    tolerance: f64,
+    /// points with the segments starting there
+    events: BTreeMap<(Number, Number), Vec<usize>>,
+    /// current event point, shared with the keys of the status
+    line: Rc<Cell<SweepLine>>,
+    /// segments crossing the sweep line from bottom to top
+    status: BTreeSet<StatusKey>,
+    result: Vec<SegmentIntersection>,
+}
+
+impl Sweep {
+    fn handle_event(&mut self, p: StaticPoint2d, starting: Vec<usize>) {
+        // the status is ordered just left of the event point until the segments through it are out
+        self.move_line(p, false);
+        let below = self.key(StatusEntry::Below(p.y.0 - self.tolerance));
+        let above = self.key(StatusEntry::Above(p.y.0 + self.tolerance));
+        let containing: Vec<StatusKey> = self.status.range(&below..&above).cloned().collect();
+        for key in containing.iter() {
+            let removed = self.status.remove(key);
+            debug_assert!(removed, "Inconsistent sweep status");
+        }
+        let containing_ids: Vec<usize> = containing.iter().map(StatusKey::id).collect();
+        self.report(p, &starting, &containing_ids);
+
+        // order just right of the event point, vertical segments come last
+        self.move_line(p, true);
+        let continuing: Vec<StatusKey> = containing
+            .into_iter()
+            .filter(|key| self.distance(self.segments[key.id()][1], p) > self.tolerance)
+            .chain(starting.into_iter().map(|id| {
+                self.key(StatusEntry::Segment {
+                    id,
+                    segment: self.segments[id],
+                })
+            }))
+            .collect();
+        self.status.extend(continuing.iter().cloned());
+
+        let (lowest, highest) = match (continuing.iter().min(), continuing.iter().max()) {
+            (Some(lowest), Some(highest)) => (lowest, highest),
+            _ => (&below, &above),
+        };
+        let neighbor_below = self.status.range(..lowest).next_back().map(StatusKey::id);
+        let neighbor_above = self
+            .status
+            .range((Bound::Excluded(highest), Bound::Unbounded))
+            .next()
+            .map(StatusKey::id);
+        if continuing.is_empty() {
+            if let (Some(below), Some(above)) = (neighbor_below, neighbor_above) {
+                self.add_crossing(below, above, p);
+            }
+        } else {
+            if let Some(below) = neighbor_below {
+                self.add_crossing(below, lowest.id(), p);
+            }
+            if let Some(above) = neighbor_above {
+                self.add_crossing(highest.id(), above, p);
+            }
+        }
+    }
+
+    fn move_line(&self, point: StaticPoint2d, right: bool) {
+        self.line.set(SweepLine {
+            point,
+            right,
+            tolerance: self.tolerance,
+        });
+    }
+
+    fn key(&self, entry: StatusEntry) -> StatusKey {
+        StatusKey {
+            entry,
+            line: self.line.clone(),
+        }
+    }
+
+    fn report(&mut self, p: StaticPoint2d, starting: &[usize], containing: &[usize]) {
+        let all: Vec<usize> = containing.iter().chain(starting).copied().collect();
+        for (i, a) in all.iter().enumerate() {
+            for b in &all[i + 1..] {
+                let (first, second) = (*a.min(b), *a.max(b));
+                let new_a = starting.contains(a);
+                let new_b = starting.contains(b);
+                if !self.collinear(*a, *b) {
+                    self.result.push(SegmentIntersection::Point {
+                        first,
+                        second,
+                        point: p,
+                    });
+                    continue;
+                }
+                // the overlap was reported where the later segment started
+                if !new_a && !new_b {
+                    continue;
+                }
+                let (end_a, end_b) = (self.segments[*a][1], self.segments[*b][1]);
+                let end = if key(end_a) <= key(end_b) {
+                    end_a
+                } else {
+                    end_b
+                };
+                self.result.push(if self.distance(end, p) > self.tolerance {
+                    SegmentIntersection::Overlap {
+                        first,
+                        second,
+                        start: p,
+                        end,
+                    }
+                } else {
+                    SegmentIntersection::Point {
+                        first,
+                        second,
+                        point: p,
+                    }
+                });
+            }
+        }
+    }
+
+    /// adds the crossing of two neighbors right of the event point as new event
+    fn add_crossing(&mut self, a: usize, b: usize, p: StaticPoint2d) {
+        let [a1, a2] = self.segments[a];
+        let [b1, b2] = self.segments[b];
+        let (da, db) = (a2 - a1, b2 - b1);
+        let div = da.x * db.y - da.y * db.x;
+        if div == 0.0 {
+            return;
+        }
+        let diff = b1 - a1;
+        let ta = (diff.x * db.y - diff.y * db.x) / div;
+        let tb = (diff.x * da.y - diff.y * da.x) / div;
+        if !(0.0..=1.0).contains(&ta.0) || !(0.0..=1.0).contains(&tb.0) {
+            return;
+        }
+        let mut crossing = a1 + da * ta;
+        // snap to end points, they are events already
+        if let Some(end) = [a1, a2, b1, b2]
+            .into_iter()
+            .find(|end| self.distance(*end, crossing) <= self.tolerance)
+        {
+            crossing = end;
+        }
+        if key(crossing) <= key(p) || self.distance(crossing, p) <= self.tolerance {
+            return;
+        }
+        // crossings of several segments in one point are computed with small differences
+        let tolerance = Number::from(self.tolerance);
+        let near = (crossing.x - tolerance, Number::from(f64::NEG_INFINITY))
+            ..=(crossing.x + tolerance, Number::from(f64::INFINITY));
+        if !self.events.range(near).any(|((x, y), _)| {
+            self.distance(StaticPoint2d { x: *x, y: *y }, crossing) <= self.tolerance
+        }) {
+            self.events.entry(key(crossing)).or_default();
+        }
+    }
+
+    fn collinear(&self, a: usize, b: usize) -> bool {
+        let [a1, a2] = self.segments[a];
+        let [b1, b2] = self.segments[b];
+        let (da, db) = (a2 - a1, b2 - b1);
+        (da.x * db.y - da.y * db.x).abs() <= self.tolerance * (da.magnitude() + db.magnitude()).0
+    }
+
+    fn distance(&self, a: StaticPoint2d, b: StaticPoint2d) -> f64 {
+        (a - b).magnitude().0
+    }
+}
+
+/// event point of the sweep and on which side of it the status is ordered
+#[derive(Debug, Copy, Clone)]
+struct SweepLine {
+    point: StaticPoint2d,
+    right: bool,
+    tolerance: f64,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum StatusEntry {
+    Segment {
+        id: usize,
+        segment: [StaticPoint2d; 2],
+    },
+    /// bounds for range queries, below or above all segments at the height
+    Below(f64),
+    Above(f64),
+}
+
+/// Entry of the status ordered by the height at the current sweep line.
+///
+/// The order of the segments only changes at event points, where the segments through the point
+/// are removed before the line moves and inserted again afterwards.
+#[derive(Debug, Clone)]
+struct StatusKey {
+    entry: StatusEntry,
+    line: Rc<Cell<SweepLine>>,
+}
+
+impl StatusKey {
+    fn id(&self) -> usize {
+        match self.entry {
+            StatusEntry::Segment { id, .. } => id,
+            _ => usize::MAX,
+        }
+    }
+}
+
+impl Ord for StatusKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let line = self.line.get();
+        let p = line.point;
+        match (self.entry, other.entry) {
+            (
+                StatusEntry::Segment {
+                    id: id_a,
+                    segment: a,
+                },
+                StatusEntry::Segment {
+                    id: id_b,
+                    segment: b,
+                },
+            ) => {
+                let (y_a, y_b) = (y_at(a, p), y_at(b, p));
+                if (y_a - y_b).abs() > line.tolerance {
+                    return y_a.total_cmp(&y_b);
+                }
+                // segments through the same point are ordered by their slopes
+                let by_slope = slope(a).cmp(&slope(b));
+                let by_slope = if line.right {
+                    by_slope
+                } else {
+                    by_slope.reverse()
+                };
+                by_slope.then(id_a.cmp(&id_b))
+            }
+            (StatusEntry::Segment { segment, .. }, bound) => {
+                bound_order(bound, y_at(segment, p)).reverse()
+            }
+            (bound, StatusEntry::Segment { segment, .. }) => bound_order(bound, y_at(segment, p)),
+            (a, b) => bound_height(a)
+                .total_cmp(&bound_height(b))
+                .then(matches!(a, StatusEntry::Above(_)).cmp(&matches!(b, StatusEntry::Above(_)))),
+        }
+    }
+}
+
+impl PartialOrd for StatusKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for StatusKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for StatusKey {}
+
+/// order of a range bound compared to a segment at the height
+fn bound_order(bound: StatusEntry, height: f64) -> Ordering {
+    match bound {
+        StatusEntry::Below(y) if y <= height => Ordering::Less,
+        StatusEntry::Above(y) if y < height => Ordering::Less,
+        _ => Ordering::Greater,
+    }
+}
+
+fn bound_height(bound: StatusEntry) -> f64 {
+    match bound {
+        StatusEntry::Below(y) | StatusEntry::Above(y) => y,
+        StatusEntry::Segment { .. } => f64::NAN,
+    }
+}
+
+/// height of the segment at the sweep line through the event point
+fn y_at([start, end]: [StaticPoint2d; 2], p: StaticPoint2d) -> f64 {
+    if start.x == end.x {
+        return p.y.0.clamp(start.y.0, end.y.0);
+    }
+    if p.x == start.x {
+        return start.y.0;
+    }
+    if p.x == end.x {
+        return end.y.0;
+    }
+    let t = ((p.x - start.x) / (end.x - start.x)).0.clamp(0.0, 1.0);
+    (start.y + (end.y - start.y) * t).0
+}
+
+fn slope([start, end]: [StaticPoint2d; 2]) -> Number {
+    if start.x == end.x {
+        Number::from(f64::INFINITY)
+    } else {
+        (end.y - start.y) / (end.x - start.x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry2d::line::StaticLine2d;
+    use crate::geometry2d::point::StaticPoint2d;
+    use crate::geometry2d::sweep::{segment_intersections, SegmentIntersection};
+
+    fn line(x1: f64, y1: f64, x2: f64, y2: f64) -> StaticLine2d<StaticPoint2d> {
+        StaticLine2d::new((x1, y1).into(), (x2, y2).into())
+    }
+
+    fn brute_force(lines: &[StaticLine2d<StaticPoint2d>]) -> Vec<(usize, usize)> {
+        use crate::geometry2d::line::{Line2d, LineIntersection};
+        let mut pairs = Vec::new();
+        for i in 0..lines.len() {
+            for j in i + 1..lines.len() {
+                if let LineIntersection::Point { .. } = lines[i].intersect(&lines[j]) {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn test_crossing_segments() {
+        let lines = vec![
+            line(0.0, 0.0, 4.0, 4.0),
+            line(0.0, 4.0, 4.0, 0.0),
+            line(1.0, 0.0, 1.0, 4.0),
+            line(0.0, 3.0, 4.0, 3.0),
+            line(5.0, 0.0, 6.0, 1.0),
+        ];
+        let mut found: Vec<(usize, usize)> = segment_intersections(&lines)
+            .iter()
+            .map(SegmentIntersection::segments)
+            .collect();
+        found.sort();
+        assert_eq!(brute_force(&lines), found);
+        assert!(
+            segment_intersections(&lines).contains(&SegmentIntersection::Point {
+                first: 0,
+                second: 1,
+                point: (2.0, 2.0).into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_many_segments() {
+        // a star of lines through the center and a grid of short segments
+        let mut lines = Vec::new();
+        for i in 0..12 {
+            let angle = i as f64 * 0.25 + 0.1;
+            let (dx, dy) = (angle.cos() * 5.0, angle.sin() * 5.0);
+            lines.push(line(5.1 - dx, 4.9 - dy, 5.1 + dx, 4.9 + dy));
+        }
+        for i in 0..10 {
+            lines.push(line(i as f64 + 0.3, 0.2, i as f64 + 0.7, 9.7));
+        }
+        let mut found: Vec<(usize, usize)> = segment_intersections(&lines)
+            .iter()
+            .map(SegmentIntersection::segments)
+            .collect();
+        found.sort();
+        assert_eq!(brute_force(&lines), found);
+    }
+
+    #[test]
+    fn test_random_segments() {
+        // linear congruential generator, the test must not depend on a random seed
+        let mut state: u64 = 12345;
+        let mut random = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64 * 100.0
+        };
+        let lines: Vec<_> = (0..200)
+            .map(|_| {
+                let (x, y) = (random(), random());
+                line(x, y, x + random() / 5.0, y + random() / 5.0 - 10.0)
+            })
+            .collect();
+        let mut found: Vec<(usize, usize)> = segment_intersections(&lines)
+            .iter()
+            .map(SegmentIntersection::segments)
+            .collect();
+        found.sort();
+        assert_eq!(brute_force(&lines), found);
+    }
+
+    #[test]
+    fn test_collinear_overlap() {
+        let lines = vec![
+            line(0.0, 0.0, 2.0, 0.0),
+            line(3.0, 0.0, 1.0, 0.0),
+            line(3.0, 0.0, 4.0, 0.0),
+            line(1.0, 1.0, 1.0, 2.0),
+            line(1.0, 1.5, 1.0, 3.0),
+        ];
+        let found = segment_intersections(&lines);
+        assert_eq!(
+            vec![
+                SegmentIntersection::Overlap {
+                    first: 0,
+                    second: 1,
+                    start: (1.0, 0.0).into(),
+                    end: (2.0, 0.0).into(),
+                },
+                SegmentIntersection::Overlap {
+                    first: 3,
+                    second: 4,
+                    start: (1.0, 1.5).into(),
+                    end: (1.0, 2.0).into(),
+                },
+                SegmentIntersection::Point {
+                    first: 1,
+                    second: 2,
+                    point: (3.0, 0.0).into(),
+                },
+            ],
+            found
+        );
+    }
+}
@@ -5,11 +5,12 @@ use svg::node::Value;
 use svg::Document;
 use triangulate::{formats, ListFormat, Mappable, PolygonList};
 
+use crate::generic_triangle::TriangleSide;
 use crate::geometry2d::line::{Line2d, SideOfLine, StaticLine2d};
 use crate::geometry2d::point::StaticPoint2d;
-use crate::geometry2d::polygon::cut::PolygonPath;
+use crate::geometry2d::polygon::cut::{PointPolygonRelationship, PolygonPath};
 use crate::geometry2d::polygon::{AnyPolygon, Polygon2d};
-use crate::geometry2d::triangle::Triangle2d;
+use crate::geometry2d::triangle::{Triangle2d, TriangulationMethod};
 use crate::prelude::{BoundingBox2d, BoundingBox2dValues, Point2d, StaticTriangle2d};
 
 struct Figure<Pt: Point2d> {
@@ -242,6 +243,23 @@ fn test_triangle_point_iterator() {
     assert_eq!(None, iterator.next());
 }
 
+#[test]
+fn test_triangle_sides() {
+    let tr = StaticTriangle2d::<StaticPoint2d>::new(
+        (2.0, 0.0).into(),
+        (3.0, 0.0).into(),
+        (2.0, 1.0).into(),
+    );
+    let mut iterator = tr.sides();
+    for (p1, p2) in [tr.l1(), tr.l2(), tr.l3()] {
+        assert!(iterator
+            .next()
+            .map(|side| side.equals(&StaticLine2d::new(*p1, *p2)))
+            .unwrap_or(false));
+    }
+    assert!(iterator.next().is_none());
+}
+
 #[test]
 fn test_area() {
     assert_eq!(
@@ -351,7 +369,7 @@ fn test_triangle_error() {
                 }
             }
         }
-        PolygonPath::None => {}
+        PolygonPath::Touching | PolygonPath::NotSimple | PolygonPath::None => {}
     }
 }
 
@@ -367,6 +385,12 @@ fn test_polygon_intersection() {
         (0.0, -25.0).into(),
         (50.0, 25.0).into(),
     );
+    // the upper corners lie outside of the big triangle, the lower one and the middle of the
+    // upper side inside
+    match big_triangle.cut(&small_triangle) {
+        PolygonPath::CutSegments(segments) => assert_eq!(2, segments.len()),
+        path => panic!("Unexpected path: {path:?}"),
+    }
     let small_triangle =
         StaticTriangle2d::new((0.0, 50.0).into(), (0.0, 25.0).into(), (-50.0, 50.0).into());
     let cut_polygon = &small_triangle;
@@ -398,12 +422,15 @@ fn test_polygon_intersection() {
                 }
             }
         }
-        PolygonPath::None => {}
+        PolygonPath::Touching | PolygonPath::NotSimple | PolygonPath::None => {}
     }
 
     let mut colors = ["blue", "green"].iter().cycle();
     let mut show = [true, true].iter().cycle();
-    for polygon in &big_triangle.triangulate_cut_polygons(&small_triangle, &path)[0] {
+    for polygon in &big_triangle
+        .triangulate_cut_polygons(&small_triangle, &path)
+        .expect("Triangulation failed")[0]
+    {
         let stroke = colors.next().unwrap();
         let show = show.next().unwrap();
         if *show {
@@ -452,3 +479,199 @@ fn test_triangulate() {
         polygons.get_vertex(triangulated_indices[2])
     );
 }
+
+#[test]
+fn test_cut_with_delaunay() {
+    let big_triangle = StaticTriangle2d::new(
+        StaticPoint2d::from((0.0, 0.0)),
+        (10.0, 0.0).into(),
+        (0.0, 10.0).into(),
+    );
+    let mut circle: Vec<StaticPoint2d> = Vec::new();
+    for i in 0..16 {
+        let angle = std::f64::consts::TAU * i as f64 / 16.0;
+        circle.push((3.0 + angle.cos(), 3.0 + angle.sin()).into());
+    }
+    let smallest_angle = |triangles: &[StaticTriangle2d<StaticPoint2d>]| {
+        triangles
+            .iter()
+            .flat_map(|t| {
+                let [a, b, c] = [t.p1, t.p2, t.p3];
+                [(a, b, c), (b, c, a), (c, a, b)].map(|(a, b, c)| {
+                    let (u, v) = (b - a, c - a);
+                    (u.dot(v).0 / (u.magnitude() * v.magnitude()).0).acos()
+                })
+            })
+            .fold(f64::INFINITY, f64::min)
+    };
+    let mut angles = Vec::new();
+    for method in [
+        TriangulationMethod::Fan,
+        TriangulationMethod::ConstrainedDelaunay,
+    ] {
+        let [outside, inside] = big_triangle
+            .cut_to_triangles_with(&circle, method)
+            .expect("Triangulation failed");
+        // `Mappable` of the triangulate crate is in scope as well
+        let outside: Vec<_> = Iterator::map(outside.iter(), |t| t.coordinates_triangle()).collect();
+        let inside: Vec<_> = Iterator::map(inside.iter(), |t| t.coordinates_triangle()).collect();
+        let area: f64 = Iterator::map(outside.iter().chain(inside.iter()), |t| t.area().0).sum();
+        assert!((area - 50.0).abs() < 1e-9);
+        angles.push(smallest_angle(&outside).min(smallest_angle(&inside)));
+    }
+    assert!(angles[1] >= angles[0]);
+}
+
+fn corner_triangle() -> StaticTriangle2d<StaticPoint2d> {
+    StaticTriangle2d::new((0.0, 0.0).into(), (4.0, 0.0).into(), (0.0, 4.0).into())
+}
+
+/// cuts the triangle `(0,0) (4,0) (0,4)` and checks that the parts cover it on the right side
+fn check_touching_cut(polygon: Vec<StaticPoint2d>, inside_area: f64) {
+    let triangle = corner_triangle();
+    for method in [
+        TriangulationMethod::Fan,
+        TriangulationMethod::ConstrainedDelaunay,
+    ] {
+        let [outside, inside] = triangle
+            .cut_to_triangles_with(&polygon, method)
+            .expect("Triangulation failed");
+        let mut areas = [0.0, 0.0];
+        for (area, (triangles, unexpected)) in areas.iter_mut().zip([
+            (&outside, PointPolygonRelationship::Inside),
+            (&inside, PointPolygonRelationship::Outside),
+        ]) {
+            for found in triangles {
+                let found = found.coordinates_triangle();
+                assert!(found.area().0 > 0.0);
+                assert_ne!(unexpected, polygon.point_position(&found.gravity_point()));
+                *area += found.area().0;
+            }
+        }
+        assert!((areas[1] - inside_area).abs() < 1e-9);
+        assert!((areas[0] + areas[1] - 8.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_touching_path() {
+    let triangle = corner_triangle();
+    let on_side: Vec<StaticPoint2d> =
+        vec![(2.0, 0.0).into(), (3.0, -1.0).into(), (1.0, -1.0).into()];
+    assert!(matches!(triangle.cut(&on_side), PolygonPath::Touching));
+    let crossing: Vec<StaticPoint2d> =
+        vec![(2.0, 1.0).into(), (3.0, -1.0).into(), (1.0, -1.0).into()];
+    assert!(matches!(
+        triangle.cut(&crossing),
+        PolygonPath::CutSegments(_)
+    ));
+}
+
+#[test]
+fn test_not_simple_path() {
+    let triangle = corner_triangle();
+    let bow_tie: Vec<StaticPoint2d> = vec![
+        (0.5, 0.5).into(),
+        (1.5, 1.5).into(),
+        (1.5, 0.5).into(),
+        (0.5, 1.5).into(),
+    ];
+    assert!(matches!(triangle.cut(&bow_tie), PolygonPath::NotSimple));
+    let [outside, inside] = triangle
+        .cut_to_triangles_with(&bow_tie, TriangulationMethod::ConstrainedDelaunay)
+        .expect("Triangulation failed");
+    assert!(inside.is_empty());
+    assert_eq!(1, outside.len());
+    assert_eq!(triangle.area(), outside[0].coordinates_triangle().area());
+}
+
+#[test]
+fn test_cut_through_corner() {
+    // a polygon point on the corner
+    check_touching_cut(
+        vec![
+            (0.0, 0.0).into(),
+            (2.0, -1.0).into(),
+            (2.0, 1.0).into(),
+            (-1.0, 2.0).into(),
+        ],
+        8.0 / 3.0,
+    );
+    // a polygon side through the corner
+    check_touching_cut(
+        vec![(-1.0, 1.0).into(), (1.0, -1.0).into(), (3.0, 3.0).into()],
+        23.0 / 6.0,
+    );
+}
+
+#[test]
+fn test_cut_along_side() {
+    // a rectangle standing on the lower side
+    check_touching_cut(
+        vec![
+            (1.0, 0.0).into(),
+            (2.0, 0.0).into(),
+            (2.0, 1.0).into(),
+            (1.0, 1.0).into(),
+        ],
+        1.0,
+    );
+    // a rectangle reaching over the lower side is a regular cut
+    check_touching_cut(
+        vec![
+            (1.0, -1.0).into(),
+            (2.0, -1.0).into(),
+            (2.0, 1.0).into(),
+            (1.0, 1.0).into(),
+        ],
+        1.0,
+    );
+    // the polygon is the triangle itself
+    check_touching_cut(
+        vec![(0.0, 0.0).into(), (4.0, 0.0).into(), (0.0, 4.0).into()],
+        8.0,
+    );
+    // touches only the corner from outside
+    check_touching_cut(
+        vec![
+            (4.0, 0.0).into(),
+            (5.0, 0.0).into(),
+            (5.0, 1.0).into(),
+            (4.0, 1.0).into(),
+        ],
+        0.0,
+    );
+    // shares the lower side from below
+    check_touching_cut(
+        vec![
+            (0.0, 0.0).into(),
+            (0.0, -1.0).into(),
+            (4.0, -1.0).into(),
+            (4.0, 0.0).into(),
+        ],
+        0.0,
+    );
+}
+
+#[test]
+fn test_cutting_edges_along_side() {
+    let triangle = corner_triangle();
+    // runs along the lower side and leaves it at the corner
+    let along_side = StaticLine2d::new(
+        StaticPoint2d::from((1.0, 0.0)),
+        StaticPoint2d::from((6.0, 0.0)),
+    );
+    assert_eq!(
+        vec![(TriangleSide::S1, 0.25.into(), 0.0.into())],
+        triangle.find_cutting_edges(&along_side)
+    );
+    // starts outside and ends on the side
+    let partly = StaticLine2d::new(
+        StaticPoint2d::from((-2.0, 0.0)),
+        StaticPoint2d::from((2.0, 0.0)),
+    );
+    assert_eq!(
+        vec![(TriangleSide::S1, 0.5.into(), 1.0.into())],
+        triangle.find_cutting_edges(&partly)
+    );
+}
@@ -114,7 +114,7 @@ impl<P: PointAlongTriangleSide + Debug> TriangleEdgePoint<P> {
 
 impl<P: PointAlongTriangleSide> PartialOrd for TriangleEdgePoint<P> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.full_cmp(other))
+        Some(self.cmp(other))
     }
 }
 
@@ -140,7 +140,7 @@ impl<'a> WalkIndex<'a> {
         for (idx, p) in edge_points.iter().enumerate() {
             if edge_peers
                 .get(idx)
-                .map_or(false, |i: &Option<usize>| i.is_some())
+                .is_some_and(|i: &Option<usize>| i.is_some())
             {
                 continue;
             }
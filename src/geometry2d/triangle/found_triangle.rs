@@ -37,7 +37,7 @@ pub enum FoundTriangle<
         p2: FoundPoint<'t, PointTriangle, PointPolygon, T, P>,
         p3: FoundPoint<'t, PointTriangle, PointPolygon, T, P>,
     },
-    FoundTriangle(StaticTriangle2d<FoundPoint<'t, PointTriangle, PointPolygon, T, P>>),
+    Cut(StaticTriangle2d<FoundPoint<'t, PointTriangle, PointPolygon, T, P>>),
     _Phantom(
         PhantomData<PointTriangle>,
         PhantomData<P>,
@@ -128,7 +128,7 @@ impl<
                 TriangleCornerPoint::P2 => p2,
                 TriangleCornerPoint::P3 => p3,
             },
-            FoundTriangle::FoundTriangle(t) => t.point(p),
+            FoundTriangle::Cut(t) => t.point(p),
             FoundTriangle::_Phantom(_, _, _) => panic!("Never use"),
         }
     }
@@ -137,7 +137,7 @@ impl<
     fn reverse(&self) -> Self {
         match self {
             FoundTriangle::Original(t) =>  FoundTriangle::Original{t.},
-            FoundTriangle::FoundTriangle(t) => FoundTriangle::FoundTriangle(t.reverse()),
+            FoundTriangle::Cut(t) => FoundTriangle::Cut(t.reverse()),
             FoundTriangle::_Phantom(_, _, _) => panic!("Never use"),
         }
     }*/
@@ -151,7 +151,7 @@ pub fn found_triangle_corner<
 >(
     corner: TriangleCornerPoint,
     triangle: &T,
-) -> FoundPoint<PointTriangle, PointPolygon, T, P> {
+) -> FoundPoint<'_, PointTriangle, PointPolygon, T, P> {
     FoundPoint::TriangleCorner {
         corner,
         triangle,
@@ -188,7 +188,7 @@ pub fn found_polygon_point<
 >(
     idx: usize,
     polygon: &P,
-) -> FoundPoint<PointTriangle, PointPolygon, T, P> {
+) -> FoundPoint<'_, PointTriangle, PointPolygon, T, P> {
     FoundPoint::PolygonPoint {
         idx,
         polygon,
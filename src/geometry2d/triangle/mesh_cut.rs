@@ -0,0 +1,313 @@
+use crate::geometry2d::delaunay::Mesh;
+use crate::geometry2d::point::bounding_box::BoundingBox2dValues;
+use crate::geometry2d::point::{Point2d, StaticPoint2d};
+use crate::geometry2d::polygon::cut::PointPolygonRelationship;
+use crate::geometry2d::polygon::with_holes::TriangulationFailed;
+use crate::geometry2d::polygon::Polygon2d;
+use crate::geometry2d::rtree::TriangleIndex2d;
+use crate::geometry2d::triangle::static_triangle::StaticTriangle2d;
+use crate::primitives::Number;
+
+/// Where a point of a [`MeshCut`] comes from
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CutPointOrigin {
+    /// index of the point in the cut mesh
+    Mesh(usize),
+    /// index of the point in the cut polygon
+    Polygon(usize),
+    /// a polygon side crosses a mesh edge
+    Cross,
+}
+
+/// Conforming triangulation of a mesh region which is split by a polygon, neighboring
+/// triangles share the new points along their common edges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshCut {
+    points: Vec<StaticPoint2d>,
+    origins: Vec<CutPointOrigin>,
+    outside: Vec<[usize; 3]>,
+    inside: Vec<[usize; 3]>,
+    outside_sources: Vec<usize>,
+    inside_sources: Vec<usize>,
+}
+
+impl MeshCut {
+    pub fn points(&self) -> &[StaticPoint2d] {
+        &self.points
+    }
+    pub fn origins(&self) -> &[CutPointOrigin] {
+        &self.origins
+    }
+    /// counter-clockwise triangles outside of the polygon
+    pub fn outside(&self) -> &[[usize; 3]] {
+        &self.outside
+    }
+    /// counter-clockwise triangles inside of the polygon
+    pub fn inside(&self) -> &[[usize; 3]] {
+        &self.inside
+    }
+    /// the index of the mesh triangle every outside triangle lies in
+    pub fn outside_sources(&self) -> &[usize] {
+        &self.outside_sources
+    }
+    /// the index of the mesh triangle every inside triangle lies in
+    pub fn inside_sources(&self) -> &[usize] {
+        &self.inside_sources
+    }
+    /// replaces the mesh point indices by the ones in a larger list
+    pub(crate) fn with_mesh_indices(mut self, indices: &[usize]) -> Self {
+        for origin in self.origins.iter_mut() {
+            if let CutPointOrigin::Mesh(idx) = origin {
+                *idx = indices[*idx];
+            }
+        }
+        self
+    }
+}
+
+/// Cuts all triangles of an indexed mesh by the polygon in one pass.
+///
+/// The mesh edges and polygon sides are inserted as constraints into one constrained Delaunay
+/// triangulation, so every cross point is created once and used on both sides of an edge.
+/// Triangles which are not touched by the polygon keep their corners, they are only split where
+/// other mesh points lie on their sides. Polygons which are not simple are rejected.
+pub fn cut_triangles<P: Point2d, Pt: Point2d, Poly: Polygon2d<Pt>>(
+    points: &[P],
+    triangles: &[[usize; 3]],
+    cut_polygon: &Poly,
+) -> Result<MeshCut, TriangulationFailed> {
+    if !cut_polygon.is_simple() {
+        return Err(TriangulationFailed(
+            "The cut polygon intersects itself".to_string(),
+        ));
+    }
+    let mesh_count = points.len();
+    let polygon_count = cut_polygon.point_count();
+    let all_points: Vec<StaticPoint2d> = points
+        .iter()
+        .map(|p| p.coordinates())
+        .chain(cut_polygon.points().map(|p| p.coordinates()))
+        .collect();
+    let mut mesh = Mesh::new(all_points)?;
+    for corners in triangles {
+        for i in 0..3 {
+            mesh.insert_constraint(corners[i], corners[(i + 1) % 3])?;
+        }
+    }
+    for idx in 0..polygon_count {
+        mesh.insert_constraint(mesh_count + idx, mesh_count + (idx + 1) % polygon_count)?;
+    }
+    let triangulation = mesh.finish(false);
+
+    let source_triangles: Vec<[StaticPoint2d; 3]> = triangles
+        .iter()
+        .map(|corners| corners.map(|p| points[p].coordinates()))
+        .collect();
+    let tolerance = Number::from(
+        source_triangles
+            .iter()
+            .flatten()
+            .map(|p| p.x.abs().max(p.y.abs()))
+            .fold(0.0, f64::max)
+            * 1e-9,
+    );
+    let index = TriangleIndex2d::new(
+        source_triangles
+            .iter()
+            .map(|[p1, p2, p3]| StaticTriangle2d::new(*p1, *p2, *p3))
+            .collect(),
+    );
+    let mut new_idx = vec![None; triangulation.points().len()];
+    let mut result = MeshCut {
+        points: Vec::new(),
+        origins: Vec::new(),
+        outside: Vec::new(),
+        inside: Vec::new(),
+        outside_sources: Vec::new(),
+        inside_sources: Vec::new(),
+    };
+    for corners in triangulation.triangles() {
+        let [a, b, c] = corners.map(|p| triangulation.points()[p]);
+        let center = StaticPoint2d {
+            x: (a.x + b.x + c.x) / 3.0,
+            y: (a.y + b.y + c.y) / 3.0,
+        };
+        let Some(source) = index
+            .in_box(&BoundingBox2dValues::new(
+                center.x, center.y, center.x, center.y,
+            ))
+            .into_iter()
+            .find(|idx| contains(&source_triangles[*idx], center, tolerance))
+        else {
+            continue;
+        };
+        let corners = corners.map(|p| {
+            *new_idx[p].get_or_insert_with(|| {
+                result.points.push(triangulation.points()[p]);
+                result.origins.push(if p < mesh_count {
+                    CutPointOrigin::Mesh(p)
+                } else if p < mesh_count + polygon_count {
+                    CutPointOrigin::Polygon(p - mesh_count)
+                } else {
+                    CutPointOrigin::Cross
+                });
+                result.points.len() - 1
+            })
+        });
+        if cut_polygon.point_position(&center) == PointPolygonRelationship::Inside {
+            result.inside.push(corners);
+            result.inside_sources.push(source);
+        } else {
+            result.outside.push(corners);
+            result.outside_sources.push(source);
+        }
+    }
+    Ok(result)
+}
+
+/// the point is inside the triangle of either orientation
+fn contains(triangle: &[StaticPoint2d; 3], p: StaticPoint2d, tolerance: Number) -> bool {
+    let sides: Vec<Number> = (0..3)
+        .map(|i| {
+            let (start, end) = (triangle[i], triangle[(i + 1) % 3]);
+            let direction = end - start;
+            let offset = p - start;
+            (direction.x * offset.y - direction.y * offset.x) / direction.magnitude()
+        })
+        .collect();
+    sides.iter().all(|side| *side >= -tolerance) || sides.iter().all(|side| *side <= tolerance)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::geometry2d::point::StaticPoint2d;
+    use crate::geometry2d::polygon::Polygon2d;
+    use crate::geometry2d::triangle::mesh_cut::{cut_triangles, CutPointOrigin, MeshCut};
+    use crate::geometry2d::triangle::static_triangle::StaticTriangle2d;
+    use crate::prelude::Triangle2d;
+
+    fn area(cut: &MeshCut, triangles: &[[usize; 3]]) -> f64 {
+        triangles
+            .iter()
+            .map(|[p1, p2, p3]| {
+                let triangle =
+                    StaticTriangle2d::new(cut.points()[*p1], cut.points()[*p2], cut.points()[*p3]);
+                assert!(triangle.area().0 > 0.0);
+                triangle.area().0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_cut_square_mesh() {
+        // four triangles around the center of a square
+        let points: Vec<StaticPoint2d> = vec![
+            (0.0, 0.0).into(),
+            (2.0, 0.0).into(),
+            (2.0, 2.0).into(),
+            (0.0, 2.0).into(),
+            (1.0, 1.0).into(),
+        ];
+        let triangles = [[0, 1, 4], [1, 2, 4], [2, 3, 4], [3, 0, 4]];
+        let polygon: Vec<StaticPoint2d> = vec![
+            (0.5, -0.5).into(),
+            (1.5, -0.5).into(),
+            (1.5, 1.5).into(),
+            (0.5, 1.5).into(),
+        ];
+        let cut = cut_triangles(&points, &triangles, &polygon).expect("Cut failed");
+        assert!((area(&cut, cut.inside()) - 1.5).abs() < 1e-9);
+        assert!((area(&cut, cut.outside()) - 2.5).abs() < 1e-9);
+        for (triangles, sources) in [
+            (cut.inside(), cut.inside_sources()),
+            (cut.outside(), cut.outside_sources()),
+        ] {
+            assert_eq!(triangles.len(), sources.len());
+        }
+
+        // no T-junctions: every inner edge is used in both directions
+        let edges: HashSet<(usize, usize)> = cut
+            .inside()
+            .iter()
+            .chain(cut.outside().iter())
+            .flat_map(|t| (0..3).map(|i| (t[i], t[(i + 1) % 3])))
+            .collect();
+        let boundary = edges
+            .iter()
+            .filter(|(a, b)| !edges.contains(&(*b, *a)))
+            .count();
+        // the square sides, the bottom one split into three parts
+        assert_eq!(6, boundary);
+
+        // the polygon sides cross the bottom side and two diagonals, the upper polygon points
+        // lie on the other two diagonals
+        let crosses = cut
+            .origins()
+            .iter()
+            .filter(|o| **o == CutPointOrigin::Cross)
+            .count();
+        assert_eq!(4, crosses);
+        assert!(cut.origins().contains(&CutPointOrigin::Mesh(4)));
+        assert!(cut.origins().contains(&CutPointOrigin::Polygon(2)));
+        // the points below the square are not used
+        assert!(!cut.origins().contains(&CutPointOrigin::Polygon(0)));
+        for p in polygon.iter().skip(2) {
+            assert!(cut.points().contains(p));
+        }
+    }
+
+    #[test]
+    fn test_self_intersecting_polygon() {
+        let points: Vec<StaticPoint2d> =
+            vec![(0.0, 0.0).into(), (2.0, 0.0).into(), (0.0, 2.0).into()];
+        let bow_tie: Vec<StaticPoint2d> = vec![
+            (0.2, 0.2).into(),
+            (0.8, 0.8).into(),
+            (0.8, 0.2).into(),
+            (0.2, 0.8).into(),
+        ];
+        assert!(cut_triangles(&points, &[[0, 1, 2]], &bow_tie).is_err());
+    }
+
+    #[test]
+    fn test_sources_of_grid() {
+        // two triangles in every cell of a 10x10 grid
+        let size = 10;
+        let points: Vec<StaticPoint2d> = (0..=size)
+            .flat_map(|y| (0..=size).map(move |x| (x as f64, y as f64).into()))
+            .collect();
+        let corner = |x: usize, y: usize| y * (size + 1) + x;
+        let triangles: Vec<[usize; 3]> = (0..size)
+            .flat_map(|y| (0..size).map(move |x| (x, y)))
+            .flat_map(|(x, y)| {
+                [
+                    [corner(x, y), corner(x + 1, y), corner(x + 1, y + 1)],
+                    [corner(x, y), corner(x + 1, y + 1), corner(x, y + 1)],
+                ]
+            })
+            .collect();
+        let polygon: Vec<StaticPoint2d> = vec![
+            (2.5, 2.2).into(),
+            (7.3, 3.1).into(),
+            (6.6, 8.4).into(),
+            (3.2, 7.7).into(),
+        ];
+        let cut = cut_triangles(&points, &triangles, &polygon).expect("Cut failed");
+        // the parts of every mesh triangle cover it
+        let mut covered = vec![0.0; triangles.len()];
+        for (parts, sources) in [
+            (cut.inside(), cut.inside_sources()),
+            (cut.outside(), cut.outside_sources()),
+        ] {
+            for (part, source) in parts.iter().zip(sources) {
+                covered[*source] += area(&cut, std::slice::from_ref(part));
+            }
+        }
+        for area in covered {
+            assert!((area - 0.5).abs() < 1e-9);
+        }
+        assert!((area(&cut, cut.inside()) - polygon.signed_area().0.abs()).abs() < 1e-9);
+    }
+}
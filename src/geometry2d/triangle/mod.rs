@@ -8,19 +8,22 @@ use itertools::Itertools;
 use log::error;
 use num_traits::{One, Zero};
 use ordered_float::OrderedFloat;
-use triangulate::{formats, ListFormat, Polygon, PolygonList};
+use triangulate::{formats, ListFormat, PolygonList};
 
 use crate::generic_triangle::{TriangleCornerPoint, TriangleSide};
+use crate::geometry2d::delaunay::constrained_delaunay;
 use crate::geometry2d::line::StaticLine2d;
 use crate::geometry2d::point::PointOrPoint;
+use crate::geometry2d::polygon::boolean::{boolean_operation, BooleanOperation};
 use crate::geometry2d::polygon::cut::PointPolygonRelationship;
+use crate::geometry2d::polygon::with_holes::{PolygonWithHoles, TriangulationFailed};
 use crate::geometry2d::triangle::found_triangle::{
     found_cross_point, found_original_triangle, found_polygon_point, found_triangle_corner,
     FoundPoint, FoundTriangle,
 };
 use crate::{
     geometry2d::{
-        line::{Line2d, LineIntersection, ReferenceLine2d, SideOfLine},
+        line::{Line2d, ReferenceLine2d, SideOfLine},
         point::{Point2d, StaticPoint2d},
         polygon::{
             cut::{CutSegment, LineCutIdx, PointRange, PolygonPath},
@@ -34,8 +37,12 @@ use crate::{
 
 mod cut;
 mod found_triangle;
+pub mod mesh_cut;
 pub mod static_triangle;
 
+/// outside and inside triangles of a cut
+pub type CutTriangles<'a, T, P, Poly, Pt> = [Vec<FoundTriangle<'a, T, P, Poly, Pt>>; 2];
+
 pub trait Triangle2d<P: Point2d>: Sized + Polygon2d<P> + PartialEq {
     fn p1(&self) -> &P;
     fn p2(&self) -> &P;
@@ -63,6 +70,10 @@ pub trait Triangle2d<P: Point2d>: Sized + Polygon2d<P> + PartialEq {
             TriangleSide::S3 => self.l3(),
         }
     }
+    /// the three sides as lines, starting at `p1`
+    fn sides(&self) -> TriangleLineIterator<'_, Self, P> {
+        TriangleLineIterator::new(self)
+    }
     fn gravity_point(&self) -> StaticPoint2d {
         let StaticPoint2d { x: x1, y: y1 } = self.p1().coordinates();
         let StaticPoint2d { x: x2, y: y2 } = self.p2().coordinates();
@@ -97,11 +108,12 @@ pub trait Triangle2d<P: Point2d>: Sized + Polygon2d<P> + PartialEq {
     ) -> Option<(TriangleSide, Number, Number)> {
         self.lines()
             .enumerate()
-            .filter_map::<(TriangleSide, Number, Number), _>(|(idx, tr_line)| {
+            .flat_map(|(idx, tr_line)| {
                 let l: ReferenceLine2d<P> = tr_line;
-                let x = l.intersect(line);
-                <LineIntersection as Into<Option<(Number, Number)>>>::into(x)
-                    .map(|(triangle_pos, polygon_pos)| (idx.into(), triangle_pos, polygon_pos))
+                l.intersect(line)
+                    .positions()
+                    .into_iter()
+                    .map(move |(triangle_pos, polygon_pos)| (idx.into(), triangle_pos, polygon_pos))
             })
             .max_by_key(|(_, pos, _)| (*pos).min(Number::one() - *pos))
     }
@@ -112,10 +124,14 @@ pub trait Triangle2d<P: Point2d>: Sized + Polygon2d<P> + PartialEq {
         let mut edges: Vec<_> = self
             .lines()
             .enumerate()
-            .filter_map::<(TriangleSide, Number, Number), _>(|(idx, tr_line)| {
-                <LineIntersection as Into<Option<(Number, Number)>>>::into(tr_line.intersect(line))
+            .flat_map(|(idx, tr_line)| {
+                // a line along the side leaves it at the ends of the overlap
+                tr_line
+                    .intersect(line)
+                    .positions()
+                    .into_iter()
                     .filter(|(t, _)| *t > Number::zero() && *t < Number::one())
-                    .map(|(triangle_pos, polygon_pos)| (idx.into(), triangle_pos, polygon_pos))
+                    .map(move |(triangle_pos, polygon_pos)| (idx.into(), triangle_pos, polygon_pos))
             })
             .collect();
         edges.sort_by_key(|(_, pos, _)| -(*pos).min(Number::one() - *pos));
@@ -124,6 +140,13 @@ pub trait Triangle2d<P: Point2d>: Sized + Polygon2d<P> + PartialEq {
 
     fn cut<Poly: Polygon2d<Pt>, Pt: Point2d>(&self, cut_polygon: &Poly) -> PolygonPath {
         assert!(self.area() > OrderedFloat::zero());
+        if !cut_polygon.is_simple() {
+            error!("Cut polygon is not simple: {cut_polygon:?}");
+            return PolygonPath::NotSimple;
+        }
+        if is_touching(self, cut_polygon) {
+            return PolygonPath::Touching;
+        }
         let mut poly_point_iter = cut_polygon.points().enumerate();
         if let Some((_, first_poly_pt)) = poly_point_iter.next() {
             let mut last_poly_pt = first_poly_pt;
@@ -196,7 +219,7 @@ pub trait Triangle2d<P: Point2d>: Sized + Polygon2d<P> + PartialEq {
                             Some((last_idx_begin, last_pt_begin)),
                             Some((_, last_pt_end)),
                         ) = (
-                            polygon.get(0),
+                            polygon.first(),
                             polygon.get(1),
                             polygon.get(point_count - 2),
                             polygon.get(point_count - 1),
@@ -298,8 +321,17 @@ pub trait Triangle2d<P: Point2d>: Sized + Polygon2d<P> + PartialEq {
         &'a self,
         cut_polygon: &'a Poly,
         path: &PolygonPath,
-    ) -> [Vec<FoundTriangle<'a, Self, P, Poly, Pt>>; 2] {
-        match path {
+    ) -> Result<CutTriangles<'a, Self, P, Poly, Pt>, TriangulationFailed> {
+        self.triangulate_cut_polygons_with(cut_polygon, path, TriangulationMethod::default())
+    }
+    /// Outside and inside triangles of the cut, fails if the parts cannot be triangulated
+    fn triangulate_cut_polygons_with<'a, Poly: Polygon2d<Pt> + 'a, Pt: Point2d>(
+        &'a self,
+        cut_polygon: &'a Poly,
+        path: &PolygonPath,
+        method: TriangulationMethod,
+    ) -> Result<CutTriangles<'a, Self, P, Poly, Pt>, TriangulationFailed> {
+        Ok(match path {
             PolygonPath::Enclosed => {
                 let outer_polygon: Vec<PointOrPoint<P, Pt>> =
                     self.points().map(|p| PointOrPoint::P1(p.clone())).collect();
@@ -308,13 +340,7 @@ pub trait Triangle2d<P: Point2d>: Sized + Polygon2d<P> + PartialEq {
                     .map(|p| PointOrPoint::P2(p.clone()))
                     .collect();
                 let outer_shape = vec![outer_polygon, inner_polygon];
-                let mut outer_triangulated_indices = Vec::<[usize; 2]>::new();
-                let outer_triangles = outer_shape
-                    .triangulate(
-                        formats::IndexedListFormat::new(&mut outer_triangulated_indices)
-                            .into_fan_format(),
-                    )
-                    .expect("Error triangulate outer polygons");
+                let outer_triangles = triangulate_rings(&outer_shape, method)?;
                 let point_iter = outer_triangles.iter().map(|p| match p[0] {
                     0 => {
                         found_triangle_corner(p[1].try_into().expect("Invalid corner index"), self)
@@ -339,21 +365,16 @@ pub trait Triangle2d<P: Point2d>: Sized + Polygon2d<P> + PartialEq {
                 } else */
                 {
                     let point_list: Vec<_> = cut_polygon.points().cloned().collect();
-                    let mut inner_triangulated_indices = Vec::<usize>::new();
-                    let inner_triangles = point_list
-                        .triangulate(
-                            formats::IndexedListFormat::new(&mut inner_triangulated_indices)
-                                .into_fan_format(),
-                        )
-                        .expect("Error triangulate inner polygons");
-                     static_triangles_from_points(inner_triangles.iter().map(|p| found_polygon_point(*p, cut_polygon)))
+                    let inner_triangles = triangulate_rings(&vec![point_list], method)?;
+                     static_triangles_from_points(inner_triangles.iter().map(|[_, p]| found_polygon_point(*p, cut_polygon)))
                 };
                 [final_outer_triangles, inner_triangles]
             }
             PolygonPath::CutSegments(segments) => {
                 let [first_edge_triangles, other_side_triangles] = walk_shape_recursive(segments)
                     .map(|p| {
-                        Iterator::flat_map(p.iter(), |polygon| {
+                        let mut triangles = Vec::new();
+                        for polygon in p.iter() {
                             let mut remaining_polygon =
                                 Iterator::map(polygon.iter().dedup(), |point| match point {
                                     TraceResultPoint::Corner(p) => found_triangle_corner(*p, self),
@@ -378,23 +399,24 @@ pub trait Triangle2d<P: Point2d>: Sized + Polygon2d<P> + PartialEq {
                                 })
                                 .collect::<Vec<_>>();
                             remaining_polygon.dedup();
-                            let mut triangulated_indices = Vec::<usize>::new();
-                            static_triangles_from_points(
-                                remaining_polygon
-                                    .triangulate(
-                                        formats::IndexedListFormat::new(&mut triangulated_indices)
-                                            .into_fan_format(),
-                                    )
-                                    .expect("Error on triangulate")
+                            let remaining_polygon = vec![remaining_polygon];
+                            triangles.extend(static_triangles_from_points(
+                                triangulate_rings(&remaining_polygon, method)?
                                     .iter()
-                                    .map(|p| remaining_polygon[*p].clone()),
-                            )
-                        })
-                        .collect::<Vec<_>>()
+                                    .map(|[_, p]| remaining_polygon[0][*p].clone()),
+                            ));
+                        }
+                        Ok(triangles)
                     });
-                let sample_triangle = first_edge_triangles
-                    .get(0)
-                    .expect("Unexpected empty result");
+                let [first_edge_triangles, other_side_triangles] =
+                    [first_edge_triangles?, other_side_triangles?];
+                let Some(sample_triangle) = first_edge_triangles.first() else {
+                    return self.triangulate_cut_polygons_with(
+                        cut_polygon,
+                        &PolygonPath::Touching,
+                        method,
+                    );
+                };
                 match cut_polygon.point_position(&sample_triangle.gravity_point()) {
                     PointPolygonRelationship::Inside => {
                         [other_side_triangles, first_edge_triangles]
@@ -402,22 +424,183 @@ pub trait Triangle2d<P: Point2d>: Sized + Polygon2d<P> + PartialEq {
                     PointPolygonRelationship::Outside => {
                         [first_edge_triangles, other_side_triangles]
                     }
-                    PointPolygonRelationship::OnEdge => panic!("Unexpected position"),
+                    PointPolygonRelationship::OnEdge => self.triangulate_cut_polygons_with(
+                        cut_polygon,
+                        &PolygonPath::Touching,
+                        method,
+                    )?,
+                }
+            }
+            PolygonPath::Touching => {
+                // the boolean operations handle shared corners and sides
+                let [outside, inside] =
+                    [BooleanOperation::Difference, BooleanOperation::Intersection]
+                        .map(|operation| boolean_operation(self, cut_polygon, operation));
+                if inside.is_empty() {
+                    return Ok([vec![found_original_triangle(self)], vec![]]);
+                }
+                if outside.is_empty() {
+                    return Ok([vec![], vec![found_original_triangle(self)]]);
                 }
+                let [outside, inside] = [outside, inside].map(|rings| {
+                    let mut triangles = Vec::new();
+                    for polygon in PolygonWithHoles::from_rings(rings) {
+                        let triangulation = polygon.triangulate_delaunay(&[])?;
+                        triangles.extend(triangulation.static_triangles().map(|t| {
+                            FoundTriangle::Cut(StaticTriangle2d::new(
+                                found_point_at(self, cut_polygon, t.p1),
+                                found_point_at(self, cut_polygon, t.p2),
+                                found_point_at(self, cut_polygon, t.p3),
+                            ))
+                        }));
+                    }
+                    Ok::<_, TriangulationFailed>(triangles)
+                });
+                [outside?, inside?]
             }
+            PolygonPath::NotSimple => [vec![found_original_triangle(self)], vec![]],
             PolygonPath::None => match cut_polygon.point_position(&self.gravity_point()) {
                 PointPolygonRelationship::Inside => [vec![], vec![found_original_triangle(self)]],
                 PointPolygonRelationship::Outside => [vec![found_original_triangle(self)], vec![]],
-                PointPolygonRelationship::OnEdge => panic!("Unexpected position"),
+                PointPolygonRelationship::OnEdge => {
+                    self.triangulate_cut_polygons_with(cut_polygon, &PolygonPath::Touching, method)?
+                }
             },
-        }
+        })
     }
     fn cut_to_triangles<'a, Poly: Polygon2d<Pt>, Pt: Point2d>(
         &'a self,
         cut_polygon: &'a Poly,
-    ) -> [Vec<FoundTriangle<'a, Self, P, Poly, Pt>>; 2] {
+    ) -> Result<CutTriangles<'a, Self, P, Poly, Pt>, TriangulationFailed> {
         self.triangulate_cut_polygons(cut_polygon, &self.cut(cut_polygon))
     }
+    fn cut_to_triangles_with<'a, Poly: Polygon2d<Pt>, Pt: Point2d>(
+        &'a self,
+        cut_polygon: &'a Poly,
+        method: TriangulationMethod,
+    ) -> Result<CutTriangles<'a, Self, P, Poly, Pt>, TriangulationFailed> {
+        self.triangulate_cut_polygons_with(cut_polygon, &self.cut(cut_polygon), method)
+    }
+}
+
+/// a polygon point lies on a side of the triangle or a corner of the triangle on a polygon side
+fn is_touching<P: Point2d, T: Triangle2d<P>, Pt: Point2d, Poly: Polygon2d<Pt>>(
+    triangle: &T,
+    cut_polygon: &Poly,
+) -> bool {
+    let on_line = |line: (StaticPoint2d, StaticPoint2d), p: StaticPoint2d| {
+        let (start, end) = line;
+        (&start, &end).side_of_pt(&p) == SideOfLine::Hit
+            && (p - start).dot(end - start) >= Number::zero()
+            && (p - end).dot(start - end) >= Number::zero()
+    };
+    cut_polygon.points().any(|p| {
+        triangle.lines().any(|side| {
+            on_line(
+                (side.p1().coordinates(), side.p2().coordinates()),
+                p.coordinates(),
+            )
+        })
+    }) || cut_polygon.lines().any(|side| {
+        triangle.points().any(|corner| {
+            on_line(
+                (side.p1().coordinates(), side.p2().coordinates()),
+                corner.coordinates(),
+            )
+        })
+    })
+}
+
+/// The point of the cut result at the coordinates, which are a triangle corner, a polygon point
+/// or lie on a triangle side and a polygon side.
+fn found_point_at<'a, P: Point2d, T: Triangle2d<P>, Pt: Point2d, Poly: Polygon2d<Pt>>(
+    triangle: &'a T,
+    cut_polygon: &'a Poly,
+    p: StaticPoint2d,
+) -> FoundPoint<'a, P, Pt, T, Poly> {
+    if let Some(corner) = [
+        TriangleCornerPoint::P1,
+        TriangleCornerPoint::P2,
+        TriangleCornerPoint::P3,
+    ]
+    .into_iter()
+    .find(|corner| triangle.point(*corner).coordinates() == p)
+    {
+        return found_triangle_corner(corner, triangle);
+    }
+    if let Some(idx) = cut_polygon.points().position(|q| q.coordinates() == p) {
+        return found_polygon_point(idx, cut_polygon);
+    }
+    let distance = |(p1, p2): (StaticPoint2d, StaticPoint2d)| {
+        let direction = p2 - p1;
+        let along = ((p - p1).dot(direction) / direction.magnitude_squared())
+            .clamp(Number::zero(), Number::one());
+        (p1 + direction * along - p).magnitude()
+    };
+    let triangle_side = triangle
+        .lines()
+        .map(|side| distance((side.p1().coordinates(), side.p2().coordinates())))
+        .enumerate()
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(idx, _)| idx)
+        .expect("Triangle has sides");
+    let polygon_side = cut_polygon
+        .lines()
+        .map(|side| distance((side.p1().coordinates(), side.p2().coordinates())))
+        .enumerate()
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(idx, _)| idx)
+        .expect("Polygon has sides");
+    found_cross_point(triangle_side.into(), triangle, polygon_side, cut_polygon, p)
+}
+
+/// How the polygons left over by a cut are split into triangles
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum TriangulationMethod {
+    /// fan triangulation of the `triangulate` crate, fast but creates thin slivers
+    #[default]
+    Fan,
+    /// constrained Delaunay triangulation, maximizes the smallest angle
+    ConstrainedDelaunay,
+}
+
+/// triangle corners as ring and point index, three per triangle
+fn triangulate_rings<Pt: Point2d>(
+    rings: &Vec<Vec<Pt>>,
+    method: TriangulationMethod,
+) -> Result<Vec<[usize; 2]>, TriangulationFailed> {
+    match method {
+        TriangulationMethod::Fan => {
+            let mut triangulated_indices = Vec::<[usize; 2]>::new();
+            rings
+                .triangulate(
+                    formats::IndexedListFormat::new(&mut triangulated_indices).into_fan_format(),
+                )
+                .map_err(|error| TriangulationFailed(error.to_string()))?;
+            Ok(triangulated_indices)
+        }
+        TriangulationMethod::ConstrainedDelaunay => {
+            let triangulation = constrained_delaunay(rings, &[])?;
+            triangulation
+                .triangles()
+                .iter()
+                .flatten()
+                .map(|p| {
+                    let mut idx = *p;
+                    for (ring_idx, ring) in rings.iter().enumerate() {
+                        if idx < ring.len() {
+                            return Ok([ring_idx, idx]);
+                        }
+                        idx -= ring.len();
+                    }
+                    Err(TriangulationFailed(format!(
+                        "Rings cross each other at {:?}",
+                        triangulation.points()[*p]
+                    )))
+                })
+                .collect()
+        }
+    }
 }
 fn static_triangles_from_points<
     't,
@@ -434,9 +617,7 @@ fn static_triangles_from_points<
         .into_iter()
         .filter_map(|mut tr| {
             if let (Some(p1), Some(p2), Some(p3)) = (tr.next(), tr.next(), tr.next()) {
-                Some(FoundTriangle::FoundTriangle(StaticTriangle2d::new(
-                    p1, p2, p3,
-                )))
+                Some(FoundTriangle::Cut(StaticTriangle2d::new(p1, p2, p3)))
             } else {
                 None
             }
@@ -524,6 +705,16 @@ pub struct TriangleLineIterator<'a, T: Triangle2d<Pt>, Pt: Point2d> {
     state: TriangleIteratorState,
 }
 
+impl<'a, T: Triangle2d<Pt>, Pt: Point2d> TriangleLineIterator<'a, T, Pt> {
+    pub fn new(triangle: &'a T) -> Self {
+        Self {
+            phantom: Default::default(),
+            triangle,
+            state: TriangleIteratorState::P1,
+        }
+    }
+}
+
 pub struct TriangleLine<'a, T: Triangle2d<Pt>, Pt: Point2d> {
     phantom: PhantomData<Pt>,
     triangle: &'a T,
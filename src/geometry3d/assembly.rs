@@ -0,0 +1,445 @@
+use std::sync::{Arc, OnceLock};
+
+use thiserror::Error;
+
+use crate::geometry3d::line::Line3d;
+use crate::geometry3d::plane::Plane3d;
+use crate::geometry3d::point::bounding_box::BoundingBox3d;
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::transform::Transform3d;
+use crate::geometry3d::triangle::Triangle3d;
+use crate::geometry3d::triangles::topology::owned::PlanePicking;
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::geometry3d::Vector3d;
+
+/// Shared meshes placed by a tree of named instances, e.g. the panels, fronts and hardware of a
+/// cabinet.
+///
+/// Parts and instances are addressed by the index returned when they were added. Parts may be
+/// open meshes, their triangles are grouped by plane when a part is hit-tested first.
+#[derive(Debug, Clone)]
+pub struct Assembly<P: Point3d> {
+    parts: Vec<Arc<IndexedTriangleList<P>>>,
+    /// built on the first intersection test of the part
+    picking: Vec<OnceLock<PlanePicking>>,
+    instances: Vec<Instance>,
+}
+
+/// Node of the assembly tree, placed relative to its parent
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    name: String,
+    transform: Transform3d,
+    part: Option<usize>,
+    parent: Option<usize>,
+}
+
+/// Result of [`Assembly::find_first_intersection`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AssemblyHit {
+    instance: usize,
+    triangle: usize,
+    point: Vector3d,
+    plane: Plane3d,
+}
+
+#[derive(Error, Debug, Eq, PartialEq, Copy, Clone)]
+pub enum AssemblyError {
+    #[error("Unknown part {0}")]
+    UnknownPart(usize),
+    #[error("Unknown instance {0}")]
+    UnknownInstance(usize),
+}
+
+impl Instance {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// placement relative to the parent instance
+    pub fn transform(&self) -> Transform3d {
+        self.transform
+    }
+    /// the placed part, `None` for instances only grouping their children
+    pub fn part(&self) -> Option<usize> {
+        self.part
+    }
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+}
+
+impl AssemblyHit {
+    pub fn instance(&self) -> usize {
+        self.instance
+    }
+    /// index of the triangle in the mesh of the part
+    pub fn triangle(&self) -> usize {
+        self.triangle
+    }
+    /// hit point in world coordinates
+    pub fn point(&self) -> Vector3d {
+        self.point
+    }
+    /// plane of the hit triangle in world coordinates
+    pub fn plane(&self) -> Plane3d {
+        self.plane
+    }
+}
+
+impl<P: Point3d> Default for Assembly<P> {
+    fn default() -> Self {
+        Self {
+            parts: Vec::new(),
+            picking: Vec::new(),
+            instances: Vec::new(),
+        }
+    }
+}
+
+impl<P: Point3d> Assembly<P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// adds a mesh which can be placed by several instances
+    pub fn add_part(&mut self, mesh: Arc<IndexedTriangleList<P>>) -> usize {
+        self.parts.push(mesh);
+        self.picking.push(OnceLock::new());
+        self.parts.len() - 1
+    }
+
+    /// Adds an instance below the parent or at the top level, instances without part group
+    /// their children.
+    pub fn add_instance(
+        &mut self,
+        parent: Option<usize>,
+        name: impl Into<String>,
+        transform: Transform3d,
+        part: Option<usize>,
+    ) -> Result<usize, AssemblyError> {
+        if let Some(parent) = parent.filter(|parent| *parent >= self.instances.len()) {
+            return Err(AssemblyError::UnknownInstance(parent));
+        }
+        if let Some(part) = part.filter(|part| *part >= self.parts.len()) {
+            return Err(AssemblyError::UnknownPart(part));
+        }
+        self.instances.push(Instance {
+            name: name.into(),
+            transform,
+            part,
+            parent,
+        });
+        Ok(self.instances.len() - 1)
+    }
+
+    pub fn parts(&self) -> &[Arc<IndexedTriangleList<P>>] {
+        &self.parts
+    }
+    pub fn instances(&self) -> &[Instance] {
+        &self.instances
+    }
+
+    /// direct children of the instance, or the top level instances for `None`
+    pub fn children(&self, parent: Option<usize>) -> Vec<usize> {
+        (0..self.instances.len())
+            .filter(|idx| self.instances[*idx].parent == parent)
+            .collect()
+    }
+
+    /// names from the top level down to the instance, separated by `/`
+    pub fn path(&self, instance: usize) -> String {
+        let mut names: Vec<&str> = self
+            .ancestors(instance)
+            .map(|idx| self.instances[idx].name.as_str())
+            .collect();
+        names.reverse();
+        names.join("/")
+    }
+
+    /// the first instance with the given path
+    pub fn find(&self, path: &str) -> Option<usize> {
+        (0..self.instances.len()).find(|idx| self.path(*idx) == path)
+    }
+
+    /// placement of the instance in world coordinates
+    pub fn world_transform(&self, instance: usize) -> Transform3d {
+        self.ancestors(instance)
+            .fold(Transform3d::identity(), |transform, idx| {
+                transform.then(&self.instances[idx].transform)
+            })
+    }
+
+    /// box around the placed points of the instance and all instances below it
+    pub fn bounding_box(&self, instance: usize) -> BoundingBox3d {
+        self.placed_parts()
+            .filter(|(idx, _, _)| self.ancestors(*idx).any(|ancestor| ancestor == instance))
+            .fold(BoundingBox3d::Empty, |bbox, (_, transform, part)| {
+                self.parts[part]
+                    .points()
+                    .iter()
+                    .fold(bbox, |bbox, p| bbox + transform.apply_point(p))
+            })
+    }
+
+    /// Merges all placed parts into one mesh in world coordinates.
+    ///
+    /// Vertex attributes and materials of the parts are kept, every triangle gets the path of its
+    /// instance as group.
+    pub fn flatten(&self) -> IndexedTriangleList<Vector3d> {
+        let mut builder = IndexedTriangleList::<Vector3d>::builder::<Vector3d>();
+        let mut names = Vec::new();
+        for (instance, transform, part) in self.placed_parts() {
+            let mesh = transform.apply_mesh(&self.parts[part]);
+            let offset = builder.point_count();
+            let vertex_attributes = mesh.vertex_attributes();
+            for (idx, p) in mesh.points().iter().enumerate() {
+                let point = builder.append_point(*p);
+                if let Some(normals) = vertex_attributes.normals() {
+                    builder.attributes.set_normal(point, normals[idx]);
+                }
+                if let Some(uvs) = vertex_attributes.uvs() {
+                    builder.attributes.set_uv(point, uvs[idx]);
+                }
+                if let Some(colors) = vertex_attributes.colors() {
+                    builder.attributes.set_color(point, colors[idx]);
+                }
+            }
+            let group = names.len() as u32;
+            names.push(self.path(instance));
+            for (idx, triangle) in mesh.triangles().iter().enumerate() {
+                let [p1, p2, p3] = triangle.points().map(|p| p.idx() + offset);
+                let triangle = builder.append_indexed_triangle(p1, p2, p3);
+                if let Some(materials) = mesh.face_attributes().materials() {
+                    builder.attributes.set_material(triangle, materials[idx]);
+                }
+                builder.attributes.set_group_index(triangle, group);
+            }
+        }
+        builder.attributes.set_group_names(names);
+        builder.build()
+    }
+
+    /// Closest hit of the sight line on any placed part, with the same rules as
+    /// [`OwnedTriangleTopology::find_first_intersection`], only triangles facing the start of the
+    /// line are hit. Instances flattened to zero volume are skipped.
+    ///
+    /// [`OwnedTriangleTopology::find_first_intersection`]:
+    ///     crate::prelude::OwnedTriangleTopology::find_first_intersection
+    pub fn find_first_intersection<L: Line3d<Pt>, Pt: Point3d>(
+        &self,
+        sight_line: &L,
+    ) -> Option<AssemblyHit> {
+        let start = sight_line.p1().coordinates();
+        let direction = sight_line.direction();
+        let mut best = None;
+        for (instance, transform, part) in self.placed_parts() {
+            let Some(inverse) = transform.inverse() else {
+                continue;
+            };
+            // the affine transformation keeps the position along the line
+            let local_line = inverse.apply_line(sight_line);
+            let picking = self.picking[part].get_or_init(|| PlanePicking::new(&self.parts[part]));
+            let Some((point, plane, triangle)) = picking.find_first_intersection(&local_line)
+            else {
+                continue;
+            };
+            let Ok(plane) = transform.apply_plane(plane) else {
+                continue;
+            };
+            let point = transform.apply_point(&point);
+            let along = (point - start).dot(direction);
+            if best
+                .as_ref()
+                .is_none_or(|(best_along, _)| along < *best_along)
+            {
+                best = Some((
+                    along,
+                    AssemblyHit {
+                        instance,
+                        triangle,
+                        point,
+                        plane,
+                    },
+                ));
+            }
+        }
+        best.map(|(_, hit)| hit)
+    }
+
+    /// the instance followed by its parent and so on
+    fn ancestors(&self, instance: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(Some(instance), |idx| self.instances[*idx].parent)
+    }
+
+    /// all instances with their world transformation and part
+    fn placed_parts(&self) -> impl Iterator<Item = (usize, Transform3d, usize)> + '_ {
+        self.instances
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, instance)| Some((idx, self.world_transform(idx), instance.part?)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use approx::assert_relative_eq;
+
+    use crate::geometry3d::assembly::{Assembly, AssemblyError};
+    use crate::geometry3d::line::static_line::StaticLine3d;
+    use crate::geometry3d::point::bounding_box::BoundingBox3d;
+    use crate::geometry3d::transform::Transform3d;
+    use crate::geometry3d::triangles::IndexedTriangleList;
+    use crate::prelude::{point_3d, Vector3d};
+    use crate::test::create_cube;
+
+    /// two unit cube panels side by side inside a moved cabinet and a third mirrored one
+    fn cabinet() -> Assembly<Vector3d> {
+        let mut assembly = Assembly::new();
+        let panel = assembly.add_part(Arc::new(create_cube(2)));
+        let cabinet = assembly
+            .add_instance(
+                None,
+                "cabinet",
+                Transform3d::translation(point_3d(10.0, 0.0, 0.0)),
+                None,
+            )
+            .expect("Valid instance");
+        for (name, x) in [("left", 0.0), ("right", 2.0)] {
+            assembly
+                .add_instance(
+                    Some(cabinet),
+                    name,
+                    Transform3d::translation(point_3d(x, 0.0, 0.0)),
+                    Some(panel),
+                )
+                .expect("Valid instance");
+        }
+        assembly
+            .add_instance(
+                None,
+                "mirrored",
+                Transform3d::scaling(point_3d(1.0, 1.0, -1.0)),
+                Some(panel),
+            )
+            .expect("Valid instance");
+        assembly
+    }
+
+    #[test]
+    fn test_tree() {
+        let mut assembly = cabinet();
+        let right = assembly.find("cabinet/right").expect("Missing instance");
+        assert_eq!("cabinet/right", assembly.path(right));
+        assert_eq!(vec![1, 2], assembly.children(Some(0)));
+        assert_eq!(vec![0, 3], assembly.children(None));
+        assert_eq!(
+            point_3d(12.0, 0.0, 0.0),
+            assembly
+                .world_transform(right)
+                .apply_point(&point_3d(0.0, 0.0, 0.0))
+        );
+        let cabinet_box =
+            BoundingBox3d::Empty + point_3d(10.0, 0.0, 0.0) + point_3d(13.0, 1.0, 1.0);
+        assert_eq!(cabinet_box, assembly.bounding_box(0));
+        let mirrored_box =
+            BoundingBox3d::Empty + point_3d(0.0, 0.0, -1.0) + point_3d(1.0, 1.0, 0.0);
+        assert_eq!(mirrored_box, assembly.bounding_box(3));
+        assert_eq!(
+            Err(AssemblyError::UnknownPart(1)),
+            assembly.add_instance(None, "missing", Transform3d::identity(), Some(1))
+        );
+        assert_eq!(
+            Err(AssemblyError::UnknownInstance(4)),
+            assembly.add_instance(Some(4), "orphan", Transform3d::identity(), None)
+        );
+    }
+
+    #[test]
+    fn test_flatten() {
+        let assembly = cabinet();
+        let flat = assembly.flatten();
+        let cube = create_cube(2);
+        assert_eq!(3 * cube.points().len(), flat.points().len());
+        assert_eq!(3 * cube.triangles().len(), flat.triangles().len());
+        let properties = flat.properties().expect("Closed meshes");
+        // the mirrored panel is flipped, so no volume cancels out
+        assert_relative_eq!(3.0, properties.volume().0, epsilon = 1e-12);
+        let faces = flat.face_attributes();
+        assert_eq!(
+            ["cabinet/left", "cabinet/right", "mirrored"],
+            faces.group_names()
+        );
+        assert_eq!(
+            Some("mirrored"),
+            faces.group_name(flat.triangles().len() - 1)
+        );
+    }
+
+    #[test]
+    fn test_pick() {
+        let assembly = cabinet();
+        // from the right through both panels of the cabinet
+        let line = StaticLine3d::new(point_3d(20.0, 0.3, 0.2), point_3d(-1.0, 0.0, 0.0));
+        let hit = assembly
+            .find_first_intersection(&line)
+            .expect("Missing hit");
+        assert_eq!(Some(2), assembly.find("cabinet/right"));
+        assert_eq!(2, hit.instance());
+        assert_relative_eq!(13.0, hit.point().x.0, epsilon = 1e-12);
+        assert_eq!(point_3d(1.0, 0.0, 0.0), hit.plane().normal());
+
+        // the mirrored panel is hit on its bottom face from below
+        let line = StaticLine3d::new(point_3d(0.3, 0.2, -5.0), point_3d(0.0, 0.0, 1.0));
+        let hit = assembly
+            .find_first_intersection(&line)
+            .expect("Missing hit");
+        assert_eq!(3, hit.instance());
+        assert_relative_eq!(-1.0, hit.point().z.0, epsilon = 1e-12);
+        assert_eq!(point_3d(0.0, 0.0, -1.0), hit.plane().normal());
+        assert!(assembly
+            .find_first_intersection(&StaticLine3d::new(
+                point_3d(5.0, 5.0, 5.0),
+                point_3d(0.0, 1.0, 0.0)
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn test_open_part() {
+        // a square facing upwards
+        let mut builder = IndexedTriangleList::<Vector3d>::builder::<Vector3d>();
+        let [a, b, c, d] = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+            .map(|(x, y)| builder.append_point(point_3d(x, y, 0.0)));
+        builder.append_indexed_triangle(a, b, c);
+        builder.append_indexed_triangle(a, c, d);
+        let mut assembly = cabinet();
+        let square = assembly.add_part(Arc::new(builder.build()));
+        let lid = assembly
+            .add_instance(
+                None,
+                "lid",
+                Transform3d::translation(point_3d(0.0, 0.0, 5.0)),
+                Some(square),
+            )
+            .expect("Valid instance");
+        assert_eq!(
+            BoundingBox3d::Empty + point_3d(0.0, 0.0, 5.0) + point_3d(1.0, 1.0, 5.0),
+            assembly.bounding_box(lid)
+        );
+        let panel = create_cube(2).triangles().len();
+        assert_eq!(3 * panel + 2, assembly.flatten().triangles().len());
+
+        let line = StaticLine3d::new(point_3d(0.7, 0.2, 10.0), point_3d(0.0, 0.0, -1.0));
+        let hit = assembly
+            .find_first_intersection(&line)
+            .expect("Missing hit");
+        assert_eq!(lid, hit.instance());
+        assert_eq!(0, hit.triangle());
+        assert_relative_eq!(5.0, hit.point().z.0, epsilon = 1e-12);
+        // the back of the square is not hit
+        let line = StaticLine3d::new(point_3d(0.7, 0.2, 2.0), point_3d(0.0, 0.0, 1.0));
+        assert!(assembly.find_first_intersection(&line).is_none());
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use thiserror::Error;
+use vek::Vec3;
+
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::triangles::IndexedTriangleList;
+
+#[derive(Error, Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ConvexHullError {
+    #[error("All points lie in one plane, the hull has no volume")]
+    Flat,
+}
+
+/// Closed convex hull with outward pointing triangles, built incrementally from a starting
+/// tetrahedron. Only the points on the hull corners are kept, points on hull faces or edges are
+/// left out.
+pub fn convex_hull_3d<P: Point3d, I: IntoIterator<Item = P>>(
+    points: I,
+) -> Result<IndexedTriangleList<P>, ConvexHullError> {
+    let points: Vec<P> = points.into_iter().collect();
+    let positions: Vec<Vec3<f64>> = points
+        .iter()
+        .map(|p| p.coordinates().map(|c| c.0))
+        .collect();
+    let size = positions
+        .iter()
+        .map(|p| p.map(f64::abs).reduce_partial_max())
+        .fold(0.0, f64::max);
+    let epsilon = size.max(1.0) * 1e-10;
+
+    let mut faces = hull_faces(&positions, epsilon).ok_or(ConvexHullError::Flat)?;
+    // points on hull edges or faces which came before the real corners are still part of the
+    // hull, a second run over the corners alone gets rid of them
+    let corners = corners(&positions, &faces);
+    if faces.iter().flatten().any(|p| !corners.contains(p)) {
+        let corners: Vec<_> = corners.into_iter().collect();
+        let corner_positions: Vec<_> = corners.iter().map(|p| positions[*p]).collect();
+        faces = hull_faces(&corner_positions, epsilon)
+            .ok_or(ConvexHullError::Flat)?
+            .into_iter()
+            .map(|face| face.map(|p| corners[p]))
+            .collect();
+    }
+
+    let mut builder = IndexedTriangleList::<P>::builder();
+    let mut new_idx = vec![None; points.len()];
+    let mut triangles = Vec::with_capacity(faces.len());
+    for face in faces {
+        triangles.push(face.map(|point| {
+            *new_idx[point].get_or_insert_with(|| builder.append_point(points[point].clone()))
+        }));
+    }
+    for [p1, p2, p3] in triangles {
+        builder.append_indexed_triangle(p1, p2, p3);
+    }
+    Ok(builder.build())
+}
+
+fn hull_faces(positions: &[Vec3<f64>], epsilon: f64) -> Option<Vec<[usize; 3]>> {
+    let [a, b, c, d] = start_tetrahedron(positions, epsilon)?;
+    let mut faces = vec![[a, b, c], [a, d, b], [b, d, c], [c, d, a]];
+    if distance(positions, faces[0], positions[d]) > 0.0 {
+        for face in faces.iter_mut() {
+            face.swap(1, 2);
+        }
+    }
+
+    for (idx, point) in positions.iter().enumerate() {
+        if [a, b, c, d].contains(&idx) {
+            continue;
+        }
+        let visible: Vec<_> = faces
+            .iter()
+            .map(|face| distance(positions, *face, *point) > epsilon)
+            .collect();
+        if !visible.contains(&true) {
+            continue;
+        }
+        let visible_edges: HashSet<_> = faces
+            .iter()
+            .zip(visible.iter())
+            .filter(|(_, visible)| **visible)
+            .flat_map(|(face, _)| (0..3).map(|i| (face[i], face[(i + 1) % 3])))
+            .collect();
+        // the edges whose other face stays form the horizon
+        let mut new_faces = Vec::new();
+        for (start, end) in visible_edges.iter() {
+            if !visible_edges.contains(&(*end, *start)) {
+                new_faces.push([*start, *end, idx]);
+            }
+        }
+        let mut visible_iter = visible.iter();
+        faces.retain(|_| !visible_iter.next().expect("One entry per face"));
+        faces.extend(new_faces);
+    }
+    Some(faces)
+}
+
+/// hull points whose face normals span all three dimensions, sorted by index
+fn corners(positions: &[Vec3<f64>], faces: &[[usize; 3]]) -> BTreeSet<usize> {
+    let mut normals = BTreeMap::<usize, Vec<Vec3<f64>>>::new();
+    for face in faces {
+        let [a, b, c] = face.map(|p| positions[p]);
+        let normal = (b - a).cross(c - a).normalized();
+        for point in face {
+            normals.entry(*point).or_default().push(normal);
+        }
+    }
+    normals
+        .into_iter()
+        .filter(|(_, normals)| {
+            let first = normals[0];
+            let Some(second) = normals
+                .iter()
+                .map(|n| first.cross(*n))
+                .find(|cross| cross.magnitude() > 1e-9)
+            else {
+                return false;
+            };
+            normals.iter().any(|n| second.dot(*n).abs() > 1e-9)
+        })
+        .map(|(point, _)| point)
+        .collect()
+}
+
+/// signed distance of the point above the face, multiplied by the doubled face area
+fn distance(positions: &[Vec3<f64>], face: [usize; 3], point: Vec3<f64>) -> f64 {
+    let [a, b, c] = face.map(|p| positions[p]);
+    (b - a).cross(c - a).dot(point - a)
+}
+
+fn start_tetrahedron(positions: &[Vec3<f64>], epsilon: f64) -> Option<[usize; 4]> {
+    let first = (0..positions.len()).min_by(|a, b| {
+        let (a, b) = (positions[*a], positions[*b]);
+        a.x.total_cmp(&b.x)
+            .then(a.y.total_cmp(&b.y))
+            .then(a.z.total_cmp(&b.z))
+    })?;
+    let farthest = |measure: &dyn Fn(Vec3<f64>) -> f64| {
+        (0..positions.len())
+            .map(|idx| (idx, measure(positions[idx])))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .filter(|(_, value)| *value > epsilon)
+            .map(|(idx, _)| idx)
+    };
+    let origin = positions[first];
+    let second = farthest(&|p| (p - origin).magnitude())?;
+    let direction = (positions[second] - origin).normalized();
+    let third = farthest(&|p| (p - origin).cross(direction).magnitude())?;
+    let normal = (positions[second] - origin)
+        .cross(positions[third] - origin)
+        .normalized();
+    let fourth = farthest(&|p| (p - origin).dot(normal).abs())?;
+    Some([first, second, third, fourth])
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, SeedableRng};
+
+    use crate::geometry3d::hull::{convex_hull_3d, ConvexHullError};
+    use crate::prelude::{point_3d, Point3d, Triangle3d, TriangleTopology, Vector3d};
+    use crate::test::create_cube;
+
+    #[test]
+    fn test_cube_points() {
+        let cube = create_cube(3);
+        let hull = convex_hull_3d(cube.points().iter().copied()).expect("Hull failed");
+        // only the corners remain, two triangles per side
+        assert_eq!(8, hull.points().len());
+        assert_eq!(12, hull.triangles().len());
+        TriangleTopology::new(&hull).expect("Hull is not closed");
+        let properties = hull.properties().expect("Hull is not closed");
+        assert!((properties.volume().0 - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_random_points() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(39);
+        let points: Vec<Vector3d> = (0..500)
+            .map(|_| {
+                point_3d(
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                )
+            })
+            .collect();
+        let hull = convex_hull_3d(points.iter().copied()).expect("Hull failed");
+        TriangleTopology::new(&hull).expect("Hull is not closed");
+        assert!(hull.validate().is_valid());
+        // every point is below every hull face
+        for triangle in hull.triangles() {
+            let [a, b, c] = triangle.points().map(|p| p.coordinates().map(|c| c.0));
+            let normal = (b - a).cross(c - a);
+            for p in points.iter() {
+                assert!(normal.dot(p.map(|c| c.0) - a) <= 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flat() {
+        let square = [
+            point_3d(0.0, 0.0, 1.0),
+            point_3d(1.0, 0.0, 1.0),
+            point_3d(1.0, 1.0, 1.0),
+            point_3d(0.0, 1.0, 1.0),
+        ];
+        assert_eq!(Some(ConvexHullError::Flat), convex_hull_3d(square).err());
+        assert!(convex_hull_3d(Vec::<Vector3d>::new()).is_err());
+    }
+}
@@ -1,7 +1,6 @@
 use crate::geometry3d::line::static_line::PointLine3d;
 use crate::geometry3d::line::Line3d;
 use crate::geometry3d::point::Point3d;
-use crate::geometry3d::triangles::indexed_point::IndexedPoint;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct ReferencedLine<'a, P: Point3d> {
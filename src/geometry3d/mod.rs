@@ -1,9 +1,12 @@
 use crate::prelude::Number;
 use vek::Vec3;
 
+pub mod assembly;
+pub mod hull;
 pub mod line;
 pub mod plane;
 pub mod point;
+pub mod transform;
 pub mod triangle;
 pub mod triangles;
 pub type Vector3d = Vec3<Number>;
@@ -34,7 +34,19 @@ impl Plane3d {
         }
 
         let normal = normal.normalized();
-        let distance = (normal.dot(v1));
+        let distance = normal.dot(v1);
+        Ok(Self { normal, distance })
+    }
+    /// plane through the point, the normal gets normalized
+    pub fn from_point_and_normal<P: Into<Vector3d>>(
+        point: P,
+        normal: Vector3d,
+    ) -> Result<Plane3d, InvalidPlane> {
+        if normal.magnitude() == Number::zero() {
+            return Err(InvalidPlane {});
+        }
+        let normal = normal.normalized();
+        let distance = normal.dot(point.into());
         Ok(Self { normal, distance })
     }
     #[inline]
@@ -120,13 +132,11 @@ impl Display for InvalidPlane {
 #[cfg(test)]
 mod test {
     use approx::assert_relative_eq;
-    use vek::approx::relative_eq;
     use vek::Quaternion;
 
     use crate::geometry3d::line::static_line::StaticLine3d;
     use crate::geometry3d::plane::{InvalidPlane, Plane3d, PlaneCutRelationship};
     use crate::geometry3d::point::point_3d;
-    use crate::prelude::AnyPolygon::StaticTrianglePolygon;
     use crate::prelude::Line3d;
     use crate::primitives::Number;
 
@@ -1,12 +1,12 @@
 use std::fmt::Debug;
 
-use log::info;
 use triangulate::Vertex;
 use vek::{Mat4, Vec3, Vec4};
 
 use crate::geometry2d::triangle::TrianglePointIterator;
 use crate::geometry3d::plane::Plane3d;
 use crate::geometry3d::point::{point_3d, Point3d};
+use crate::geometry3d::Vector3d;
 use crate::prelude::{Point2d, Polygon2d, StaticPoint2d, Triangle2d, Triangle3d};
 use crate::primitives::Number;
 
@@ -29,6 +29,12 @@ impl PlaneProjection {
     pub fn project_point<'a, Pt: Point3d>(&self, p: &'a Pt) -> ProjectedPoint2d<'a, Pt> {
         ProjectedPoint2d::project_point(p, &self.projection)
     }
+    /// back from the plane coordinates, `height` is the distance of the plane from the origin
+    pub fn lift_point<P: Point2d>(&self, p: &P, height: Number) -> Vector3d {
+        let StaticPoint2d { x, y } = p.coordinates();
+        let lifted = self.projection.map(|v| v.0).transposed() * Vec4::new(x.0, y.0, height.0, 1.0);
+        point_3d(lifted.x, lifted.y, lifted.z)
+    }
     pub fn project_triangle<'a, Pt: Point3d, T: Triangle3d<Pt> + Debug + Clone>(
         &self,
         triangle: &'a T,
@@ -47,7 +53,7 @@ impl<'a, P: Point3d> ProjectedPoint2d<'a, P> {
     pub fn project_point(p: &'a P, q: &Mat4<Number>) -> Self {
         let v4: Vec3<f64> = p.coordinates().map(|v| v.0);
 
-        let Vec4 { x, y, z, w } = q.map(|v| v.0) * Into::<Vec4<f64>>::into(v4);
+        let Vec4 { x, y, .. } = q.map(|v| v.0) * Into::<Vec4<f64>>::into(v4);
         //info!("z: {z}, w: {w}");
         Self {
             origin: p,
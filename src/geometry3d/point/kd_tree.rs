@@ -0,0 +1,232 @@
+use std::collections::BinaryHeap;
+
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::Vector3d;
+use crate::primitives::Number;
+
+/// Balanced k-d tree over borrowed points, the points are addressed by their index in the slice.
+///
+/// The tree only stores an order of the indices, so it can be built directly on
+/// [`IndexedTriangleList::points`](crate::prelude::IndexedTriangleList::points) without
+/// copying them.
+#[derive(Debug, Clone)]
+pub struct KdTree3d<'a, P: Point3d> {
+    points: &'a [P],
+    /// point indices, every range is split at its middle entry
+    order: Vec<usize>,
+    /// split axis of the middle entry of every range
+    axes: Vec<usize>,
+}
+
+impl<'a, P: Point3d> KdTree3d<'a, P> {
+    pub fn new(points: &'a [P]) -> Self {
+        let mut tree = Self {
+            points,
+            order: (0..points.len()).collect(),
+            axes: vec![0; points.len()],
+        };
+        tree.build(0, points.len());
+        tree
+    }
+
+    pub fn points(&self) -> &'a [P] {
+        self.points
+    }
+
+    /// indices of all points within the radius around `p` in ascending order
+    pub fn within_radius<Pt: Point3d>(&self, p: &Pt, radius: Number) -> Vec<usize> {
+        let p = p.coordinates();
+        let radius_square = radius * radius;
+        let mut found = Vec::new();
+        let mut stack = vec![(0, self.order.len())];
+        while let Some((start, end)) = stack.pop() {
+            if start >= end {
+                continue;
+            }
+            let middle = (start + end) / 2;
+            let idx = self.order[middle];
+            let q = self.coordinates(idx);
+            if q.distance_squared(p) <= radius_square {
+                found.push(idx);
+            }
+            let axis = self.axes[middle];
+            let offset = p[axis] - q[axis];
+            if offset <= radius {
+                stack.push((start, middle));
+            }
+            if offset >= -radius {
+                stack.push((middle + 1, end));
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+
+    /// the `k` closest points to `p` with their distances, the closest first
+    pub fn nearest<Pt: Point3d>(&self, p: &Pt, k: usize) -> Vec<(usize, Number)> {
+        let p = p.coordinates();
+        // farthest of the best candidates on top
+        let mut best: BinaryHeap<(Number, usize)> = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search_nearest(&p, k, 0, self.order.len(), &mut best);
+        }
+        best.into_sorted_vec()
+            .into_iter()
+            .map(|(distance_square, idx)| (idx, distance_square.sqrt().into()))
+            .collect()
+    }
+
+    /// Maps every point to the first point closer than the tolerance, which maps to itself.
+    ///
+    /// Useful to weld vertices of separately exported triangles.
+    pub fn weld_map(&self, tolerance: Number) -> Vec<usize> {
+        let mut map: Vec<Option<usize>> = vec![None; self.points.len()];
+        for idx in 0..self.points.len() {
+            if map[idx].is_some() {
+                continue;
+            }
+            for other in self.within_radius(&self.points[idx], tolerance) {
+                map[other].get_or_insert(idx);
+            }
+        }
+        map.into_iter()
+            .enumerate()
+            .map(|(idx, target)| target.unwrap_or(idx))
+            .collect()
+    }
+
+    fn search_nearest(
+        &self,
+        p: &Vector3d,
+        k: usize,
+        start: usize,
+        end: usize,
+        best: &mut BinaryHeap<(Number, usize)>,
+    ) {
+        if start >= end {
+            return;
+        }
+        let middle = (start + end) / 2;
+        let idx = self.order[middle];
+        let q = self.coordinates(idx);
+        best.push((q.distance_squared(*p), idx));
+        if best.len() > k {
+            best.pop();
+        }
+        let axis = self.axes[middle];
+        let offset = p[axis] - q[axis];
+        let (near, far) = if offset < Number::from(0.0) {
+            ((start, middle), (middle + 1, end))
+        } else {
+            ((middle + 1, end), (start, middle))
+        };
+        self.search_nearest(p, k, near.0, near.1, best);
+        let worst = best.peek().map(|(distance, _)| *distance);
+        if best.len() < k || worst.is_some_and(|worst| offset * offset <= worst) {
+            self.search_nearest(p, k, far.0, far.1, best);
+        }
+    }
+
+    fn build(&mut self, start: usize, end: usize) {
+        if end - start < 2 {
+            return;
+        }
+        let (min, max) = self.order[start..end].iter().fold(
+            (
+                self.coordinates(self.order[start]),
+                self.coordinates(self.order[start]),
+            ),
+            |(min, max), idx| {
+                let p = self.coordinates(*idx);
+                (Vector3d::partial_min(min, p), Vector3d::partial_max(max, p))
+            },
+        );
+        let extent = max - min;
+        let axis = (0..3).max_by_key(|axis| extent[*axis]).expect("Three axes");
+        let middle = (start + end) / 2;
+        let points = self.points;
+        self.order[start..end]
+            .select_nth_unstable_by_key(middle - start, |idx| points[*idx].coordinates()[axis]);
+        self.axes[middle] = axis;
+        self.build(start, middle);
+        self.build(middle + 1, end);
+    }
+
+    fn coordinates(&self, idx: usize) -> Vector3d {
+        self.points[idx].coordinates()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry3d::point::kd_tree::KdTree3d;
+    use crate::geometry3d::point::{point_3d, Point3d};
+    use crate::geometry3d::triangle::Triangle3d;
+    use crate::geometry3d::Vector3d;
+    use crate::test::create_cube;
+
+    /// points on a twisted spiral, so no axis is sorted already
+    fn spiral(count: usize) -> Vec<Vector3d> {
+        (0..count)
+            .map(|i| {
+                let t = i as f64 * 0.37;
+                point_3d(t.cos() * t, (t * 1.3).sin() * 5.0, (i % 17) as f64)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_queries_match_brute_force() {
+        let points = spiral(500);
+        let tree = KdTree3d::new(&points);
+        for center in [
+            point_3d(0.0, 0.0, 0.0),
+            points[321] + point_3d(0.5, -0.5, 1.5),
+        ] {
+            let expected: Vec<usize> = (0..points.len())
+                .filter(|idx| points[*idx].dist_square(&center) <= 16.0.into())
+                .collect();
+            assert!(!expected.is_empty());
+            assert_eq!(expected, tree.within_radius(&center, 4.0.into()));
+
+            let mut by_distance: Vec<usize> = (0..points.len()).collect();
+            by_distance.sort_by_key(|idx| (points[*idx].dist_square(&center), *idx));
+            let nearest: Vec<usize> = tree
+                .nearest(&center, 7)
+                .into_iter()
+                .map(|(idx, _)| idx)
+                .collect();
+            assert_eq!(by_distance[..7], nearest[..]);
+        }
+        assert!(KdTree3d::<Vector3d>::new(&[])
+            .nearest(&point_3d(0.0, 0.0, 0.0), 3)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_indexed_points() {
+        let cube = create_cube(2);
+        let tree = KdTree3d::new(cube.points());
+        let (corner, distance) = tree.nearest(&point_3d(1.1, 1.2, 1.0), 1)[0];
+        assert_eq!(point_3d(1.0, 1.0, 1.0), cube.points()[corner]);
+        assert!((distance.0 - 0.05f64.sqrt()).abs() < 1e-12);
+
+        // the same on the points referenced by the triangles
+        let triangles = cube.triangles();
+        let corners: Vec<_> = triangles
+            .iter()
+            .flat_map(|t| t.points().map(|p| *p))
+            .collect();
+        let tree = KdTree3d::new(&corners);
+        let (found, _) = tree.nearest(&point_3d(1.1, 1.2, 1.0), 1)[0];
+        assert_eq!(corner, corners[found].idx());
+
+        // every corner is used by several triangles
+        let welded = tree.weld_map(1e-9.into());
+        for (idx, target) in welded.iter().enumerate() {
+            assert!(*target <= idx);
+            assert_eq!(corners[idx].idx(), corners[*target].idx());
+            assert_eq!(*target, welded[*target]);
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt::Debug;
 use ordered_float::OrderedFloat;
 use stl_io::Vertex;
 pub mod bounding_box;
+pub mod kd_tree;
 
 use crate::geometry3d::Vector3d;
 use crate::primitives::Number;
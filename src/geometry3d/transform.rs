@@ -0,0 +1,319 @@
+use num_traits::Zero;
+use vek::{Mat3, Mat4, Quaternion};
+
+use crate::geometry3d::line::static_line::StaticLine3d;
+use crate::geometry3d::line::Line3d;
+use crate::geometry3d::plane::{InvalidPlane, Plane3d};
+use crate::geometry3d::point::bounding_box::{BoundingBox3d, BoundingBox3dValues};
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::geometry3d::Vector3d;
+use crate::primitives::Number;
+
+/// Affine transformation of 3D geometry, stored as homogeneous matrix.
+///
+/// Transformations with a negative determinant mirror the geometry, meshes get their triangles
+/// flipped then, so they keep pointing outwards.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transform3d {
+    matrix: Mat4<Number>,
+}
+
+impl Default for Transform3d {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl From<Quaternion<Number>> for Transform3d {
+    fn from(rotation: Quaternion<Number>) -> Self {
+        Self::rotation(rotation)
+    }
+}
+
+impl Transform3d {
+    pub fn identity() -> Self {
+        Self {
+            matrix: Mat4::identity(),
+        }
+    }
+    /// the last row has to be `0 0 0 1`
+    pub fn from_matrix(matrix: Mat4<Number>) -> Self {
+        Self { matrix }
+    }
+    pub fn translation(offset: Vector3d) -> Self {
+        Self {
+            matrix: Mat4::translation_3d(offset),
+        }
+    }
+    pub fn rotation(rotation: Quaternion<Number>) -> Self {
+        Self {
+            matrix: Mat4::from(rotation.normalized()),
+        }
+    }
+    /// scales along the axes, negative factors mirror
+    pub fn scaling(factors: Vector3d) -> Self {
+        Self {
+            matrix: Mat4::scaling_3d(factors),
+        }
+    }
+    /// reflection at the plane
+    pub fn mirror(plane: &Plane3d) -> Self {
+        let n = plane.normal();
+        let mut matrix = Mat4::identity();
+        for row in 0..3 {
+            for col in 0..3 {
+                matrix[(row, col)] -= n[row] * n[col] * 2.0;
+            }
+            matrix[(row, 3)] = n[row] * plane.distance() * 2.0;
+        }
+        Self { matrix }
+    }
+    /// applies this transformation first and `next` afterwards
+    pub fn then(&self, next: &Transform3d) -> Self {
+        Self {
+            matrix: (next.values() * self.values()).map(Number::from),
+        }
+    }
+    /// `None` for transformations flattening the geometry
+    pub fn inverse(&self) -> Option<Self> {
+        if self.determinant() == Number::zero() {
+            None
+        } else {
+            Some(Self {
+                matrix: self.values().inverted().map(Number::from),
+            })
+        }
+    }
+    pub fn matrix(&self) -> Mat4<Number> {
+        self.matrix
+    }
+    /// determinant of the linear part, the volume scale factor
+    pub fn determinant(&self) -> Number {
+        self.linear().determinant().into()
+    }
+    pub fn is_mirroring(&self) -> bool {
+        self.determinant() < Number::zero()
+    }
+
+    pub fn apply_point<P: Point3d>(&self, p: &P) -> Vector3d {
+        self.values()
+            .mul_point(p.coordinates().map(|v| v.0))
+            .map(Number::from)
+    }
+    /// transforms a direction, the translation is ignored
+    pub fn apply_direction(&self, direction: Vector3d) -> Vector3d {
+        self.values()
+            .mul_direction(direction.map(|v| v.0))
+            .map(Number::from)
+    }
+    /// transforms a surface normal by the inverse transposed matrix and normalizes it
+    pub fn apply_normal(&self, normal: Vector3d) -> Vector3d {
+        let linear = self.linear();
+        // the cofactor matrix is the inverse transposed scaled by the determinant
+        let cofactors = Mat3::new(
+            cofactor(&linear, 0, 0),
+            cofactor(&linear, 0, 1),
+            cofactor(&linear, 0, 2),
+            cofactor(&linear, 1, 0),
+            cofactor(&linear, 1, 1),
+            cofactor(&linear, 1, 2),
+            cofactor(&linear, 2, 0),
+            cofactor(&linear, 2, 1),
+            cofactor(&linear, 2, 2),
+        );
+        let transformed = cofactors * normal.map(|v| v.0);
+        let length = transformed.magnitude();
+        let normalized = if length == 0.0 {
+            transformed
+        } else if self.is_mirroring() {
+            -transformed / length
+        } else {
+            transformed / length
+        };
+        normalized.map(Number::from)
+    }
+    pub fn apply_line<P: Point3d, L: Line3d<P>>(&self, line: &L) -> StaticLine3d {
+        StaticLine3d::new(
+            self.apply_point(&line.p1()),
+            self.apply_direction(line.direction()),
+        )
+    }
+    pub fn apply_plane(&self, plane: &Plane3d) -> Result<Plane3d, InvalidPlane> {
+        let point = self.apply_point(&(plane.normal() * plane.distance()));
+        // points in front of the plane stay in front
+        Plane3d::from_point_and_normal(point, self.apply_normal(plane.normal()))
+    }
+    /// the box around the transformed corners
+    pub fn apply_bbox(&self, bbox: &BoundingBox3d) -> BoundingBox3d {
+        let BoundingBox3d::Box(BoundingBox3dValues {
+            min_x,
+            min_y,
+            min_z,
+            max_x,
+            max_y,
+            max_z,
+        }) = bbox
+        else {
+            return BoundingBox3d::Empty;
+        };
+        let mut result = BoundingBox3d::Empty;
+        for x in [min_x, max_x] {
+            for y in [min_y, max_y] {
+                for z in [min_z, max_z] {
+                    result += self.apply_point(&Vector3d::new(*x, *y, *z));
+                }
+            }
+        }
+        result
+    }
+    /// Transforms the points and vertex normals, mirrored meshes get flipped triangles, so the
+    /// normals of the triangles keep pointing to the same side as the vertex normals.
+    pub fn apply_mesh<P: Point3d>(
+        &self,
+        mesh: &IndexedTriangleList<P>,
+    ) -> IndexedTriangleList<Vector3d> {
+        let attributes = mesh.vertex_attributes().clone();
+        let attributes = match attributes.normals() {
+            Some(normals) => {
+                let normals: Vec<Vector3d> = normals
+                    .iter()
+                    .map(|normal| self.apply_normal(*normal))
+                    .collect();
+                attributes.with_normals(normals)
+            }
+            None => attributes,
+        };
+        let transformed = mesh
+            .clone()
+            .transform_points(|p| self.apply_point(p))
+            .with_vertex_attributes(attributes)
+            .expect("Same point count");
+        if self.is_mirroring() {
+            transformed.flipped()
+        } else {
+            transformed
+        }
+    }
+
+    fn values(&self) -> Mat4<f64> {
+        self.matrix.map(|v| v.0)
+    }
+    fn linear(&self) -> Mat3<f64> {
+        Mat3::from(self.values())
+    }
+}
+
+fn cofactor(matrix: &Mat3<f64>, row: usize, col: usize) -> f64 {
+    let rows = [(row + 1) % 3, (row + 2) % 3];
+    let cols = [(col + 1) % 3, (col + 2) % 3];
+    matrix[(rows[0], cols[0])] * matrix[(rows[1], cols[1])]
+        - matrix[(rows[0], cols[1])] * matrix[(rows[1], cols[0])]
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use vek::Quaternion;
+
+    use crate::geometry3d::plane::Plane3d;
+    use crate::geometry3d::point::bounding_box::{BoundingBox3d, BoundingBox3dValues};
+    use crate::geometry3d::transform::Transform3d;
+    use crate::prelude::{point_3d, Number, Vector3d};
+    use crate::test::create_cube;
+
+    fn assert_near(expected: Vector3d, actual: Vector3d) {
+        assert_relative_eq!(0.0, (expected - actual).magnitude().0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_rotate_and_move() {
+        let rotation = Transform3d::from(Quaternion::rotation_z(Number::from(
+            std::f64::consts::FRAC_PI_2,
+        )));
+        let transform = rotation.then(&Transform3d::translation(point_3d(1.0, 2.0, 3.0)));
+        assert_near(
+            point_3d(1.0, 3.0, 3.0),
+            transform.apply_point(&point_3d(1.0, 0.0, 0.0)),
+        );
+        assert_near(
+            point_3d(0.0, 1.0, 0.0),
+            transform.apply_direction(point_3d(1.0, 0.0, 0.0)),
+        );
+        let inverse = transform.inverse().expect("Not flat");
+        assert_near(
+            point_3d(1.0, 0.0, 0.0),
+            inverse.apply_point(&point_3d(1.0, 3.0, 3.0)),
+        );
+        assert!(!transform.is_mirroring());
+        assert_relative_eq!(1.0, transform.determinant().0, epsilon = 1e-12);
+        assert!(Transform3d::scaling(point_3d(1.0, 0.0, 1.0))
+            .inverse()
+            .is_none());
+    }
+
+    #[test]
+    fn test_plane_and_box() {
+        // normals stay perpendicular to the surface under non uniform scaling
+        let scaling = Transform3d::scaling(point_3d(2.0, 1.0, 1.0));
+        let plane = Plane3d::from_points(
+            point_3d(1.0, 0.0, 0.0),
+            point_3d(0.0, 1.0, 0.0),
+            point_3d(0.0, 0.0, 1.0),
+        )
+        .expect("Valid plane");
+        let expected = Plane3d::from_points(
+            point_3d(2.0, 0.0, 0.0),
+            point_3d(0.0, 1.0, 0.0),
+            point_3d(0.0, 0.0, 1.0),
+        )
+        .expect("Valid plane");
+        let transformed = scaling.apply_plane(&plane).expect("Valid plane");
+        assert_near(expected.normal(), transformed.normal());
+        assert!(expected.dist_square(&transformed).0 < 1e-20);
+
+        let bbox = BoundingBox3d::Empty + point_3d(0.0, 0.0, 0.0) + point_3d(1.0, 1.0, 1.0);
+        let rotation = Transform3d::rotation(Quaternion::rotation_z(Number::from(
+            std::f64::consts::FRAC_PI_4,
+        )));
+        let BoundingBox3d::Box(BoundingBox3dValues {
+            min_x,
+            max_x,
+            max_y,
+            ..
+        }) = rotation.apply_bbox(&bbox)
+        else {
+            panic!("Box expected")
+        };
+        assert_relative_eq!(-0.5f64.sqrt(), min_x.0, epsilon = 1e-12);
+        assert_relative_eq!(0.5f64.sqrt(), max_x.0, epsilon = 1e-12);
+        assert_relative_eq!(2f64.sqrt(), max_y.0, epsilon = 1e-12);
+        assert_eq!(
+            BoundingBox3d::Empty,
+            rotation.apply_bbox(&BoundingBox3d::Empty)
+        );
+    }
+
+    #[test]
+    fn test_mirror_mesh() {
+        let cube = create_cube(2);
+        let mirror = Transform3d::mirror(
+            &Plane3d::from_point_and_normal(point_3d(2.0, 0.0, 0.0), point_3d(1.0, 0.0, 0.0))
+                .expect("Valid plane"),
+        );
+        assert!(mirror.is_mirroring());
+        assert_near(
+            point_3d(3.0, 1.0, 1.0),
+            mirror.apply_point(&point_3d(1.0, 1.0, 1.0)),
+        );
+        let mirrored = mirror.apply_mesh(&cube);
+        let properties = mirrored.properties().expect("Closed mesh");
+        // flipped triangles keep the volume positive
+        assert_relative_eq!(1.0, properties.volume().0, epsilon = 1e-12);
+        assert_near(point_3d(3.5, 0.5, 0.5), properties.centroid());
+
+        let not_flipped = cube.clone().transform_points(|p| mirror.apply_point(p));
+        let properties = not_flipped.properties().expect("Closed mesh");
+        assert_relative_eq!(-1.0, properties.volume().0, epsilon = 1e-12);
+    }
+}
@@ -6,13 +6,13 @@ use crate::geometry3d::point::Point3d;
 pub trait Triangle3d<P: Point3d>: Sized + PartialEq {
     fn get_point(&self, p: TriangleCornerPoint) -> &P;
     fn points(&self) -> [&P; 3];
-    fn get_side(&self, side: TriangleSide) -> ReferencedLine<P> {
+    fn get_side(&self, side: TriangleSide) -> ReferencedLine<'_, P> {
         ReferencedLine::new(
             self.get_point(side.start_corner()),
             self.get_point(side.end_corner()),
         )
     }
-    fn sides(&self) -> [ReferencedLine<P>; 3] {
+    fn sides(&self) -> [ReferencedLine<'_, P>; 3] {
         let [p1, p2, p3] = self.points();
         [
             ReferencedLine::new(p1, p2),
@@ -0,0 +1,205 @@
+use thiserror::Error;
+use vek::Rgb;
+
+use crate::geometry2d::vector::Vector2d;
+use crate::geometry3d::Vector3d;
+
+pub type Color = Rgb<u8>;
+
+/// Optional values for every point of a triangle list
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct VertexAttributes {
+    normals: Option<Box<[Vector3d]>>,
+    uvs: Option<Box<[Vector2d]>>,
+    colors: Option<Box<[Color]>>,
+}
+
+/// Optional values for every triangle of a triangle list
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FaceAttributes {
+    materials: Option<Box<[u32]>>,
+    groups: Option<Box<[u32]>>,
+    group_names: Vec<String>,
+}
+
+#[derive(Error, Debug, Eq, PartialEq, Copy, Clone)]
+pub enum AttributeError {
+    #[error("Attribute {name} has {found} entries, expected {expected}")]
+    WrongLength {
+        name: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Group index {0} has no name")]
+    UnknownGroup(u32),
+}
+
+impl VertexAttributes {
+    pub fn normals(&self) -> Option<&[Vector3d]> {
+        self.normals.as_deref()
+    }
+    pub fn uvs(&self) -> Option<&[Vector2d]> {
+        self.uvs.as_deref()
+    }
+    pub fn colors(&self) -> Option<&[Color]> {
+        self.colors.as_deref()
+    }
+    pub fn with_normals(mut self, normals: impl Into<Box<[Vector3d]>>) -> Self {
+        self.normals = Some(normals.into());
+        self
+    }
+    pub fn with_uvs(mut self, uvs: impl Into<Box<[Vector2d]>>) -> Self {
+        self.uvs = Some(uvs.into());
+        self
+    }
+    pub fn with_colors(mut self, colors: impl Into<Box<[Color]>>) -> Self {
+        self.colors = Some(colors.into());
+        self
+    }
+    pub fn is_empty(&self) -> bool {
+        self.normals.is_none() && self.uvs.is_none() && self.colors.is_none()
+    }
+
+    pub(crate) fn check(&self, point_count: usize) -> Result<(), AttributeError> {
+        check_length("normals", self.normals.as_deref(), point_count)?;
+        check_length("uvs", self.uvs.as_deref(), point_count)?;
+        check_length("colors", self.colors.as_deref(), point_count)
+    }
+
+    /// attributes of the points in the given order, e.g. after dropping unused points
+    pub(crate) fn select(&self, points: &[usize]) -> Self {
+        Self {
+            normals: select(self.normals.as_deref(), points),
+            uvs: select(self.uvs.as_deref(), points),
+            colors: select(self.colors.as_deref(), points),
+        }
+    }
+}
+
+impl FaceAttributes {
+    pub fn materials(&self) -> Option<&[u32]> {
+        self.materials.as_deref()
+    }
+    /// index into [`FaceAttributes::group_names`] for every triangle
+    pub fn groups(&self) -> Option<&[u32]> {
+        self.groups.as_deref()
+    }
+    pub fn group_names(&self) -> &[String] {
+        &self.group_names
+    }
+    pub fn group_name(&self, triangle: usize) -> Option<&str> {
+        let group = self.groups.as_ref()?.get(triangle)?;
+        self.group_names.get(*group as usize).map(String::as_str)
+    }
+    pub fn with_materials(mut self, materials: impl Into<Box<[u32]>>) -> Self {
+        self.materials = Some(materials.into());
+        self
+    }
+    pub fn with_groups(mut self, groups: impl Into<Box<[u32]>>, names: Vec<String>) -> Self {
+        self.groups = Some(groups.into());
+        self.group_names = names;
+        self
+    }
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_none() && self.groups.is_none()
+    }
+
+    pub(crate) fn check(&self, triangle_count: usize) -> Result<(), AttributeError> {
+        check_length("materials", self.materials.as_deref(), triangle_count)?;
+        check_length("groups", self.groups.as_deref(), triangle_count)?;
+        match self
+            .groups
+            .iter()
+            .flat_map(|groups| groups.iter())
+            .find(|group| **group as usize >= self.group_names.len())
+        {
+            Some(group) => Err(AttributeError::UnknownGroup(*group)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn check_length<T>(
+    name: &'static str,
+    values: Option<&[T]>,
+    expected: usize,
+) -> Result<(), AttributeError> {
+    match values {
+        Some(values) if values.len() != expected => Err(AttributeError::WrongLength {
+            name,
+            expected,
+            found: values.len(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn select<T: Clone>(values: Option<&[T]>, indices: &[usize]) -> Option<Box<[T]>> {
+    values.map(|values| indices.iter().map(|idx| values[*idx].clone()).collect())
+}
+
+/// Collects attributes while a triangle list is built, entries which are never set get the default
+#[derive(Debug, Default)]
+pub(crate) struct AttributeBuilder {
+    normals: Option<Vec<Vector3d>>,
+    uvs: Option<Vec<Vector2d>>,
+    colors: Option<Vec<Color>>,
+    materials: Option<Vec<u32>>,
+    groups: Option<Vec<u32>>,
+    group_names: Vec<String>,
+}
+
+impl AttributeBuilder {
+    pub(crate) fn set_normal(&mut self, point: usize, normal: Vector3d) {
+        set_value(&mut self.normals, point, normal);
+    }
+    pub(crate) fn set_uv(&mut self, point: usize, uv: Vector2d) {
+        set_value(&mut self.uvs, point, uv);
+    }
+    pub(crate) fn set_color(&mut self, point: usize, color: Color) {
+        set_value(&mut self.colors, point, color);
+    }
+    pub(crate) fn set_material(&mut self, triangle: usize, material: u32) {
+        set_value(&mut self.materials, triangle, material);
+    }
+    pub(crate) fn set_group_names(&mut self, names: Vec<String>) {
+        self.group_names = names;
+    }
+    pub(crate) fn set_group_index(&mut self, triangle: usize, group: u32) {
+        set_value(&mut self.groups, triangle, group);
+    }
+
+    pub(crate) fn build(
+        self,
+        point_count: usize,
+        triangle_count: usize,
+    ) -> (VertexAttributes, FaceAttributes) {
+        (
+            VertexAttributes {
+                normals: finish(self.normals, point_count),
+                uvs: finish(self.uvs, point_count),
+                colors: finish(self.colors, point_count),
+            },
+            FaceAttributes {
+                materials: finish(self.materials, triangle_count),
+                groups: finish(self.groups, triangle_count),
+                group_names: self.group_names,
+            },
+        )
+    }
+}
+
+fn set_value<T: Clone + Default>(values: &mut Option<Vec<T>>, idx: usize, value: T) {
+    let values = values.get_or_insert_with(Vec::new);
+    if values.len() <= idx {
+        values.resize(idx + 1, T::default());
+    }
+    values[idx] = value;
+}
+
+fn finish<T: Clone + Default>(values: Option<Vec<T>>, len: usize) -> Option<Box<[T]>> {
+    values.map(|mut values| {
+        values.resize(len, T::default());
+        values.into_boxed_slice()
+    })
+}
@@ -0,0 +1,452 @@
+use std::collections::HashSet;
+
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::triangles::attributes::Color;
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::geometry3d::Vector3d;
+use crate::primitives::Number;
+
+const LEAF_SIZE: usize = 4;
+
+/// Distances of the surface of one mesh to the surface of another one, sampled at the vertices,
+/// edge midpoints, face centroids and optionally on a denser grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshDeviation {
+    distances: Vec<Number>,
+    samples: Vec<Number>,
+}
+
+/// Deviations of two meshes in both directions
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshComparison {
+    forward: MeshDeviation,
+    backward: MeshDeviation,
+}
+
+impl MeshDeviation {
+    /// distance of every point to the other surface, infinite if the other mesh is empty and zero
+    /// for points which no triangle refers to
+    pub fn distances(&self) -> &[Number] {
+        &self.distances
+    }
+    /// largest distance at the samples, a lower bound of the one-sided Hausdorff distance
+    pub fn max(&self) -> Number {
+        self.samples
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(Number::from(0.0))
+    }
+    /// root mean square of the distances at the samples
+    pub fn rms(&self) -> Number {
+        rms(&self.samples)
+    }
+    /// Vertex colors from blue for no deviation over green to red at `limit` and above, ready
+    /// for [`VertexAttributes::with_colors`](crate::prelude::VertexAttributes::with_colors).
+    pub fn colors(&self, limit: Number) -> Vec<Color> {
+        self.distances
+            .iter()
+            .map(|distance| {
+                let t = if limit > Number::from(0.0) {
+                    (*distance / limit).0.clamp(0.0, 1.0)
+                } else {
+                    1.0
+                };
+                let channel = |value: f64| (value * 255.0).round() as u8;
+                if t < 0.5 {
+                    Color::new(0, channel(t * 2.0), channel(1.0 - t * 2.0))
+                } else {
+                    Color::new(channel(t * 2.0 - 1.0), channel(2.0 - t * 2.0), 0)
+                }
+            })
+            .collect()
+    }
+}
+
+impl MeshComparison {
+    /// deviation of the first mesh from the second one
+    pub fn forward(&self) -> &MeshDeviation {
+        &self.forward
+    }
+    /// deviation of the second mesh from the first one
+    pub fn backward(&self) -> &MeshDeviation {
+        &self.backward
+    }
+    /// largest distance at the samples of both meshes, a lower bound of the symmetric Hausdorff
+    /// distance
+    pub fn hausdorff(&self) -> Number {
+        self.forward.max().max(self.backward.max())
+    }
+    /// root mean square over the samples of both directions
+    pub fn rms(&self) -> Number {
+        let all: Vec<Number> = [&self.forward, &self.backward]
+            .into_iter()
+            .flat_map(|deviation| deviation.samples.iter().copied())
+            .collect();
+        rms(&all)
+    }
+}
+
+impl<P: Point3d> IndexedTriangleList<P> {
+    /// Distance of the vertices, edge midpoints and face centroids to the closest point on the
+    /// triangles of the other mesh. Points which no triangle refers to are skipped.
+    pub fn deviation_from<P2: Point3d>(&self, other: &IndexedTriangleList<P2>) -> MeshDeviation {
+        self.deviation_from_sampled(other, 1)
+    }
+
+    /// Same as [`IndexedTriangleList::deviation_from`], every edge and face is additionally
+    /// sampled on a grid with `subdivisions` steps along each edge.
+    pub fn deviation_from_sampled<P2: Point3d>(
+        &self,
+        other: &IndexedTriangleList<P2>,
+        subdivisions: usize,
+    ) -> MeshDeviation {
+        let surface = Surface::new(other);
+        let distance = |p: Vector3d| -> Number { surface.distance_squared(p).sqrt().into() };
+        let point = |idx: usize| self.points[idx].coordinates();
+        let mut referenced = vec![false; self.points.len()];
+        let mut edges = HashSet::new();
+        let mut samples = Vec::new();
+        for triangle in self.triangles.iter() {
+            let corners = triangle.points();
+            let [a, b, c] = corners.map(point);
+            samples.push(distance((a + b + c) * Number::from(1.0 / 3.0)));
+            // inner grid points, the centroid is already sampled
+            for i in 1..subdivisions {
+                for j in 1..subdivisions - i {
+                    let k = subdivisions - i - j;
+                    if 3 * i != subdivisions || 3 * j != subdivisions {
+                        let weight =
+                            |steps: usize| Number::from(steps as f64 / subdivisions as f64);
+                        samples.push(distance(a * weight(i) + b * weight(j) + c * weight(k)));
+                    }
+                }
+            }
+            for i in 0..3 {
+                let (start, end) = (corners[i], corners[(i + 1) % 3]);
+                referenced[start] = true;
+                if edges.insert((start.min(end), start.max(end))) {
+                    samples.push(distance((point(start) + point(end)) * Number::from(0.5)));
+                    // grid points on the edge except for the midpoint
+                    for step in (1..subdivisions).filter(|step| 2 * step != subdivisions) {
+                        let t = Number::from(step as f64 / subdivisions as f64);
+                        samples.push(distance(point(start) + (point(end) - point(start)) * t));
+                    }
+                }
+            }
+        }
+        let distances: Vec<Number> = (0..self.points.len())
+            .map(|idx| {
+                if referenced[idx] {
+                    distance(point(idx))
+                } else {
+                    Number::from(0.0)
+                }
+            })
+            .collect();
+        samples.extend(
+            distances
+                .iter()
+                .zip(referenced)
+                .filter(|(_, used)| *used)
+                .map(|(distance, _)| *distance),
+        );
+        MeshDeviation { distances, samples }
+    }
+
+    /// Compares both meshes at their vertices, edge midpoints and face centroids, e.g. for
+    /// regression checks of exports.
+    ///
+    /// Deviations between these samples are not measured, so the Hausdorff distance is a lower
+    /// bound which is exact if the largest deviation lies at one of them.
+    pub fn compare<P2: Point3d>(&self, other: &IndexedTriangleList<P2>) -> MeshComparison {
+        self.compare_sampled(other, 1)
+    }
+
+    /// Same as [`IndexedTriangleList::compare`] with additional samples on a grid with
+    /// `subdivisions` steps along each edge, see [`IndexedTriangleList::deviation_from_sampled`].
+    pub fn compare_sampled<P2: Point3d>(
+        &self,
+        other: &IndexedTriangleList<P2>,
+        subdivisions: usize,
+    ) -> MeshComparison {
+        MeshComparison {
+            forward: self.deviation_from_sampled(other, subdivisions),
+            backward: other.deviation_from_sampled(self, subdivisions),
+        }
+    }
+}
+
+fn rms(distances: &[Number]) -> Number {
+    if distances.is_empty() {
+        return Number::from(0.0);
+    }
+    let sum: f64 = distances.iter().map(|d| (*d * *d).0).sum();
+    (sum / distances.len() as f64).sqrt().into()
+}
+
+/// bounding volume hierarchy over the triangles of a mesh
+struct Surface {
+    triangles: Vec<[Vector3d; 3]>,
+    nodes: Vec<SurfaceNode>,
+}
+
+struct SurfaceNode {
+    min: Vector3d,
+    max: Vector3d,
+    /// range of triangles for leaves, otherwise the indices of both child nodes
+    content: NodeContent,
+}
+
+enum NodeContent {
+    Leaf(usize, usize),
+    Inner(usize, usize),
+}
+
+impl Surface {
+    fn new<P: Point3d>(mesh: &IndexedTriangleList<P>) -> Self {
+        let mut surface = Self {
+            triangles: mesh
+                .triangles
+                .iter()
+                .map(|entry| entry.points().map(|p| mesh.points[p].coordinates()))
+                .collect(),
+            nodes: Vec::new(),
+        };
+        if !surface.triangles.is_empty() {
+            surface.build(0, surface.triangles.len());
+        }
+        surface
+    }
+
+    /// adds the nodes of the range and returns the index of its root
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let corners = self.triangles[start..end].iter().flatten();
+        let (min, max) = corners.fold(
+            (self.triangles[start][0], self.triangles[start][0]),
+            |(min, max), p| {
+                (
+                    Vector3d::partial_min(min, *p),
+                    Vector3d::partial_max(max, *p),
+                )
+            },
+        );
+        let node = self.nodes.len();
+        self.nodes.push(SurfaceNode {
+            min,
+            max,
+            content: NodeContent::Leaf(start, end),
+        });
+        if end - start > LEAF_SIZE {
+            let extent = max - min;
+            let axis = (0..3).max_by_key(|axis| extent[*axis]).expect("Three axes");
+            let middle = (start + end) / 2;
+            self.triangles[start..end].select_nth_unstable_by_key(middle - start, |corners| {
+                corners[0][axis] + corners[1][axis] + corners[2][axis]
+            });
+            let left = self.build(start, middle);
+            let right = self.build(middle, end);
+            self.nodes[node].content = NodeContent::Inner(left, right);
+        }
+        node
+    }
+
+    fn distance_squared(&self, p: Vector3d) -> Number {
+        let mut best = Number::from(f64::INFINITY);
+        if !self.nodes.is_empty() {
+            self.search(0, p, &mut best);
+        }
+        best
+    }
+
+    fn search(&self, node: usize, p: Vector3d, best: &mut Number) {
+        match self.nodes[node].content {
+            NodeContent::Leaf(start, end) => {
+                for triangle in &self.triangles[start..end] {
+                    let distance = closest_point(triangle, p).distance_squared(p);
+                    if distance < *best {
+                        *best = distance;
+                    }
+                }
+            }
+            NodeContent::Inner(left, right) => {
+                let mut children = [left, right].map(|child| (self.box_distance(child, p), child));
+                children.sort();
+                for (distance, child) in children {
+                    if distance < *best {
+                        self.search(child, p, best);
+                    }
+                }
+            }
+        }
+    }
+
+    fn box_distance(&self, node: usize, p: Vector3d) -> Number {
+        let SurfaceNode { min, max, .. } = &self.nodes[node];
+        let outside =
+            Vector3d::partial_max(Vector3d::partial_max(*min - p, p - *max), Vector3d::zero());
+        outside.magnitude_squared()
+    }
+}
+
+/// closest point on the triangle, see Ericson: Real-Time Collision Detection, 5.1.5
+fn closest_point(triangle: &[Vector3d; 3], p: Vector3d) -> Vector3d {
+    let [a, b, c] = *triangle;
+    let (ab, ac) = (b - a, c - a);
+    let along = |start: Vector3d, direction: Vector3d, t: f64| start + direction * Number::from(t);
+    let ap = p - a;
+    let (d1, d2) = (ab.dot(ap).0, ac.dot(ap).0);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(bp).0, ac.dot(bp).0);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return along(a, ab, d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(cp).0, ac.dot(cp).0);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return along(a, ac, d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return along(b, c - b, (d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let sum = va + vb + vc;
+    if sum == 0.0 {
+        // degenerated triangle
+        return a;
+    }
+    along(along(a, ab, vb / sum), ac, vc / sum)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry3d::triangles::attributes::{Color, VertexAttributes};
+    use crate::geometry3d::triangles::IndexedTriangleList;
+    use crate::prelude::{point_3d, Vector3d};
+    use crate::primitives::Number;
+    use crate::test::create_cube;
+
+    #[test]
+    fn test_compare_with_itself() {
+        let cube = create_cube(3);
+        let comparison = cube.compare(&cube);
+        // the face centroids are only found up to rounding
+        assert!(comparison.hausdorff().0 < 1e-12);
+        assert!(comparison.rms().0 < 1e-12);
+        assert_eq!(cube.points().len(), comparison.forward().distances().len());
+    }
+
+    #[test]
+    fn test_compare_scaled_cube() {
+        let cube = create_cube(2);
+        let center = point_3d(0.5, 0.5, 0.5);
+        let scaled = cube
+            .clone()
+            .transform_points(|p| center + (*p - center) * Number::from(1.1));
+        let comparison = cube.compare(&scaled);
+        // the inner points are 0.05 below the scaled faces
+        assert!((comparison.forward().max().0 - 0.05).abs() < 1e-12);
+        // the scaled corners are farthest from the original corners
+        assert!((comparison.backward().max().0 - 0.05 * 3f64.sqrt()).abs() < 1e-12);
+        assert_eq!(comparison.backward().max(), comparison.hausdorff());
+        assert!(comparison.rms() > comparison.forward().rms());
+        assert!(comparison.rms() < comparison.hausdorff());
+
+        let colors = comparison.backward().colors(0.1.into());
+        assert_eq!(scaled.points().len(), colors.len());
+        // the center of a face deviates by half of the limit
+        let face_center = scaled
+            .points()
+            .iter()
+            .position(|p| *p == point_3d(1.05, 0.5, 0.5))
+            .expect("Missing face center");
+        assert_eq!(Color::new(0, 255, 0), colors[face_center]);
+        let colored =
+            scaled.with_vertex_attributes(VertexAttributes::default().with_colors(colors));
+        assert!(colored.is_ok());
+    }
+
+    #[test]
+    fn test_compare_with_empty() {
+        let cube = create_cube(1);
+        let empty = IndexedTriangleList::<Vector3d>::builder::<Vector3d>().build();
+        assert!(cube.compare(&empty).forward().max().0.is_infinite());
+        assert!(empty.compare(&cube).forward().distances().is_empty());
+    }
+
+    #[test]
+    fn test_compare_inside_faces() {
+        // a flat triangle and a tent over it with the same corners
+        let corners = [
+            point_3d(0.0, 0.0, 0.0),
+            point_3d(3.0, 0.0, 0.0),
+            point_3d(0.0, 3.0, 0.0),
+        ];
+        let mut builder = IndexedTriangleList::<Vector3d>::builder::<Vector3d>();
+        let [a, b, c] = corners.map(|p| builder.append_point(p));
+        builder.append_indexed_triangle(a, b, c);
+        // not referenced by any triangle
+        builder.append_point(point_3d(10.0, 10.0, 10.0));
+        let flat = builder.build();
+        let mut builder = IndexedTriangleList::<Vector3d>::builder::<Vector3d>();
+        let [a, b, c] = corners.map(|p| builder.append_point(p));
+        let top = builder.append_point(point_3d(1.0, 1.0, 1.0));
+        builder.append_indexed_triangle(a, b, top);
+        builder.append_indexed_triangle(b, c, top);
+        builder.append_indexed_triangle(c, a, top);
+        let tent = builder.build();
+
+        let comparison = flat.compare(&tent);
+        // the corners match, the centroid lies below the top
+        assert!(comparison.forward().max().0 > 0.1);
+        assert!(comparison.forward().max() < comparison.backward().max());
+        assert_eq!(1.0, comparison.backward().max().0);
+        // the unreferenced point is not compared
+        assert_eq!(4, comparison.forward().distances().len());
+        assert_eq!(0.0, comparison.forward().distances()[3].0);
+        assert!(flat.compare(&flat).hausdorff().0 < 1e-12);
+    }
+
+    #[test]
+    fn test_maximum_between_samples() {
+        let corners = [
+            point_3d(0.0, 0.0, 0.0),
+            point_3d(3.0, 0.0, 0.0),
+            point_3d(0.0, 3.0, 0.0),
+        ];
+        let mut builder = IndexedTriangleList::<Vector3d>::builder::<Vector3d>();
+        let [a, b, c] = corners.map(|p| builder.append_point(p));
+        builder.append_indexed_triangle(a, b, c);
+        let flat = builder.build();
+        // the top is above (0.5, 0.5), which is no vertex, midpoint or centroid of the flat one
+        let mut builder = IndexedTriangleList::<Vector3d>::builder::<Vector3d>();
+        let [a, b, c] = corners.map(|p| builder.append_point(p));
+        let top = builder.append_point(point_3d(0.5, 0.5, 1.0));
+        builder.append_indexed_triangle(a, b, top);
+        builder.append_indexed_triangle(b, c, top);
+        builder.append_indexed_triangle(c, a, top);
+        let tent = builder.build();
+
+        let coarse = flat.deviation_from(&tent).max();
+        assert_eq!(coarse, flat.deviation_from_sampled(&tent, 1).max());
+        // the grid with six steps hits the point below the top
+        let dense = flat.deviation_from_sampled(&tent, 6).max();
+        assert!(dense > coarse);
+        // corners, five points on every edge, the centroid and nine more inner points
+        let samples = flat.deviation_from_sampled(&tent, 6).samples.len();
+        assert_eq!(3 + 3 * 5 + 1 + 9, samples);
+        assert!(dense.0 < 1.0);
+        assert_eq!(dense, flat.compare_sampled(&tent, 6).forward().max());
+    }
+}
@@ -0,0 +1,553 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::ops::AddAssign;
+
+use ordered_float::OrderedFloat;
+use vek::Vec3;
+
+use crate::geometry3d::line::Line3d;
+use crate::geometry3d::plane::Plane3d;
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::triangles::topology::{
+    collect_line_neighbors, LineNeighbors, TopologyError,
+};
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::geometry3d::Vector3d;
+use crate::primitives::Number;
+
+/// When [`IndexedTriangleList::decimate`] stops collapsing edges
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DecimationTarget {
+    /// stop as soon as the mesh has no more than this number of triangles
+    TriangleCount(usize),
+    /// only collapse edges where the new point stays within this distance to the planes of all
+    /// original triangles merged into it
+    MaxError(Number),
+}
+
+impl<P: Point3d> IndexedTriangleList<P> {
+    /// Reduces the triangle count by edge collapses ordered by the Garland–Heckbert quadric error.
+    ///
+    /// Points on boundary edges keep their position and are only removed where the boundary runs
+    /// straight. Collapses which would flip a triangle or break the manifold property are skipped.
+    /// Face attributes stay with the remaining triangles, a collapsed edge keeps the vertex
+    /// attributes of the point which is not removed.
+    pub fn decimate(
+        &self,
+        target: DecimationTarget,
+    ) -> Result<IndexedTriangleList<Vector3d>, TopologyError<'_, P>> {
+        let triangles = self.triangles();
+        let neighbors = collect_line_neighbors(&triangles)?;
+        let mut mesh = DecimationMesh::new(self);
+        for (edge, neighbor) in neighbors.iter() {
+            if let LineNeighbors::OnlyForward(_) | LineNeighbors::OnlyBackward(_) = neighbor {
+                mesh.boundary_points[edge.p1().idx()] = true;
+                mesh.boundary_points[edge.p2().idx()] = true;
+            }
+        }
+        let mut candidates = BinaryHeap::with_capacity(neighbors.len());
+        for edge in neighbors.keys() {
+            candidates.push(mesh.candidate(edge.p1().idx(), edge.p2().idx()));
+        }
+        mesh.collapse_edges(candidates, target);
+        Ok(mesh.build(self))
+    }
+}
+
+/// symmetric 4x4 matrix of the plane equations: sum of p*p^T with p = (a, b, c, d)
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_plane(normal: Vec3<f64>, distance: f64, weight: f64) -> Self {
+        let Vec3 { x: a, y: b, z: c } = normal;
+        let d = -distance;
+        Self(
+            [
+                a * a,
+                a * b,
+                a * c,
+                a * d,
+                b * b,
+                b * c,
+                b * d,
+                c * c,
+                c * d,
+                d * d,
+            ]
+            .map(|v| v * weight),
+        )
+    }
+    fn error(&self, p: Vec3<f64>) -> f64 {
+        let [aa, ab, ac, ad, bb, bc, bd, cc, cd, dd] = self.0;
+        let Vec3 { x, y, z } = p;
+        let error = x * (aa * x + ab * y + ac * z + ad)
+            + y * (ab * x + bb * y + bc * z + bd)
+            + z * (ac * x + bc * y + cc * z + cd)
+            + (ad * x + bd * y + cd * z + dd);
+        error.max(0.0)
+    }
+    /// point with the smallest error, `None` if the planes do not define a single point
+    fn optimal_point(&self) -> Option<Vec3<f64>> {
+        let [aa, ab, ac, ad, bb, bc, bd, cc, cd, _] = self.0;
+        let det = aa * (bb * cc - bc * bc) - ab * (ab * cc - bc * ac) + ac * (ab * bc - bb * ac);
+        let scale = aa.abs().max(bb.abs()).max(cc.abs());
+        if det.abs() <= 1e-12 * scale * scale * scale {
+            return None;
+        }
+        // cramer's rule for A * p = -(ad, bd, cd)
+        let (rx, ry, rz) = (-ad, -bd, -cd);
+        let x = rx * (bb * cc - bc * bc) - ab * (ry * cc - bc * rz) + ac * (ry * bc - bb * rz);
+        let y = aa * (ry * cc - bc * rz) - rx * (ab * cc - bc * ac) + ac * (ab * rz - ry * ac);
+        let z = aa * (bb * rz - ry * bc) - ab * (ab * rz - ry * ac) + rx * (ab * bc - bb * ac);
+        Some(Vec3::new(x, y, z) / det)
+    }
+}
+
+impl AddAssign for Quadric {
+    fn add_assign(&mut self, rhs: Self) {
+        for (value, other) in self.0.iter_mut().zip(rhs.0) {
+            *value += other;
+        }
+    }
+}
+
+/// heap entry: cost, both points and their versions at the time of calculation
+type Candidate = Reverse<(OrderedFloat<f64>, usize, usize, u32, u32)>;
+
+struct DecimationMesh {
+    positions: Vec<Vec3<f64>>,
+    quadrics: Vec<Quadric>,
+    /// plane of every original triangle as normal and distance, `None` if degenerated
+    planes: Vec<Option<(Vec3<f64>, f64)>>,
+    /// original triangles merged into a point, sorted
+    point_planes: Vec<Vec<usize>>,
+    versions: Vec<u32>,
+    removed_points: Vec<bool>,
+    boundary_points: Vec<bool>,
+    point_triangles: Vec<Vec<usize>>,
+    triangles: Vec<[usize; 3]>,
+    removed_triangles: Vec<bool>,
+    triangle_count: usize,
+}
+
+impl DecimationMesh {
+    fn new<P: Point3d>(list: &IndexedTriangleList<P>) -> Self {
+        let positions: Vec<_> = list
+            .points
+            .iter()
+            .map(|p| p.coordinates().map(|c| c.0))
+            .collect();
+        let mut quadrics = vec![Quadric::default(); positions.len()];
+        let mut point_triangles = vec![Vec::new(); positions.len()];
+        let triangles: Vec<_> = list.triangles.iter().map(|t| t.points()).collect();
+        let mut planes = Vec::with_capacity(triangles.len());
+        for (idx, triangle) in triangles.iter().enumerate() {
+            let [p1, p2, p3] = triangle.map(|p| list.points[p].coordinates());
+            let plane = Plane3d::from_points(p1, p2, p3)
+                .ok()
+                .map(|plane| (plane.normal().map(|c| c.0), plane.distance().0));
+            if let Some((normal, distance)) = plane {
+                let quadric = Quadric::from_plane(normal, distance, 1.0);
+                for p in triangle {
+                    quadrics[*p] += quadric;
+                }
+            }
+            planes.push(plane);
+            for p in triangle {
+                point_triangles[*p].push(idx);
+            }
+        }
+        Self {
+            quadrics,
+            planes,
+            point_planes: point_triangles.clone(),
+            versions: vec![0; positions.len()],
+            removed_points: vec![false; positions.len()],
+            boundary_points: vec![false; positions.len()],
+            positions,
+            point_triangles,
+            removed_triangles: vec![false; triangles.len()],
+            triangle_count: triangles.len(),
+            triangles,
+        }
+    }
+
+    fn collapse_position(&self, p1: usize, p2: usize) -> (Vec3<f64>, f64) {
+        let mut quadric = self.quadrics[p1];
+        quadric += self.quadrics[p2];
+        let start = self.positions[p1];
+        let end = self.positions[p2];
+        // boundary points never move
+        let positions = match (self.boundary_points[p1], self.boundary_points[p2]) {
+            (true, false) => vec![start],
+            (false, true) => vec![end],
+            (true, true) => vec![start, end],
+            (false, false) => quadric
+                .optimal_point()
+                .into_iter()
+                .chain([start, end, (start + end) / 2.0])
+                .collect(),
+        };
+        positions
+            .into_iter()
+            .map(|p| (p, quadric.error(p)))
+            .min_by_key(|(_, error)| OrderedFloat(*error))
+            .expect("At least the end points are candidates")
+    }
+
+    fn candidate(&self, p1: usize, p2: usize) -> Candidate {
+        let (_, error) = self.collapse_position(p1, p2);
+        Reverse((
+            OrderedFloat(error),
+            p1,
+            p2,
+            self.versions[p1],
+            self.versions[p2],
+        ))
+    }
+
+    fn collapse_edges(&mut self, mut candidates: BinaryHeap<Candidate>, target: DecimationTarget) {
+        while let Some(Reverse((_, p1, p2, version1, version2))) = candidates.pop() {
+            if let DecimationTarget::TriangleCount(count) = target {
+                if self.triangle_count <= count {
+                    break;
+                }
+            }
+            if self.removed_points[p1]
+                || self.removed_points[p2]
+                || self.versions[p1] != version1
+                || self.versions[p2] != version2
+            {
+                continue;
+            }
+            let (position, _) = self.collapse_position(p1, p2);
+            if let DecimationTarget::MaxError(max_error) = target {
+                // the quadric sums squared distances, so it cannot stop the loop
+                if self.max_plane_distance(p1, p2, position) > max_error.0 {
+                    continue;
+                }
+            }
+            if !self.can_collapse(p1, p2, position) {
+                continue;
+            }
+            self.collapse(p1, p2, position);
+            for neighbor in self.neighbor_points(p1) {
+                candidates.push(self.candidate(p1, neighbor));
+            }
+        }
+    }
+
+    /// largest distance of the position to the original planes of both points
+    fn max_plane_distance(&self, p1: usize, p2: usize, position: Vec3<f64>) -> f64 {
+        self.point_planes[p1]
+            .iter()
+            .chain(&self.point_planes[p2])
+            .filter_map(|t| self.planes[*t])
+            .map(|(normal, distance)| (normal.dot(position) - distance).abs())
+            .fold(0.0, f64::max)
+    }
+
+    fn neighbor_points(&self, point: usize) -> HashSet<usize> {
+        self.point_triangles[point]
+            .iter()
+            .flat_map(|t| self.triangles[*t])
+            .filter(|p| *p != point)
+            .collect()
+    }
+
+    fn can_collapse(&self, p1: usize, p2: usize, position: Vec3<f64>) -> bool {
+        let shared_triangles: Vec<_> = self.point_triangles[p1]
+            .iter()
+            .filter(|t| self.triangles[**t].contains(&p2))
+            .copied()
+            .collect();
+        if shared_triangles.is_empty() {
+            return false;
+        }
+        if self.boundary_points[p1] && self.boundary_points[p2] {
+            // a inner edge between two boundary points would pinch the surface
+            if shared_triangles.len() > 1 {
+                return false;
+            }
+            // along the boundary only points in a straight line can be removed
+            let (kept, removed) = if position == self.positions[p1] {
+                (p1, p2)
+            } else {
+                (p2, p1)
+            };
+            if !self.is_straight_boundary(removed, kept) {
+                return false;
+            }
+        }
+        // link condition: the only common neighbors are the tips of the removed triangles
+        let opposite_points: HashSet<_> = shared_triangles
+            .iter()
+            .flat_map(|t| self.triangles[*t])
+            .filter(|p| *p != p1 && *p != p2)
+            .collect();
+        let neighbors1 = self.neighbor_points(p1);
+        if self
+            .neighbor_points(p2)
+            .iter()
+            .any(|p| neighbors1.contains(p) && !opposite_points.contains(p))
+        {
+            return false;
+        }
+
+        let mut remaining_triangles = HashSet::new();
+        for point in [p1, p2] {
+            for triangle_idx in self.point_triangles[point].iter() {
+                let triangle = self.triangles[*triangle_idx];
+                if triangle.contains(&p1) && triangle.contains(&p2) {
+                    continue;
+                }
+                let [old1, old2, old3] = triangle.map(|p| self.positions[p]);
+                let [new1, new2, new3] = triangle.map(|p| {
+                    if p == p1 || p == p2 {
+                        position
+                    } else {
+                        self.positions[p]
+                    }
+                });
+                let old_normal = (old2 - old1).cross(old3 - old1);
+                let new_normal = (new2 - new1).cross(new3 - new1);
+                // no flipped and no degenerated triangles
+                if new_normal.magnitude_squared() == 0.0 || old_normal.dot(new_normal) <= 0.0 {
+                    return false;
+                }
+                let mut merged = triangle.map(|p| if p == p2 { p1 } else { p });
+                merged.sort();
+                // two triangles on the same points would close a fold
+                if !remaining_triangles.insert(merged) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn is_straight_boundary(&self, point: usize, boundary_neighbor: usize) -> bool {
+        let center = self.positions[point];
+        let to_neighbor = self.positions[boundary_neighbor] - center;
+        self.neighbor_points(point)
+            .into_iter()
+            .filter(|other| *other != boundary_neighbor)
+            .filter(|other| {
+                self.point_triangles[point]
+                    .iter()
+                    .filter(|t| self.triangles[**t].contains(other))
+                    .count()
+                    == 1
+            })
+            .all(|other| {
+                let to_other = self.positions[other] - center;
+                let cross = to_neighbor.cross(to_other).magnitude();
+                cross <= 1e-9 * to_neighbor.magnitude() * to_other.magnitude()
+                    && to_neighbor.dot(to_other) < 0.0
+            })
+    }
+
+    fn collapse(&mut self, p1: usize, p2: usize, position: Vec3<f64>) {
+        let quadric = self.quadrics[p2];
+        self.quadrics[p1] += quadric;
+        let merged_planes = std::mem::take(&mut self.point_planes[p2]);
+        let planes = &mut self.point_planes[p1];
+        planes.extend(merged_planes);
+        planes.sort_unstable();
+        planes.dedup();
+        self.positions[p1] = position;
+        self.boundary_points[p1] |= self.boundary_points[p2];
+        self.removed_points[p2] = true;
+        self.versions[p1] += 1;
+        for triangle_idx in std::mem::take(&mut self.point_triangles[p2]) {
+            let triangle = &mut self.triangles[triangle_idx];
+            if triangle.contains(&p1) {
+                self.removed_triangles[triangle_idx] = true;
+                self.triangle_count -= 1;
+                for p in *triangle {
+                    self.point_triangles[p].retain(|t| *t != triangle_idx);
+                }
+            } else {
+                for p in triangle.iter_mut() {
+                    if *p == p2 {
+                        *p = p1;
+                    }
+                }
+                self.point_triangles[p1].push(triangle_idx);
+            }
+        }
+    }
+
+    fn build<P: Point3d>(self, list: &IndexedTriangleList<P>) -> IndexedTriangleList<Vector3d> {
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        let mut new_index = vec![0; self.positions.len()];
+        let mut kept_points = Vec::with_capacity(self.positions.len());
+        for (idx, position) in self.positions.iter().enumerate() {
+            if !self.removed_points[idx] && !self.point_triangles[idx].is_empty() {
+                new_index[idx] = builder.append_point(position.map(Number::from));
+                kept_points.push(idx);
+            }
+        }
+        let face_attributes = &list.face_attributes;
+        builder
+            .attributes
+            .set_group_names(face_attributes.group_names().to_vec());
+        for (idx, [p1, p2, p3]) in self.triangles.iter().enumerate() {
+            if !self.removed_triangles[idx] {
+                let new_idx =
+                    builder.append_indexed_triangle(new_index[*p1], new_index[*p2], new_index[*p3]);
+                if let Some(materials) = face_attributes.materials() {
+                    builder.attributes.set_material(new_idx, materials[idx]);
+                }
+                if let Some(groups) = face_attributes.groups() {
+                    builder.attributes.set_group_index(new_idx, groups[idx]);
+                }
+            }
+        }
+        let mut decimated = builder.build();
+        decimated.vertex_attributes = list.vertex_attributes.select(&kept_points);
+        decimated
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry3d::triangles::attributes::FaceAttributes;
+    use crate::geometry3d::triangles::decimate::DecimationTarget;
+    use crate::prelude::{point_3d, Point3d, Triangle3d, TriangleTopology, Vector3d};
+    use crate::test::{create_cube, create_grid, load_schublade_as_triangles};
+
+    fn on_cube_surface(p: &Vector3d) -> bool {
+        let c = p.coordinates();
+        let inside = [c.x, c.y, c.z]
+            .iter()
+            .all(|v| v.0 >= -1e-9 && v.0 <= 1.0 + 1e-9);
+        let on_face = [c.x, c.y, c.z]
+            .iter()
+            .any(|v| v.0.abs() < 1e-9 || (v.0 - 1.0).abs() < 1e-9);
+        inside && on_face
+    }
+
+    #[test]
+    fn test_decimate_cube_to_count() {
+        let cube = create_cube(8);
+        let decimated = cube
+            .decimate(DecimationTarget::TriangleCount(12))
+            .expect("Topology error");
+        assert!(decimated.triangles().len() <= 12);
+        assert!(decimated.points().iter().all(on_cube_surface));
+        TriangleTopology::new(&decimated).expect("Decimated cube is not closed");
+    }
+
+    #[test]
+    fn test_decimate_cube_by_error() {
+        let cube = create_cube(6);
+        let decimated = cube
+            .decimate(DecimationTarget::MaxError(1e-6.into()))
+            .expect("Topology error");
+        assert!(decimated.triangles().len() < cube.triangles().len() / 4);
+        assert!(decimated.points().iter().all(on_cube_surface));
+        // every corner must survive
+        for corner in 0..8 {
+            let expected = point_3d(
+                (corner & 1) as f64,
+                ((corner >> 1) & 1) as f64,
+                ((corner >> 2) & 1) as f64,
+            );
+            assert!(decimated
+                .points()
+                .iter()
+                .any(|p| p.dist_square(&expected) < 1e-12.into()));
+        }
+        TriangleTopology::new(&decimated).expect("Decimated cube is not closed");
+    }
+
+    #[test]
+    fn test_decimate_keeps_boundary() {
+        let size = 5;
+        let grid = create_grid(size);
+        let decimated = grid
+            .decimate(DecimationTarget::TriangleCount(0))
+            .expect("Topology error");
+        assert!(decimated.triangles().len() < grid.triangles().len());
+        for p in decimated.points() {
+            assert!(p.x.0 >= 0.0 && p.x.0 <= size as f64);
+            assert!(p.y.0 >= 0.0 && p.y.0 <= size as f64);
+            assert!(p.z.0.abs() < 1e-9);
+        }
+        for corner in [(0, 0), (size, 0), (size, size), (0, size)] {
+            let expected = point_3d(corner.0 as f64, corner.1 as f64, 0.0);
+            assert!(decimated
+                .points()
+                .iter()
+                .any(|p| p.dist_square(&expected) < 1e-12.into()));
+        }
+        let area: f64 = decimated
+            .triangles()
+            .iter()
+            .map(|t| {
+                let [p1, p2, p3] = t.points().map(|p| p.coordinates().map(|c| c.0));
+                (p2 - p1).cross(p3 - p1).z / 2.0
+            })
+            .sum();
+        assert!((area - (size * size) as f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_decimate_keeps_peak() {
+        let grid = create_grid(4).transform_points(|p| {
+            if p.x.0 == 2.0 && p.y.0 == 2.0 {
+                point_3d(2.0, 2.0, 0.5)
+            } else {
+                *p
+            }
+        });
+        let decimated = grid
+            .decimate(DecimationTarget::MaxError(0.1.into()))
+            .expect("Topology error");
+        assert!(decimated.triangles().len() < grid.triangles().len());
+        assert!(decimated.points().iter().any(|p| p.z.0 > 0.4));
+    }
+
+    #[test]
+    fn test_decimate_keeps_face_attributes() {
+        let cube = create_cube(4);
+        // one material per side of the cube
+        let materials: Vec<u32> = (0..cube.triangles().len())
+            .map(|idx| (idx / 32) as u32)
+            .collect();
+        let cube = cube
+            .with_face_attributes(FaceAttributes::default().with_materials(materials))
+            .expect("Invalid attributes");
+        let decimated = cube
+            .decimate(DecimationTarget::MaxError(1e-6.into()))
+            .expect("Topology error");
+        let materials = decimated
+            .face_attributes()
+            .materials()
+            .expect("Materials lost");
+        assert_eq!(decimated.triangles().len(), materials.len());
+        let sides = cube.triangles();
+        for (triangle, material) in decimated.triangles().iter().zip(materials) {
+            let normal = triangle.calculate_plane().expect("Degenerated").normal();
+            let side = sides
+                .iter()
+                .find(|t| t.idx() as u32 / 32 == *material)
+                .expect("Unknown material");
+            let side_normal = side.calculate_plane().expect("Degenerated").normal();
+            assert!((normal - side_normal).magnitude().0 < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_decimate_schublade() {
+        let triangles = load_schublade_as_triangles();
+        let target = triangles.triangles().len() / 2;
+        let decimated = triangles
+            .decimate(DecimationTarget::TriangleCount(target))
+            .expect("Topology error");
+        assert!(decimated.triangles().len() <= target);
+        TriangleTopology::new(&decimated).expect("Decimated mesh is not closed");
+    }
+}
@@ -0,0 +1,750 @@
+use std::collections::{HashMap, HashSet};
+
+use thiserror::Error;
+
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::triangles::IndexedTriangleList;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct VertexId(usize);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct HalfEdgeId(usize);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct FaceId(usize);
+
+impl VertexId {
+    pub fn idx(&self) -> usize {
+        self.0
+    }
+}
+
+impl HalfEdgeId {
+    pub fn idx(&self) -> usize {
+        self.0
+    }
+}
+
+impl FaceId {
+    pub fn idx(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Error, Debug, Eq, PartialEq, Copy, Clone)]
+pub enum HalfEdgeError {
+    #[error("Triangle {0} uses a point more than once")]
+    DegeneratedTriangle(usize),
+    #[error("Triangle {0} uses an edge in the same direction as another triangle")]
+    DuplicateEdge(usize),
+    #[error("Point {0} is not manifold")]
+    NonManifoldVertex(usize),
+    #[error("Half edge {0} does not exist")]
+    MissingHalfEdge(usize),
+    #[error("Edge is on the boundary")]
+    BoundaryEdge,
+    #[error("Edge already exists")]
+    ExistingEdge,
+    #[error("Operation would break the manifold property")]
+    NonManifoldResult,
+}
+
+#[derive(Clone, Debug)]
+struct Vertex<P: Point3d> {
+    point: P,
+    /// outgoing half edge, the boundary one if the vertex is on the boundary
+    half_edge: Option<HalfEdgeId>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct HalfEdge {
+    origin: VertexId,
+    twin: HalfEdgeId,
+    next: HalfEdgeId,
+    prev: HalfEdgeId,
+    /// `None` on the boundary
+    face: Option<FaceId>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Face {
+    half_edge: HalfEdgeId,
+}
+
+/// Triangle mesh with editable topology.
+///
+/// Every edge consists of two half edges, boundaries are closed by half edges without face. Removed
+/// elements leave gaps, so ids stay valid until the mesh is converted back into an
+/// [`IndexedTriangleList`].
+#[derive(Clone, Debug)]
+pub struct HalfEdgeMesh<P: Point3d> {
+    vertices: Vec<Option<Vertex<P>>>,
+    half_edges: Vec<Option<HalfEdge>>,
+    faces: Vec<Option<Face>>,
+}
+
+impl<P: Point3d> TryFrom<&IndexedTriangleList<P>> for HalfEdgeMesh<P> {
+    type Error = HalfEdgeError;
+
+    fn try_from(list: &IndexedTriangleList<P>) -> Result<Self, Self::Error> {
+        let mut mesh = HalfEdgeMesh {
+            vertices: list
+                .points
+                .iter()
+                .map(|point| {
+                    Some(Vertex {
+                        point: point.clone(),
+                        half_edge: None,
+                    })
+                })
+                .collect(),
+            half_edges: Vec::with_capacity(list.triangles.len() * 3),
+            faces: Vec::with_capacity(list.triangles.len()),
+        };
+        let mut edges = HashMap::with_capacity(list.triangles.len() * 3);
+        for (idx, triangle) in list.triangles.iter().enumerate() {
+            let points = triangle.points();
+            if points[0] == points[1] || points[1] == points[2] || points[2] == points[0] {
+                return Err(HalfEdgeError::DegeneratedTriangle(idx));
+            }
+            let face = FaceId(mesh.faces.len());
+            let first = mesh.half_edges.len();
+            mesh.faces.push(Some(Face {
+                half_edge: HalfEdgeId(first),
+            }));
+            for (corner, [from, to]) in triangle.sides().into_iter().enumerate() {
+                let id = HalfEdgeId(first + corner);
+                if edges.insert((from, to), id).is_some() {
+                    return Err(HalfEdgeError::DuplicateEdge(idx));
+                }
+                mesh.half_edges.push(Some(HalfEdge {
+                    origin: VertexId(from),
+                    // twins are connected below
+                    twin: id,
+                    next: HalfEdgeId(first + (corner + 1) % 3),
+                    prev: HalfEdgeId(first + (corner + 2) % 3),
+                    face: Some(face),
+                }));
+                mesh.vertex_mut(VertexId(from)).half_edge = Some(id);
+            }
+        }
+
+        let mut boundary_edges = HashMap::new();
+        let mut inner_edges: Vec<_> = edges.iter().map(|(k, v)| (*k, *v)).collect();
+        // keep the ids independent of the hash map order
+        inner_edges.sort();
+        for ((from, to), id) in inner_edges {
+            if let Some(twin) = edges.get(&(to, from)) {
+                mesh.half_edge_mut(id).twin = *twin;
+            } else {
+                let boundary = mesh.push_half_edge(VertexId(to), None);
+                mesh.half_edge_mut(boundary).twin = id;
+                mesh.half_edge_mut(id).twin = boundary;
+                if boundary_edges.insert(to, boundary).is_some() {
+                    return Err(HalfEdgeError::NonManifoldVertex(to));
+                }
+                mesh.vertex_mut(VertexId(to)).half_edge = Some(boundary);
+            }
+        }
+        for boundary in boundary_edges.values() {
+            let next = boundary_edges[&mesh.destination(*boundary).0];
+            mesh.half_edge_mut(*boundary).next = next;
+            mesh.half_edge_mut(next).prev = *boundary;
+        }
+
+        // all triangles around a vertex have to be reachable from each other
+        let mut outgoing_count = vec![0; mesh.vertices.len()];
+        for half_edge in mesh.half_edges.iter().flatten() {
+            outgoing_count[half_edge.origin.0] += 1;
+        }
+        for vertex in mesh.vertices() {
+            if mesh.outgoing_half_edges(vertex).count() != outgoing_count[vertex.0] {
+                return Err(HalfEdgeError::NonManifoldVertex(vertex.0));
+            }
+        }
+        Ok(mesh)
+    }
+}
+
+impl<P: Point3d> From<&HalfEdgeMesh<P>> for IndexedTriangleList<P> {
+    /// drops the gaps of removed elements, so the point indices do not match the vertex ids
+    fn from(mesh: &HalfEdgeMesh<P>) -> Self {
+        let mut builder = IndexedTriangleList::<P>::builder();
+        let mut new_index = vec![0; mesh.vertices.len()];
+        for vertex in mesh.vertices() {
+            new_index[vertex.0] = builder.append_point(mesh.point(vertex).clone());
+        }
+        for face in mesh.faces() {
+            let [p1, p2, p3] = mesh.face_vertices(face).map(|v| new_index[v.0]);
+            builder.append_indexed_triangle(p1, p2, p3);
+        }
+        builder.build()
+    }
+}
+
+impl<P: Point3d> HalfEdgeMesh<P> {
+    pub fn vertices(&self) -> impl Iterator<Item = VertexId> + '_ {
+        ids(&self.vertices).map(VertexId)
+    }
+    pub fn half_edges(&self) -> impl Iterator<Item = HalfEdgeId> + '_ {
+        ids(&self.half_edges).map(HalfEdgeId)
+    }
+    pub fn faces(&self) -> impl Iterator<Item = FaceId> + '_ {
+        ids(&self.faces).map(FaceId)
+    }
+    pub fn vertex_count(&self) -> usize {
+        self.vertices().count()
+    }
+    pub fn face_count(&self) -> usize {
+        self.faces().count()
+    }
+
+    pub fn point(&self, vertex: VertexId) -> &P {
+        &self.vertex(vertex).point
+    }
+    pub fn set_point(&mut self, vertex: VertexId, point: P) {
+        self.vertex_mut(vertex).point = point;
+    }
+    /// one of the half edges starting at the vertex, `None` for isolated points
+    pub fn vertex_half_edge(&self, vertex: VertexId) -> Option<HalfEdgeId> {
+        self.vertex(vertex).half_edge
+    }
+    pub fn is_boundary_vertex(&self, vertex: VertexId) -> bool {
+        self.vertex(vertex)
+            .half_edge
+            .is_some_and(|h| self.is_boundary(h))
+    }
+
+    pub fn origin(&self, half_edge: HalfEdgeId) -> VertexId {
+        self.half_edge(half_edge).origin
+    }
+    pub fn destination(&self, half_edge: HalfEdgeId) -> VertexId {
+        self.origin(self.twin(half_edge))
+    }
+    pub fn twin(&self, half_edge: HalfEdgeId) -> HalfEdgeId {
+        self.half_edge(half_edge).twin
+    }
+    pub fn next(&self, half_edge: HalfEdgeId) -> HalfEdgeId {
+        self.half_edge(half_edge).next
+    }
+    pub fn prev(&self, half_edge: HalfEdgeId) -> HalfEdgeId {
+        self.half_edge(half_edge).prev
+    }
+    pub fn face(&self, half_edge: HalfEdgeId) -> Option<FaceId> {
+        self.half_edge(half_edge).face
+    }
+    pub fn is_boundary(&self, half_edge: HalfEdgeId) -> bool {
+        self.face(half_edge).is_none()
+    }
+    /// half edge from `from` to `to`
+    pub fn find_half_edge(&self, from: VertexId, to: VertexId) -> Option<HalfEdgeId> {
+        self.outgoing_half_edges(from)
+            .find(|h| self.destination(*h) == to)
+    }
+
+    pub fn face_half_edges(&self, face: FaceId) -> [HalfEdgeId; 3] {
+        let first = self.face_data(face).half_edge;
+        let second = self.next(first);
+        [first, second, self.next(second)]
+    }
+    /// corners in counter clockwise order
+    pub fn face_vertices(&self, face: FaceId) -> [VertexId; 3] {
+        self.face_half_edges(face).map(|h| self.origin(h))
+    }
+
+    /// all half edges starting at the vertex
+    pub fn outgoing_half_edges(&self, vertex: VertexId) -> OneRingIterator<'_, P> {
+        let start = self.vertex(vertex).half_edge;
+        OneRingIterator {
+            mesh: self,
+            start,
+            current: start,
+        }
+    }
+    /// the one ring of the vertex
+    pub fn vertex_neighbors(&self, vertex: VertexId) -> impl Iterator<Item = VertexId> + '_ {
+        self.outgoing_half_edges(vertex)
+            .map(|h| self.destination(h))
+    }
+    pub fn vertex_faces(&self, vertex: VertexId) -> impl Iterator<Item = FaceId> + '_ {
+        self.outgoing_half_edges(vertex)
+            .filter_map(|h| self.face(h))
+    }
+
+    /// Replaces the two triangles on both sides of the edge by the two triangles on the other
+    /// diagonal.
+    pub fn flip_edge(&mut self, half_edge: HalfEdgeId) -> Result<(), HalfEdgeError> {
+        self.check_half_edge(half_edge)?;
+        let h = half_edge;
+        let t = self.twin(h);
+        let (Some(f0), Some(f1)) = (self.face(h), self.face(t)) else {
+            return Err(HalfEdgeError::BoundaryEdge);
+        };
+        // (a, b, c) and (b, a, d) become (d, c, a) and (c, d, b)
+        let [h_next, h_prev] = [self.next(h), self.prev(h)];
+        let [t_next, t_prev] = [self.next(t), self.prev(t)];
+        let a = self.origin(h);
+        let b = self.origin(t);
+        let c = self.origin(h_prev);
+        let d = self.origin(t_prev);
+        if c == d || self.find_half_edge(c, d).is_some() {
+            return Err(HalfEdgeError::ExistingEdge);
+        }
+        self.half_edge_mut(h).origin = d;
+        self.half_edge_mut(t).origin = c;
+        self.link_face(f0, [h, h_prev, t_next]);
+        self.link_face(f1, [t, t_prev, h_next]);
+        if self.vertex(a).half_edge == Some(h) {
+            self.vertex_mut(a).half_edge = Some(t_next);
+        }
+        if self.vertex(b).half_edge == Some(t) {
+            self.vertex_mut(b).half_edge = Some(h_next);
+        }
+        Ok(())
+    }
+
+    /// Inserts a new vertex on the edge and splits the triangles on both sides of it.
+    pub fn split_edge(
+        &mut self,
+        half_edge: HalfEdgeId,
+        point: P,
+    ) -> Result<VertexId, HalfEdgeError> {
+        self.check_half_edge(half_edge)?;
+        let h = half_edge;
+        let t = self.twin(h);
+        let middle = VertexId(self.vertices.len());
+        self.vertices.push(Some(Vertex {
+            point,
+            half_edge: None,
+        }));
+        // h: a -> m, h2: m -> b, t: b -> m, t2: m -> a
+        let h2 = self.push_half_edge(middle, None);
+        let t2 = self.push_half_edge(middle, None);
+        self.half_edge_mut(h).twin = t2;
+        self.half_edge_mut(t2).twin = h;
+        self.half_edge_mut(t).twin = h2;
+        self.half_edge_mut(h2).twin = t;
+        self.split_side(h, h2);
+        self.split_side(t, t2);
+        let middle_half_edge = if self.is_boundary(t2) { t2 } else { h2 };
+        self.vertex_mut(middle).half_edge = Some(middle_half_edge);
+        Ok(middle)
+    }
+
+    /// Merges the destination of the half edge into its origin, which is moved to `point`.
+    ///
+    /// The collapse is refused if it would create a non manifold mesh.
+    pub fn collapse_edge(
+        &mut self,
+        half_edge: HalfEdgeId,
+        point: P,
+    ) -> Result<VertexId, HalfEdgeError> {
+        self.check_half_edge(half_edge)?;
+        let h = half_edge;
+        let t = self.twin(h);
+        let a = self.origin(h);
+        let b = self.origin(t);
+        let opposite = |side: HalfEdgeId| self.face(side).map(|_| self.origin(self.prev(side)));
+        let tips: Vec<_> = [opposite(h), opposite(t)].into_iter().flatten().collect();
+
+        if !self.is_boundary(h)
+            && !self.is_boundary(t)
+            && self.is_boundary_vertex(a)
+            && self.is_boundary_vertex(b)
+        {
+            return Err(HalfEdgeError::NonManifoldResult);
+        }
+        // link condition on the vertices
+        let neighbors: HashSet<_> = self.vertex_neighbors(a).collect();
+        if self
+            .vertex_neighbors(b)
+            .any(|v| neighbors.contains(&v) && !tips.contains(&v))
+        {
+            return Err(HalfEdgeError::NonManifoldResult);
+        }
+        // link condition on the edges: the triangles left and right of the tips would merge
+        if let [c, d] = tips[..] {
+            let touches = |vertex: VertexId| {
+                self.vertex_faces(vertex).any(|f| {
+                    let corners = self.face_vertices(f);
+                    corners.contains(&c) && corners.contains(&d)
+                })
+            };
+            if touches(a) && touches(b) {
+                return Err(HalfEdgeError::NonManifoldResult);
+            }
+        }
+        // a triangle without neighbours would vanish completely
+        for side in [h, t] {
+            if !self.is_boundary(side)
+                && self.is_boundary(self.twin(self.next(side)))
+                && self.is_boundary(self.twin(self.prev(side)))
+            {
+                return Err(HalfEdgeError::NonManifoldResult);
+            }
+        }
+
+        let b_half_edges: Vec<_> = self.outgoing_half_edges(b).collect();
+        let mut touched = vec![a];
+        for side in [h, t] {
+            let next = self.next(side);
+            let prev = self.prev(side);
+            if let Some(face) = self.face(side) {
+                // side: x -> y, next: y -> c, prev: c -> x; the twins of next and prev are joined
+                let outer_next = self.twin(next);
+                let outer_prev = self.twin(prev);
+                self.half_edge_mut(outer_next).twin = outer_prev;
+                self.half_edge_mut(outer_prev).twin = outer_next;
+                let c = self.origin(prev);
+                self.vertex_mut(c).half_edge = Some(outer_next);
+                touched.push(c);
+                self.vertex_mut(a).half_edge = Some(outer_prev);
+                self.faces[face.0] = None;
+                self.half_edges[next.0] = None;
+                self.half_edges[prev.0] = None;
+            } else {
+                self.half_edge_mut(prev).next = next;
+                self.half_edge_mut(next).prev = prev;
+                self.vertex_mut(a).half_edge = Some(next);
+            }
+        }
+        self.half_edges[h.0] = None;
+        self.half_edges[t.0] = None;
+        for outgoing in b_half_edges {
+            if let Some(half_edge) = self.half_edges[outgoing.0].as_mut() {
+                half_edge.origin = a;
+            }
+        }
+        self.vertices[b.0] = None;
+        self.vertex_mut(a).point = point;
+        for vertex in touched {
+            self.prefer_boundary_half_edge(vertex);
+        }
+        Ok(a)
+    }
+
+    /// splits `first` (from x to the new middle point) and `second` (from the middle point to y)
+    /// off the old face of `first`, adding a triangle if the side is not on the boundary
+    fn split_side(&mut self, first: HalfEdgeId, second: HalfEdgeId) {
+        let next = self.next(first);
+        let prev = self.prev(first);
+        let middle = self.origin(second);
+        match self.face(first) {
+            None => {
+                self.link(first, second);
+                self.link(second, next);
+            }
+            Some(face) => {
+                let tip = self.origin(prev);
+                let new_face = FaceId(self.faces.len());
+                self.faces.push(Some(Face { half_edge: second }));
+                let to_tip = self.push_half_edge(middle, Some(face));
+                let from_tip = self.push_half_edge(tip, Some(new_face));
+                self.half_edge_mut(to_tip).twin = from_tip;
+                self.half_edge_mut(from_tip).twin = to_tip;
+                self.link_face(face, [first, to_tip, prev]);
+                self.link_face(new_face, [second, next, from_tip]);
+            }
+        }
+    }
+
+    fn link_face(&mut self, face: FaceId, half_edges: [HalfEdgeId; 3]) {
+        self.face_data_mut(face).half_edge = half_edges[0];
+        for idx in 0..3 {
+            self.half_edge_mut(half_edges[idx]).face = Some(face);
+            self.link(half_edges[idx], half_edges[(idx + 1) % 3]);
+        }
+    }
+
+    fn link(&mut self, half_edge: HalfEdgeId, next: HalfEdgeId) {
+        self.half_edge_mut(half_edge).next = next;
+        self.half_edge_mut(next).prev = half_edge;
+    }
+
+    fn prefer_boundary_half_edge(&mut self, vertex: VertexId) {
+        if let Some(boundary) = self
+            .outgoing_half_edges(vertex)
+            .find(|h| self.is_boundary(*h))
+        {
+            self.vertex_mut(vertex).half_edge = Some(boundary);
+        }
+    }
+
+    fn push_half_edge(&mut self, origin: VertexId, face: Option<FaceId>) -> HalfEdgeId {
+        let id = HalfEdgeId(self.half_edges.len());
+        self.half_edges.push(Some(HalfEdge {
+            origin,
+            twin: id,
+            next: id,
+            prev: id,
+            face,
+        }));
+        id
+    }
+
+    fn check_half_edge(&self, half_edge: HalfEdgeId) -> Result<(), HalfEdgeError> {
+        match self.half_edges.get(half_edge.0) {
+            Some(Some(_)) => Ok(()),
+            _ => Err(HalfEdgeError::MissingHalfEdge(half_edge.0)),
+        }
+    }
+
+    fn vertex(&self, vertex: VertexId) -> &Vertex<P> {
+        self.vertices[vertex.0]
+            .as_ref()
+            .expect("Vertex was removed")
+    }
+    fn vertex_mut(&mut self, vertex: VertexId) -> &mut Vertex<P> {
+        self.vertices[vertex.0]
+            .as_mut()
+            .expect("Vertex was removed")
+    }
+    fn half_edge(&self, half_edge: HalfEdgeId) -> &HalfEdge {
+        self.half_edges[half_edge.0]
+            .as_ref()
+            .expect("Half edge was removed")
+    }
+    fn half_edge_mut(&mut self, half_edge: HalfEdgeId) -> &mut HalfEdge {
+        self.half_edges[half_edge.0]
+            .as_mut()
+            .expect("Half edge was removed")
+    }
+    fn face_data(&self, face: FaceId) -> &Face {
+        self.faces[face.0].as_ref().expect("Face was removed")
+    }
+    fn face_data_mut(&mut self, face: FaceId) -> &mut Face {
+        self.faces[face.0].as_mut().expect("Face was removed")
+    }
+}
+
+fn ids<T>(entries: &[Option<T>]) -> impl Iterator<Item = usize> + '_ {
+    entries
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, entry)| entry.as_ref().map(|_| idx))
+}
+
+/// Rotates around a vertex over its outgoing half edges.
+pub struct OneRingIterator<'a, P: Point3d> {
+    mesh: &'a HalfEdgeMesh<P>,
+    start: Option<HalfEdgeId>,
+    current: Option<HalfEdgeId>,
+}
+
+impl<'a, P: Point3d> Iterator for OneRingIterator<'a, P> {
+    type Item = HalfEdgeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current?;
+        let next = self.mesh.twin(self.mesh.prev(current));
+        self.current = if Some(next) == self.start {
+            None
+        } else {
+            Some(next)
+        };
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry3d::triangles::half_edge::{HalfEdgeError, HalfEdgeMesh, VertexId};
+    use crate::prelude::{point_3d, IndexedTriangleList, Number, TriangleTopology, Vector3d};
+    use crate::test::{create_cube, create_grid};
+
+    fn assert_consistent(mesh: &HalfEdgeMesh<Vector3d>) {
+        for h in mesh.half_edges() {
+            assert_eq!(h, mesh.twin(mesh.twin(h)));
+            assert_eq!(h, mesh.prev(mesh.next(h)));
+            assert_ne!(mesh.origin(h), mesh.destination(h));
+            assert_eq!(mesh.destination(h), mesh.origin(mesh.next(h)));
+            assert_eq!(mesh.face(h), mesh.face(mesh.next(h)));
+        }
+        for f in mesh.faces() {
+            for h in mesh.face_half_edges(f) {
+                assert_eq!(Some(f), mesh.face(h));
+            }
+        }
+        for v in mesh.vertices() {
+            let outgoing: Vec<_> = mesh.outgoing_half_edges(v).collect();
+            assert!(outgoing.iter().all(|h| mesh.origin(*h) == v));
+            let boundary_count = outgoing.iter().filter(|h| mesh.is_boundary(**h)).count();
+            assert!(boundary_count <= 1);
+            assert_eq!(boundary_count == 1, mesh.is_boundary_vertex(v));
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cube = create_cube(2);
+        let mesh = HalfEdgeMesh::try_from(&cube).expect("Invalid mesh");
+        assert_consistent(&mesh);
+        assert_eq!(cube.points().len(), mesh.vertex_count());
+        assert_eq!(cube.triangles().len(), mesh.face_count());
+        assert_eq!(cube.triangles().len() * 3, mesh.half_edges().count());
+        assert!(mesh.vertices().all(|v| !mesh.is_boundary_vertex(v)));
+        assert_eq!(cube, IndexedTriangleList::from(&mesh));
+    }
+
+    #[test]
+    fn test_one_ring() {
+        let grid = create_grid(2);
+        let mesh = HalfEdgeMesh::try_from(&grid).expect("Invalid mesh");
+        assert_consistent(&mesh);
+        // center point of the grid
+        let mut neighbors: Vec<_> = mesh
+            .vertex_neighbors(VertexId(4))
+            .map(|v| v.idx())
+            .collect();
+        neighbors.sort();
+        assert_eq!(vec![0, 1, 3, 5, 7, 8], neighbors);
+        assert_eq!(6, mesh.vertex_faces(VertexId(4)).count());
+        assert!(!mesh.is_boundary_vertex(VertexId(4)));
+        // corner with one triangle
+        assert_eq!(2, mesh.vertex_neighbors(VertexId(2)).count());
+        assert_eq!(1, mesh.vertex_faces(VertexId(2)).count());
+        assert!(mesh.is_boundary_vertex(VertexId(2)));
+        assert_eq!(
+            8,
+            mesh.half_edges().filter(|h| mesh.is_boundary(*h)).count()
+        );
+    }
+
+    #[test]
+    fn test_non_manifold() {
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (-1, 0), (0, -1)] {
+            builder.append_point(point_3d(x as f64, y as f64, 0.0));
+        }
+        // two triangles touching at one point
+        builder.append_indexed_triangle(0, 1, 2);
+        builder.append_indexed_triangle(0, 3, 4);
+        let bow_tie = builder.build();
+        assert_eq!(
+            Some(HalfEdgeError::NonManifoldVertex(0)),
+            HalfEdgeMesh::try_from(&bow_tie).err()
+        );
+    }
+
+    #[test]
+    fn test_flip() {
+        let cube = create_cube(1);
+        let mut mesh = HalfEdgeMesh::try_from(&cube).expect("Invalid mesh");
+        let diagonal = mesh
+            .half_edges()
+            .find(|h| {
+                mesh.point(mesh.origin(*h))
+                    .distance(*mesh.point(mesh.destination(*h)))
+                    .0
+                    > 1.1
+            })
+            .expect("No diagonal");
+        let (a, b) = (mesh.origin(diagonal), mesh.destination(diagonal));
+        mesh.flip_edge(diagonal).expect("Cannot flip");
+        assert_consistent(&mesh);
+        assert!(mesh.find_half_edge(a, b).is_none());
+        assert_eq!(12, mesh.face_count());
+        let flipped = IndexedTriangleList::from(&mesh);
+        TriangleTopology::new(&flipped).expect("Flipped cube is not closed");
+        // flipping back restores the old edge
+        mesh.flip_edge(diagonal).expect("Cannot flip");
+        assert!(mesh.find_half_edge(a, b).is_some() || mesh.find_half_edge(b, a).is_some());
+
+        let grid = create_grid(1);
+        let mut mesh = HalfEdgeMesh::try_from(&grid).expect("Invalid mesh");
+        let boundary = mesh.half_edges().find(|h| mesh.is_boundary(*h)).unwrap();
+        assert_eq!(Err(HalfEdgeError::BoundaryEdge), mesh.flip_edge(boundary));
+    }
+
+    #[test]
+    fn test_split() {
+        let cube = create_cube(1);
+        let mut mesh = HalfEdgeMesh::try_from(&cube).expect("Invalid mesh");
+        let edge = mesh
+            .find_half_edge(VertexId(0), VertexId(1))
+            .expect("No edge");
+        let middle_point =
+            (*mesh.point(VertexId(0)) + *mesh.point(VertexId(1))) / Number::from(2.0);
+        let middle = mesh.split_edge(edge, middle_point).expect("Cannot split");
+        assert_consistent(&mesh);
+        assert_eq!(9, mesh.vertex_count());
+        assert_eq!(14, mesh.face_count());
+        assert_eq!(4, mesh.vertex_neighbors(middle).count());
+        TriangleTopology::new(&IndexedTriangleList::from(&mesh)).expect("Split cube is not closed");
+
+        let grid = create_grid(1);
+        let mut mesh = HalfEdgeMesh::try_from(&grid).expect("Invalid mesh");
+        let boundary = mesh.half_edges().find(|h| mesh.is_boundary(*h)).unwrap();
+        let middle = mesh
+            .split_edge(boundary, point_3d(0.5, 0.0, 0.0))
+            .expect("Cannot split");
+        assert_consistent(&mesh);
+        assert_eq!(3, mesh.face_count());
+        assert!(mesh.is_boundary_vertex(middle));
+        assert_eq!(3, mesh.vertex_neighbors(middle).count());
+    }
+
+    #[test]
+    fn test_collapse() {
+        let cube = create_cube(2);
+        let mut mesh = HalfEdgeMesh::try_from(&cube).expect("Invalid mesh");
+        // collapse the center of every face into one of its neighbors
+        let centers: Vec<_> = mesh
+            .vertices()
+            .filter(|v| mesh.point(*v).iter().filter(|c| c.0 == 0.5).count() == 2)
+            .collect();
+        assert_eq!(6, centers.len());
+        for center in centers {
+            let edge = mesh.outgoing_half_edges(center).next().unwrap();
+            let target = *mesh.point(mesh.destination(edge));
+            let kept = mesh.collapse_edge(edge, target).expect("Cannot collapse");
+            assert_eq!(center, kept);
+            assert_consistent(&mesh);
+        }
+        assert_eq!(cube.points().len() - 6, mesh.vertex_count());
+        assert_eq!(cube.triangles().len() - 12, mesh.face_count());
+        TriangleTopology::new(&IndexedTriangleList::from(&mesh))
+            .expect("Collapsed cube is not closed");
+
+        // a tetrahedron cannot lose any more points
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        for (x, y, z) in [(0, 0, 0), (1, 0, 0), (0, 1, 0), (0, 0, 1)] {
+            builder.append_point(point_3d(x as f64, y as f64, z as f64));
+        }
+        for [p1, p2, p3] in [[0, 2, 1], [0, 1, 3], [1, 2, 3], [0, 3, 2]] {
+            builder.append_indexed_triangle(p1, p2, p3);
+        }
+        let tetrahedron = builder.build();
+        let mut mesh = HalfEdgeMesh::try_from(&tetrahedron).expect("Invalid mesh");
+        let edge = mesh.half_edges().next().unwrap();
+        assert_eq!(
+            Err(HalfEdgeError::NonManifoldResult),
+            mesh.collapse_edge(edge, point_3d(0.0, 0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn test_collapse_boundary() {
+        let grid = create_grid(2);
+        let mut mesh = HalfEdgeMesh::try_from(&grid).expect("Invalid mesh");
+        // boundary edge between the points 0 and 1
+        let edge = mesh.find_half_edge(VertexId(1), VertexId(0)).unwrap();
+        assert!(mesh.is_boundary(edge) || mesh.is_boundary(mesh.twin(edge)));
+        mesh.collapse_edge(edge, point_3d(0.5, 0.0, 0.0))
+            .expect("Cannot collapse");
+        assert_consistent(&mesh);
+        assert_eq!(8, mesh.vertex_count());
+        assert_eq!(7, mesh.face_count());
+        assert_eq!(
+            7,
+            mesh.half_edges().filter(|h| mesh.is_boundary(*h)).count()
+        );
+        // inner edge between two boundary points would pinch the surface
+        let inner = mesh.find_half_edge(VertexId(1), VertexId(5)).unwrap();
+        assert_eq!(
+            Err(HalfEdgeError::NonManifoldResult),
+            mesh.collapse_edge(inner, point_3d(1.0, 0.0, 0.0))
+        );
+    }
+}
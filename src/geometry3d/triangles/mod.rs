@@ -4,53 +4,79 @@ use std::ptr::eq;
 
 use stl_io::{IndexedMesh, IndexedTriangle, Vector, Vertex};
 
-use crate::generic_triangle::{TriangleCornerPoint, TriangleSide};
+use crate::generic_triangle::TriangleCornerPoint;
 use crate::geometry3d::plane::Plane3d;
 use crate::geometry3d::point::Point3d;
 use crate::geometry3d::triangle::Triangle3d;
+use crate::geometry3d::triangles::attributes::{
+    AttributeBuilder, AttributeError, FaceAttributes, VertexAttributes,
+};
 use crate::geometry3d::triangles::indexed_point::IndexedPoint;
 
+pub mod attributes;
+pub mod compare;
+pub mod decimate;
+pub mod half_edge;
 pub mod indexed_point;
+pub mod normals;
+pub mod properties;
+pub mod simplify;
 pub mod topology;
+pub mod validate;
 
 #[derive(Clone, Debug, PartialEq, Hash)]
 pub struct IndexedTriangleList<P: Point3d> {
     points: Box<[P]>,
     triangles: Box<[IndexedTriangleEntry]>,
+    vertex_attributes: VertexAttributes,
+    face_attributes: FaceAttributes,
 }
 
 pub struct TriangleListBuilder<P: Point3d> {
     points: Vec<P>,
     triangles: Vec<IndexedTriangleEntry>,
+    pub(crate) attributes: AttributeBuilder,
 }
 
 impl<P: Point3d> TriangleListBuilder<P> {
-    fn append_point(&mut self, point: P) -> usize {
+    pub(crate) fn append_point(&mut self, point: P) -> usize {
         self.points.push(point);
         self.points.len() - 1
     }
-    fn append_indexed_triangle(&mut self, p1: usize, p2: usize, p3: usize) -> usize {
+    pub(crate) fn point_count(&self) -> usize {
+        self.points.len()
+    }
+    pub(crate) fn append_indexed_triangle(&mut self, p1: usize, p2: usize, p3: usize) -> usize {
         assert!(p1 < self.points.len());
         assert!(p2 < self.points.len());
         assert!(p3 < self.points.len());
         self.triangles.push(IndexedTriangleEntry { p1, p2, p3 });
         self.triangles.len() - 1
     }
-    fn build(self) -> IndexedTriangleList<P> {
+    pub(crate) fn build(self) -> IndexedTriangleList<P> {
+        let (vertex_attributes, face_attributes) = self
+            .attributes
+            .build(self.points.len(), self.triangles.len());
         let points = self.points.into_boxed_slice();
         let triangles = self.triangles.into_boxed_slice();
-        IndexedTriangleList { points, triangles }
+        IndexedTriangleList {
+            points,
+            triangles,
+            vertex_attributes,
+            face_attributes,
+        }
     }
 }
 
 impl<P: Point3d> IndexedTriangleList<P> {
-    fn builder<Pn: Point3d>() -> TriangleListBuilder<Pn> {
+    pub(crate) fn builder<Pn: Point3d>() -> TriangleListBuilder<Pn> {
         TriangleListBuilder {
             points: Vec::<Pn>::new(),
             triangles: vec![],
+            attributes: AttributeBuilder::default(),
         }
     }
-    pub fn triangles(&self) -> Vec<ReferencedTriangle<P>> {
+    pub fn triangles(&self) -> Vec<ReferencedTriangle<'_, P>> {
         self.triangles
             .iter()
             .enumerate()
@@ -58,7 +84,11 @@ impl<P: Point3d> IndexedTriangleList<P> {
             .collect()
     }
 
-    fn create_triangle(&self, idx: usize, entry: &IndexedTriangleEntry) -> ReferencedTriangle<P> {
+    fn create_triangle(
+        &self,
+        idx: usize,
+        entry: &IndexedTriangleEntry,
+    ) -> ReferencedTriangle<'_, P> {
         let p1 = IndexedPoint::new(self, entry.p1);
         let p2 = IndexedPoint::new(self, entry.p2);
         let p3 = IndexedPoint::new(self, entry.p3);
@@ -70,7 +100,7 @@ impl<P: Point3d> IndexedTriangleList<P> {
             p3,
         }
     }
-    pub fn get_triangle(&self, idx: usize) -> Option<ReferencedTriangle<P>> {
+    pub fn get_triangle(&self, idx: usize) -> Option<ReferencedTriangle<'_, P>> {
         self.triangles
             .get(idx)
             .map(|entry| self.create_triangle(idx, entry))
@@ -80,6 +110,40 @@ impl<P: Point3d> IndexedTriangleList<P> {
         &self.points
     }
 
+    pub fn vertex_attributes(&self) -> &VertexAttributes {
+        &self.vertex_attributes
+    }
+    pub fn face_attributes(&self) -> &FaceAttributes {
+        &self.face_attributes
+    }
+    pub fn with_vertex_attributes(
+        mut self,
+        attributes: VertexAttributes,
+    ) -> Result<Self, AttributeError> {
+        attributes.check(self.points.len())?;
+        self.vertex_attributes = attributes;
+        Ok(self)
+    }
+    pub fn with_face_attributes(
+        mut self,
+        attributes: FaceAttributes,
+    ) -> Result<Self, AttributeError> {
+        attributes.check(self.triangles.len())?;
+        self.face_attributes = attributes;
+        Ok(self)
+    }
+
+    /// Reverses the orientation of all triangles, the vertex normals are kept unchanged.
+    pub fn flipped(mut self) -> Self {
+        for entry in self.triangles.iter_mut() {
+            std::mem::swap(&mut entry.p2, &mut entry.p3);
+        }
+        self
+    }
+
+    /// Moves the points, the attributes are kept unchanged. Use
+    /// [`Transform3d::apply_mesh`](crate::prelude::Transform3d::apply_mesh) for affine
+    /// transformations, which also handles normals and mirroring.
     pub fn transform_points<T, Pt>(self, transform: T) -> IndexedTriangleList<Pt>
     where
         T: FnMut(&P) -> Pt,
@@ -88,6 +152,8 @@ impl<P: Point3d> IndexedTriangleList<P> {
         IndexedTriangleList {
             points: self.points.iter().map(transform).collect(),
             triangles: self.triangles,
+            vertex_attributes: self.vertex_attributes,
+            face_attributes: self.face_attributes,
         }
     }
 }
@@ -100,24 +166,10 @@ pub struct IndexedTriangleEntry {
 }
 
 impl IndexedTriangleEntry {
-    #[inline]
-    fn get_point(&self, p: TriangleCornerPoint) -> usize {
-        match p {
-            TriangleCornerPoint::P1 => self.p1,
-            TriangleCornerPoint::P2 => self.p2,
-            TriangleCornerPoint::P3 => self.p3,
-        }
-    }
     #[inline]
     fn points(&self) -> [usize; 3] {
         [self.p1, self.p2, self.p3]
     }
-    fn get_side(&self, side: TriangleSide) -> (usize, usize) {
-        (
-            self.get_point(side.start_corner()),
-            self.get_point(side.end_corner()),
-        )
-    }
     fn sides(&self) -> [[usize; 2]; 3] {
         [[self.p1, self.p2], [self.p2, self.p3], [self.p3, self.p1]]
     }
@@ -253,6 +305,6 @@ mod test {
             .map(|mesh| mesh.faces.iter().map(|f| f.vertices).collect::<Vec<_>>())
             .collect::<Vec<_>>();
         // compare triangles (without normals)
-        assert_eq!(triangles.get(0), triangles.get(1));
+        assert_eq!(triangles.first(), triangles.get(1));
     }
 }
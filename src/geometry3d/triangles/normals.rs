@@ -0,0 +1,200 @@
+use num_traits::Zero;
+
+use crate::geometry3d::line::Line3d;
+use crate::geometry3d::plane::Plane3d;
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::triangles::topology::{
+    collect_line_neighbors, LineNeighbors, TopologyError,
+};
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::geometry3d::Vector3d;
+use crate::primitives::Number;
+
+impl<P: Point3d> IndexedTriangleList<P> {
+    /// Calculates angle weighted vertex normals and stores them as vertex attribute.
+    ///
+    /// Points on edges whose triangles meet at more than `crease_angle` (in radians) are split, so
+    /// every side of a sharp edge gets its own normal. Other vertex attributes are copied to the
+    /// split points, the triangles keep their order and face attributes.
+    pub fn vertex_normals(
+        &self,
+        crease_angle: Number,
+    ) -> Result<IndexedTriangleList<P>, TopologyError<'_, P>> {
+        let triangles = self.triangles();
+        let face_normals: Vec<_> = self
+            .triangles
+            .iter()
+            .map(|t| {
+                let [p1, p2, p3] = t.points().map(|p| self.points[p].coordinates());
+                Plane3d::from_points(p1, p2, p3)
+                    .map(|plane| plane.normal())
+                    .ok()
+            })
+            .collect();
+
+        // corners of the same point which are connected by smooth edges share a normal
+        let mut corners = CornerSets::new(self.triangles.len() * 3);
+        let min_cos = crease_angle.cos();
+        for (edge, neighbors) in collect_line_neighbors(&triangles)? {
+            let LineNeighbors::Both { forward, backward } = neighbors else {
+                continue;
+            };
+            let (Some(n1), Some(n2)) = (face_normals[forward.idx()], face_normals[backward.idx()])
+            else {
+                continue;
+            };
+            if n1.dot(n2).0 < min_cos {
+                continue;
+            }
+            for point in [edge.p1().idx(), edge.p2().idx()] {
+                corners.union(
+                    self.corner_idx(forward.idx(), point),
+                    self.corner_idx(backward.idx(), point),
+                );
+            }
+        }
+
+        let mut normals = vec![Vector3d::zero(); corners.len()];
+        for (triangle_idx, triangle) in self.triangles.iter().enumerate() {
+            let Some(face_normal) = face_normals[triangle_idx] else {
+                continue;
+            };
+            let points = triangle
+                .points()
+                .map(|p| self.points[p].coordinates().map(|c| c.0));
+            for corner in 0..3 {
+                let to_next = points[(corner + 1) % 3] - points[corner];
+                let to_prev = points[(corner + 2) % 3] - points[corner];
+                let angle = Number::from(to_next.angle_between(to_prev));
+                normals[corners.find(triangle_idx * 3 + corner)] += face_normal * angle;
+            }
+        }
+
+        let mut builder = Self::builder();
+        let mut new_points = vec![None; corners.len()];
+        let mut original_points = Vec::new();
+        let mut point_normals = Vec::new();
+        let mut new_triangles = Vec::with_capacity(self.triangles.len());
+        for (triangle_idx, triangle) in self.triangles.iter().enumerate() {
+            let new_triangle = triangle.points().map(|point| {
+                let root = corners.find(self.corner_idx(triangle_idx, point));
+                *new_points[root].get_or_insert_with(|| {
+                    let normal = normals[root];
+                    // points only used by degenerated triangles get no direction
+                    point_normals.push(if normal.magnitude_squared() > Number::zero() {
+                        normal.normalized()
+                    } else {
+                        normal
+                    });
+                    original_points.push(point);
+                    builder.append_point(self.points[point].clone())
+                })
+            });
+            new_triangles.push(new_triangle);
+        }
+        for [p1, p2, p3] in new_triangles {
+            builder.append_indexed_triangle(p1, p2, p3);
+        }
+        let mut result = builder.build();
+        result.vertex_attributes = self
+            .vertex_attributes
+            .select(&original_points)
+            .with_normals(point_normals);
+        result.face_attributes = self.face_attributes.clone();
+        Ok(result)
+    }
+
+    fn corner_idx(&self, triangle: usize, point: usize) -> usize {
+        let corner = self.triangles[triangle]
+            .points()
+            .iter()
+            .position(|p| *p == point)
+            .expect("Point is not part of the triangle");
+        triangle * 3 + corner
+    }
+}
+
+/// union find over the triangle corners
+struct CornerSets {
+    parents: Vec<usize>,
+}
+
+impl CornerSets {
+    fn new(count: usize) -> Self {
+        Self {
+            parents: (0..count).collect(),
+        }
+    }
+    fn len(&self) -> usize {
+        self.parents.len()
+    }
+    fn find(&mut self, mut idx: usize) -> usize {
+        while self.parents[idx] != idx {
+            self.parents[idx] = self.parents[self.parents[idx]];
+            idx = self.parents[idx];
+        }
+        idx
+    }
+    fn union(&mut self, first: usize, second: usize) {
+        let first = self.find(first);
+        let second = self.find(second);
+        if first != second {
+            self.parents[second] = first;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::prelude::{point_3d, Number, Point3d, Triangle3d, TriangleTopology};
+    use crate::test::create_cube;
+
+    #[test]
+    fn test_hard_edges() {
+        let cube = create_cube(2);
+        let shaded = cube
+            .vertex_normals(Number::from(PI / 6.0))
+            .expect("Topology error");
+        // every face gets its own 3x3 points
+        assert_eq!(6 * 9, shaded.points().len());
+        assert_eq!(cube.triangles().len(), shaded.triangles().len());
+        let normals = shaded
+            .vertex_attributes()
+            .normals()
+            .expect("Missing normals");
+        for (point, normal) in shaded.points().iter().zip(normals) {
+            // axis aligned and pointing outwards
+            assert_eq!(Number::from(1.0), normal.dot(*normal));
+            let outwards = *point - point_3d(0.5, 0.5, 0.5);
+            assert!(outwards.dot(*normal) > Number::from(0.0));
+            assert_eq!(2, normal.iter().filter(|c| c.0 == 0.0).count());
+        }
+        // the split points form open faces, the triangles themselves are unchanged
+        for (original, split) in cube.triangles().iter().zip(shaded.triangles()) {
+            let original = original.points().map(|p| p.coordinates());
+            let split = split.points().map(|p| p.coordinates());
+            assert_eq!(original, split);
+        }
+        assert!(TriangleTopology::new(&shaded).is_err());
+    }
+
+    #[test]
+    fn test_smooth() {
+        let cube = create_cube(1);
+        let shaded = cube
+            .vertex_normals(Number::from(PI))
+            .expect("Topology error");
+        assert_eq!(cube.points().len(), shaded.points().len());
+        let normals = shaded
+            .vertex_attributes()
+            .normals()
+            .expect("Missing normals");
+        for (point, normal) in shaded.points().iter().zip(normals) {
+            let diagonal = (*point - point_3d(0.5, 0.5, 0.5)).normalized();
+            assert!(diagonal.distance(*normal).0 < 1e-12);
+        }
+        TriangleTopology::new(&shaded).expect("Smooth mesh is not closed");
+    }
+}
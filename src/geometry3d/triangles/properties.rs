@@ -0,0 +1,247 @@
+use vek::{Mat3, Vec3};
+
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::triangles::topology::{
+    collect_line_neighbors, LineNeighbors, TopologyError,
+};
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::geometry3d::Vector3d;
+use crate::primitives::Number;
+
+/// Volume related properties of a closed mesh, assuming a solid body of density 1
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MeshProperties {
+    volume: Number,
+    surface_area: Number,
+    centroid: Vector3d,
+    inertia: Mat3<Number>,
+    principal_moments: Vector3d,
+    principal_axes: [Vector3d; 3],
+}
+
+impl MeshProperties {
+    /// negative if the triangles point inwards
+    pub fn volume(&self) -> Number {
+        self.volume
+    }
+    pub fn surface_area(&self) -> Number {
+        self.surface_area
+    }
+    /// center of mass
+    pub fn centroid(&self) -> Vector3d {
+        self.centroid
+    }
+    /// inertia tensor relative to the centroid, multiply by the density for real bodies
+    pub fn inertia(&self) -> Mat3<Number> {
+        self.inertia
+    }
+    /// eigenvalues of the inertia tensor in ascending order
+    pub fn principal_moments(&self) -> Vector3d {
+        self.principal_moments
+    }
+    /// normalized eigenvectors of the inertia tensor in the order of the principal moments
+    pub fn principal_axes(&self) -> [Vector3d; 3] {
+        self.principal_axes
+    }
+    pub fn mass(&self, density: Number) -> Number {
+        self.volume * density
+    }
+}
+
+impl<P: Point3d> IndexedTriangleList<P> {
+    /// Calculates volume, area, centroid and inertia by summing up the tetrahedrons between the
+    /// origin and every triangle. Fails if the mesh is not closed.
+    pub fn properties(&self) -> Result<MeshProperties, TopologyError<'_, P>> {
+        let triangles = self.triangles();
+        for neighbors in collect_line_neighbors(&triangles)?.into_values() {
+            match neighbors {
+                LineNeighbors::OnlyForward(triangle) | LineNeighbors::OnlyBackward(triangle) => {
+                    return Err(TopologyError::MissingNeighborError(triangle));
+                }
+                LineNeighbors::None | LineNeighbors::Both { .. } => {}
+            }
+        }
+
+        let mut volume = 0.0;
+        let mut surface_area = 0.0;
+        let mut first_moment = Vec3::<f64>::zero();
+        // integral of x * x^T over the body
+        let mut second_moment = Mat3::<f64>::zero();
+        for triangle in self.triangles.iter() {
+            let [a, b, c] = triangle
+                .points()
+                .map(|p| self.points[p].coordinates().map(|c| c.0));
+            let det = a.dot(b.cross(c));
+            volume += det / 6.0;
+            surface_area += (b - a).cross(c - a).magnitude() / 2.0;
+            let sum = a + b + c;
+            first_moment += sum * (det / 24.0);
+            let outer = |v: Vec3<f64>| {
+                Mat3::<f64>::from_col_arrays([
+                    (v * v.x).into_array(),
+                    (v * v.y).into_array(),
+                    (v * v.z).into_array(),
+                ])
+            };
+            second_moment += (outer(a) + outer(b) + outer(c) + outer(sum)) * (det / 120.0);
+        }
+        let centroid = if volume != 0.0 {
+            first_moment / volume
+        } else {
+            Vec3::zero()
+        };
+        let covariance = second_moment
+            - Mat3::<f64>::from_col_arrays([
+                (centroid * centroid.x).into_array(),
+                (centroid * centroid.y).into_array(),
+                (centroid * centroid.z).into_array(),
+            ]) * volume;
+        let trace = covariance.trace();
+        let inertia = Mat3::<f64>::identity() * trace - covariance;
+        let (moments, axes) = symmetric_eigen(inertia);
+
+        Ok(MeshProperties {
+            volume: volume.into(),
+            surface_area: surface_area.into(),
+            centroid: centroid.map(Number::from),
+            inertia: inertia.map(Number::from),
+            principal_moments: moments.map(Number::from),
+            principal_axes: axes.map(|axis| axis.map(Number::from)),
+        })
+    }
+}
+
+/// Jacobi eigenvalue iteration, eigenvalues are sorted ascending
+fn symmetric_eigen(matrix: Mat3<f64>) -> (Vec3<f64>, [Vec3<f64>; 3]) {
+    let mut a = matrix.into_row_arrays();
+    let mut v = Mat3::<f64>::identity().into_row_arrays();
+    for _ in 0..50 {
+        let (p, q) = [(0, 1), (0, 2), (1, 2)]
+            .into_iter()
+            .max_by(|(p1, q1), (p2, q2)| a[*p1][*q1].abs().total_cmp(&a[*p2][*q2].abs()))
+            .expect("Three entries");
+        let scale = a.iter().flatten().map(|x| x.abs()).fold(0.0, f64::max);
+        if a[p][q].abs() <= scale * 1e-15 {
+            break;
+        }
+        let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+        let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+        let t = if theta == 0.0 { 1.0 } else { t };
+        let c = 1.0 / (t * t + 1.0).sqrt();
+        let s = t * c;
+        // a = J^T * a * J, v = v * J
+        for row in a.iter_mut().chain(v.iter_mut()) {
+            let (rp, rq) = (row[p], row[q]);
+            row[p] = c * rp - s * rq;
+            row[q] = s * rp + c * rq;
+        }
+        let (row_p, row_q) = (a[p], a[q]);
+        for (k, (apk, aqk)) in row_p.into_iter().zip(row_q).enumerate() {
+            a[p][k] = c * apk - s * aqk;
+            a[q][k] = s * apk + c * aqk;
+        }
+    }
+    let mut pairs: Vec<_> = (0..3)
+        .map(|i| (a[i][i], Vec3::new(v[0][i], v[1][i], v[2][i]).normalized()))
+        .collect();
+    pairs.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    (
+        Vec3::new(pairs[0].0, pairs[1].0, pairs[2].0),
+        [pairs[0].1, pairs[1].1, pairs[2].1],
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use vek::{Quaternion, Vec3};
+
+    use crate::geometry3d::triangles::properties::MeshProperties;
+    use crate::prelude::{point_3d, IndexedTriangleList, Number, Vector3d};
+    use crate::test::{create_cube, load_schublade_as_triangles};
+
+    fn properties(mesh: &IndexedTriangleList<Vector3d>) -> MeshProperties {
+        mesh.properties().expect("Mesh is not closed")
+    }
+
+    #[test]
+    fn test_unit_cube() {
+        let properties = properties(&create_cube(3));
+        assert_relative_eq!(1.0, properties.volume().0, epsilon = 1e-12);
+        assert_relative_eq!(6.0, properties.surface_area().0, epsilon = 1e-12);
+        for c in properties.centroid() {
+            assert_relative_eq!(0.5, c.0, epsilon = 1e-12);
+        }
+        let inertia = properties.inertia().into_row_arrays();
+        for (row, values) in inertia.iter().enumerate() {
+            for (column, value) in values.iter().enumerate() {
+                let expected = if row == column { 1.0 / 6.0 } else { 0.0 };
+                assert_relative_eq!(expected, value.0, epsilon = 1e-12);
+            }
+        }
+        assert_relative_eq!(7.5, properties.mass(7.5.into()).0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_rotated_box() {
+        let rotation = Quaternion::<f64>::rotation_z(0.3) * Quaternion::rotation_x(0.5);
+        let cuboid = create_cube(2).transform_points(|p| {
+            let p = p.map(|c| c.0) * Vec3::new(4.0, 2.0, 1.0);
+            (rotation * p + Vec3::new(1.0, -2.0, 3.0)).map(Number::from)
+        });
+        let properties = properties(&cuboid);
+        assert_relative_eq!(8.0, properties.volume().0, epsilon = 1e-9);
+        assert_relative_eq!(
+            2.0 * (8.0 + 4.0 + 2.0),
+            properties.surface_area().0,
+            epsilon = 1e-9
+        );
+        let center = rotation * Vec3::new(2.0, 1.0, 0.5) + Vec3::new(1.0, -2.0, 3.0);
+        assert_relative_eq!(
+            0.0,
+            (properties.centroid().map(|c| c.0) - center).magnitude(),
+            epsilon = 1e-9
+        );
+        // m/12 * (b² + c²) for every axis
+        let moments = properties.principal_moments();
+        for (moment, expected) in moments.iter().zip([10.0 / 3.0, 34.0 / 3.0, 40.0 / 3.0]) {
+            assert_relative_eq!(expected, moment.0, epsilon = 1e-9);
+        }
+        // the smallest moment belongs to the longest side
+        let long_axis = rotation * Vec3::unit_x();
+        let axis = properties.principal_axes()[0].map(|c| c.0);
+        assert_relative_eq!(1.0, axis.dot(long_axis).abs(), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_inverted_and_open() {
+        let inverted = create_cube(1).transform_points(|p| point_3d(p.x, p.z, p.y));
+        assert_relative_eq!(-1.0, properties(&inverted).volume().0, epsilon = 1e-12);
+
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)] {
+            builder.append_point(point_3d(x, y, 0.0));
+        }
+        builder.append_indexed_triangle(0, 1, 2);
+        assert!(builder.build().properties().is_err());
+    }
+
+    #[test]
+    fn test_schublade() {
+        let triangles = load_schublade_as_triangles();
+        let properties = triangles.properties().expect("Mesh is not closed");
+        assert_relative_eq!(4613769.3126, properties.volume().0, epsilon = 1e-3);
+        assert_relative_eq!(665845.1478, properties.surface_area().0, epsilon = 1e-3);
+        // symmetric to the yz plane
+        let centroid = properties.centroid().map(|c| c.0);
+        assert_relative_eq!(0.0, centroid.x, epsilon = 1e-9);
+        assert_relative_eq!(-7.8295, centroid.y, epsilon = 1e-3);
+        assert_relative_eq!(228.3056, centroid.z, epsilon = 1e-3);
+        let moments = properties.principal_moments();
+        for (moment, expected) in moments.iter().zip([7.8648e10, 1.72411e11, 2.50875e11]) {
+            assert_relative_eq!(expected, moment.0, max_relative = 1e-5);
+        }
+        let axis = properties.principal_axes()[0].map(|c| c.0);
+        assert_relative_eq!(1.0, axis.x.abs(), epsilon = 1e-9);
+    }
+}
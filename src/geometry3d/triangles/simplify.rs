@@ -0,0 +1,376 @@
+use std::collections::{HashMap, HashSet};
+
+use log::debug;
+use num_traits::Zero;
+use triangulate::{formats, ListFormat, PolygonList};
+
+use crate::geometry3d::plane::Plane3d;
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::triangle::Triangle3d;
+use crate::geometry3d::triangles::topology::triangle_group::TriangleGroup;
+use crate::geometry3d::triangles::topology::{TopologyError, TriangleTopology};
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::prelude::{
+    Line3d, PlaneProjection, Point2d, StaticPoint2d, StaticTriangle2d, Triangle2d,
+};
+use crate::primitives::Number;
+
+impl<P: Point3d> IndexedTriangleList<P> {
+    /// Replaces the triangles of every flat region by a minimal triangulation of its outline.
+    ///
+    /// Points inside a region are dropped. Points on straight parts of an outline are dropped as
+    /// well if all regions using them drop them, so the result stays watertight. Regions which
+    /// cannot be retriangulated (touching outlines, degenerated results) are copied unchanged.
+    /// Triangles with different face attributes are never merged.
+    pub fn simplify_planes(&self) -> Result<IndexedTriangleList<P>, TopologyError<'_, P>> {
+        let topology = TriangleTopology::new(self)?;
+        let face_attributes = &self.face_attributes;
+        let face_key = |idx: usize| {
+            (
+                face_attributes.materials().map(|m| m[idx]),
+                face_attributes.groups().map(|g| g[idx]),
+            )
+        };
+        let mut groups: Vec<_> = topology
+            .plane_groups()
+            .iter()
+            .flat_map(|(plane, planes)| {
+                let mut triangles_by_key = HashMap::<_, Vec<_>>::new();
+                for triangle in planes
+                    .iter()
+                    .filter_map(|p| topology.triangles_of_plane().get(p))
+                    .flat_map(|group| group.triangles().iter().cloned())
+                {
+                    triangles_by_key
+                        .entry(face_key(triangle.idx()))
+                        .or_default()
+                        .push(triangle);
+                }
+                triangles_by_key
+                    .into_iter()
+                    .map(move |(key, triangles)| (plane, key, TriangleGroup::new(triangles)))
+            })
+            .collect();
+        // keep the result independent of the hash map order
+        groups.sort_by_key(|(_, _, group)| group.triangles().iter().map(|t| t.idx()).min());
+
+        // outline corners stay, points on straight outline parts only if a neighbour needs them
+        let mut kept_outline_points = HashSet::new();
+        for (_, _, group) in groups.iter() {
+            for outline in group.edges() {
+                for (idx, line) in outline.iter().enumerate() {
+                    let previous = &outline[(idx + outline.len() - 1) % outline.len()];
+                    if !is_straight(&previous.p1(), &line.p1(), &line.p2()) {
+                        kept_outline_points.insert(line.p1().idx());
+                    }
+                }
+            }
+        }
+        let retriangulated = loop {
+            let retriangulated: Vec<_> = groups
+                .iter()
+                .map(|(plane, _, group)| retriangulate_group(plane, group, &kept_outline_points))
+                .collect();
+            // groups keeping their triangles keep all their points for the neighbours as well
+            let mut changed = false;
+            for ((_, _, group), triangles) in groups.iter().zip(&retriangulated) {
+                if triangles.is_none() {
+                    for triangle in group.triangles() {
+                        for p in triangle.points() {
+                            changed |= kept_outline_points.insert(p.idx());
+                        }
+                    }
+                }
+            }
+            if !changed {
+                break retriangulated;
+            }
+        };
+
+        let mut triangles = Vec::with_capacity(self.triangles.len());
+        for ((_, key, group), new_triangles) in groups.iter().zip(retriangulated) {
+            let new_triangles = new_triangles.unwrap_or_else(|| {
+                group
+                    .triangles()
+                    .iter()
+                    .map(|t| t.points().map(|p| p.idx()))
+                    .collect()
+            });
+            triangles.extend(new_triangles.into_iter().map(|t| (t, *key)));
+        }
+
+        let mut used_points = vec![false; self.points.len()];
+        for idx in triangles.iter().flat_map(|(t, _)| t) {
+            used_points[*idx] = true;
+        }
+        let mut new_index = vec![0; self.points.len()];
+        let mut kept_points = Vec::with_capacity(self.points.len());
+        let mut builder = Self::builder();
+        for (idx, point) in self.points.iter().enumerate() {
+            if used_points[idx] {
+                new_index[idx] = builder.append_point(point.clone());
+                kept_points.push(idx);
+            }
+        }
+        builder
+            .attributes
+            .set_group_names(self.face_attributes.group_names().to_vec());
+        for ([p1, p2, p3], (material, group)) in triangles {
+            let idx = builder.append_indexed_triangle(new_index[p1], new_index[p2], new_index[p3]);
+            if let Some(material) = material {
+                builder.attributes.set_material(idx, material);
+            }
+            if let Some(group) = group {
+                builder.attributes.set_group_index(idx, group);
+            }
+        }
+        let mut simplified = builder.build();
+        simplified.vertex_attributes = self.vertex_attributes.select(&kept_points);
+        Ok(simplified)
+    }
+}
+
+/// Triangulates the outline of a flat group without the outline points which are not kept,
+/// returns `None` if the original triangles should be kept.
+fn retriangulate_group<P: Point3d>(
+    plane: &Plane3d,
+    group: &TriangleGroup<P>,
+    kept_outline_points: &HashSet<usize>,
+) -> Option<Vec<[usize; 3]>> {
+    let outlines: Vec<Vec<_>> = group
+        .edges()
+        .iter()
+        .map(|outline| {
+            outline
+                .iter()
+                .map(|line| line.p1())
+                .filter(|p| kept_outline_points.contains(&p.idx()))
+                .collect()
+        })
+        .collect();
+    let point_count: usize = outlines.iter().map(Vec::len).sum();
+    let mut distinct_points = HashSet::with_capacity(point_count);
+    if !outlines
+        .iter()
+        .flatten()
+        .all(|p| distinct_points.insert(p.idx()))
+    {
+        // outlines touching each other cannot be triangulated
+        return None;
+    }
+
+    let projection = PlaneProjection::new(plane);
+    let polygons: Vec<Vec<_>> = outlines
+        .iter()
+        .map(|outline| {
+            outline
+                .iter()
+                .map(|p| projection.project_point(p))
+                .collect()
+        })
+        .collect();
+    let mut triangulated_indices = Vec::<[usize; 2]>::new();
+    if let Err(error) = polygons
+        .triangulate(formats::IndexedListFormat::new(&mut triangulated_indices).into_fan_format())
+    {
+        debug!("Cannot triangulate plane {plane:?}: {error}");
+        return None;
+    }
+    let offsets: Vec<usize> = outlines
+        .iter()
+        .scan(0, |offset, outline| {
+            let start = *offset;
+            *offset += outline.len();
+            Some(start)
+        })
+        .collect();
+    let points: Vec<_> = polygons.iter().flatten().map(|p| p.coordinates()).collect();
+    let mut triangles: Vec<[usize; 3]> = triangulated_indices
+        .chunks_exact(3)
+        .map(|corners| {
+            [corners[0], corners[1], corners[2]].map(|[outline, idx]| offsets[outline] + idx)
+        })
+        .collect();
+    if triangles.len() >= group.triangles().len() {
+        return None;
+    }
+    if !fix_degenerated_triangles(&points, &mut triangles) {
+        debug!("Degenerated triangles on plane {plane:?}");
+        return None;
+    }
+
+    let original_area: Number = group
+        .triangles()
+        .iter()
+        .map(|t| projection.project_triangle(t).area())
+        .sum();
+    let mut new_area = Number::zero();
+    for triangle in triangles.iter() {
+        let area = corner_triangle(&points, triangle).area();
+        if area <= Number::zero() {
+            debug!("Inverted triangle on plane {plane:?}");
+            return None;
+        }
+        new_area += area;
+    }
+    // holes or overlaps which are not detected by the triangulation
+    if (new_area - original_area).0.abs() > original_area.0 * 1e-9 {
+        debug!("Area mismatch on plane {plane:?}: {original_area} != {new_area}");
+        return None;
+    }
+    let point_ids: Vec<_> = outlines.iter().flatten().map(|p| p.idx()).collect();
+    Some(
+        triangles
+            .into_iter()
+            .map(|triangle| triangle.map(|idx| point_ids[idx]))
+            .collect(),
+    )
+}
+
+/// `true` if `p` lies on the straight line from `previous` to `next` between both
+fn is_straight<P: Point3d>(previous: &P, p: &P, next: &P) -> bool {
+    let [previous, p, next] = [previous, p, next].map(|p| p.coordinates().map(|c| c.0));
+    let (incoming, outgoing) = (p - previous, next - p);
+    incoming.dot(outgoing) > 0.0
+        && incoming.cross(outgoing).magnitude()
+            <= incoming.magnitude() * outgoing.magnitude() * 1e-9
+}
+
+/// Removes the zero area triangles the triangulation creates on collinear outline points.
+///
+/// The neighbour on the long side of such a triangle is split at the middle point, so the outline
+/// edges stay untouched. Returns `false` if not all degenerated triangles could be removed.
+fn fix_degenerated_triangles(points: &[StaticPoint2d], triangles: &mut [[usize; 3]]) -> bool {
+    let mut remaining_attempts = triangles.len() * 3;
+    while let Some((idx, middle)) = triangles
+        .iter()
+        .enumerate()
+        .find_map(|(idx, triangle)| collinear_middle(points, triangle).map(|m| (idx, m)))
+    {
+        if remaining_attempts == 0 {
+            return false;
+        }
+        remaining_attempts -= 1;
+        let triangle = triangles[idx];
+        let m = triangle[middle];
+        let a = triangle[(middle + 2) % 3];
+        let b = triangle[(middle + 1) % 3];
+        // the neighbour uses the long side in the opposite direction: a -> b
+        let neighbor = triangles.iter().enumerate().find_map(|(n_idx, n)| {
+            (0..3)
+                .find(|i| n[*i] == a && n[(i + 1) % 3] == b)
+                .map(|i| (n_idx, n[(i + 2) % 3]))
+        });
+        let Some((neighbor_idx, x)) = neighbor else {
+            return false;
+        };
+        triangles[idx] = [a, m, x];
+        triangles[neighbor_idx] = [m, b, x];
+    }
+    true
+}
+
+/// index of the corner lying between the two others, if the triangle has (nearly) no area
+fn collinear_middle(points: &[StaticPoint2d], triangle: &[usize; 3]) -> Option<usize> {
+    let corners = triangle.map(|idx| points[idx]);
+    let side_lengths: [Number; 3] =
+        [0, 1, 2].map(|i| corners[(i + 1) % 3].dist_square(&corners[(i + 2) % 3]));
+    let (middle, longest) = side_lengths
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, length)| **length)
+        .map(|(idx, length)| (idx, *length))?;
+    let area = corner_triangle(points, triangle).area();
+    // height over the longest side relative to its length
+    if area.0.abs() * 2.0 <= longest.0 * 1e-9 {
+        Some(middle)
+    } else {
+        None
+    }
+}
+
+fn corner_triangle(
+    points: &[StaticPoint2d],
+    triangle: &[usize; 3],
+) -> StaticTriangle2d<StaticPoint2d> {
+    let [p1, p2, p3] = triangle.map(|idx| points[idx]);
+    StaticTriangle2d::new(p1, p2, p3)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry3d::triangles::attributes::{Color, FaceAttributes, VertexAttributes};
+    use crate::prelude::{
+        IndexedTriangleList, Number, Point3d, Triangle3d, TriangleTopology, Vector3d,
+    };
+    use crate::test::{create_cube, load_schublade_as_triangles};
+
+    #[test]
+    fn test_simplify_cube() {
+        let cube = create_cube(4);
+        assert_eq!(6 * 4 * 4 * 2, cube.triangles().len());
+        let simplified = cube.simplify_planes().expect("Topology error");
+        // the points on the edges of the cube are dropped by both faces
+        assert_eq!(6 * 2, simplified.triangles().len());
+        assert_eq!(8, simplified.points().len());
+        TriangleTopology::new(&simplified).expect("Result is not watertight");
+    }
+
+    #[test]
+    fn test_simplify_keeps_face_attributes() {
+        let cube = create_cube(4);
+        // tag the two halves of every face differently
+        let materials: Vec<u32> = (0..cube.triangles().len())
+            .map(|idx| (idx / 16 % 2) as u32)
+            .collect();
+        let colors: Vec<_> = (0..cube.points().len())
+            .map(|idx| Color::new(idx as u8, 0, 0))
+            .collect();
+        let cube = cube
+            .with_face_attributes(FaceAttributes::default().with_materials(materials))
+            .and_then(|c| c.with_vertex_attributes(VertexAttributes::default().with_colors(colors)))
+            .expect("Invalid attributes");
+        let simplified = cube.simplify_planes().expect("Topology error");
+        let materials = simplified
+            .face_attributes()
+            .materials()
+            .expect("Materials lost");
+        assert_eq!(simplified.triangles().len(), materials.len());
+        assert!(materials.contains(&0) && materials.contains(&1));
+        // the halves keep the points where the material changes on the edges of the face
+        assert_eq!(36, simplified.triangles().len());
+        let colors = simplified
+            .vertex_attributes()
+            .colors()
+            .expect("Colors lost");
+        for (point, color) in simplified.points().iter().zip(colors) {
+            let original = cube.points().iter().position(|p| p == point).unwrap();
+            assert_eq!(original as u8, color.r);
+        }
+        TriangleTopology::new(&simplified).expect("Result is not watertight");
+    }
+
+    #[test]
+    fn test_simplify_schublade() {
+        let triangles = load_schublade_as_triangles();
+        // the export is minimal already, split every triangle at its centroid
+        let mut builder = IndexedTriangleList::<Vector3d>::builder::<Vector3d>();
+        for p in triangles.points() {
+            builder.append_point(p.coordinates());
+        }
+        for triangle in triangles.triangles() {
+            let [p1, p2, p3] = triangle.points().map(|p| p.idx());
+            let [a, b, c] = triangle.points().map(|p| p.coordinates());
+            let centroid = builder.append_point((a + b + c) * Number::from(1.0 / 3.0));
+            builder.append_indexed_triangle(p1, p2, centroid);
+            builder.append_indexed_triangle(p2, p3, centroid);
+            builder.append_indexed_triangle(p3, p1, centroid);
+        }
+        let split = builder.build();
+        assert_eq!(3 * 304, split.triangles().len());
+        let simplified = split.simplify_planes().expect("Topology error");
+        assert_eq!(304, simplified.triangles().len());
+        assert_eq!(triangles.points().len(), simplified.points().len());
+        TriangleTopology::new(&simplified).expect("Result is not watertight");
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use num_traits::Zero;
+use thiserror::Error;
+
+use crate::geometry3d::line::Line3d;
+use crate::geometry3d::plane::{InvalidPlane, Plane3d};
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::triangles::topology::triangle_group::TriangleGroup;
+use crate::geometry3d::triangles::topology::{TopologyError, TriangleTopology};
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::geometry3d::Vector3d;
+use crate::prelude::{
+    PlaneProjection, Point2d, StaticPoint2d, StaticTriangle2d, Triangle3d, TriangleIndex2d,
+};
+use crate::primitives::Number;
+
+/// [`TriangleTopology`] without lifetime: triangles and points are referenced by their index in
+/// the shared triangle list, so the topology can be stored, cached and sent to other threads.
+#[derive(Debug, Clone)]
+pub struct OwnedTriangleTopology<P: Point3d> {
+    triangle_list: Arc<IndexedTriangleList<P>>,
+    edge_neighbors: HashMap<[usize; 2], [usize; 2]>,
+    triangles_of_plane: HashMap<Plane3d, OwnedTriangleGroup>,
+    plane_groups: HashMap<Plane3d, Vec<Plane3d>>,
+    /// built by the first [`OwnedTriangleTopology::find_first_intersection`]
+    picking: OnceLock<PlanePicking>,
+}
+
+/// triangle indices and projected triangles of every plane for picking, shared by
+/// [`TriangleTopology`], [`OwnedTriangleTopology`] and the assembly
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PlanePicking {
+    plane_indices: HashMap<Plane3d, PlaneIndex>,
+}
+
+type PlaneIndex = (
+    Vec<usize>,
+    TriangleIndex2d<StaticPoint2d, StaticTriangle2d<StaticPoint2d>>,
+);
+
+/// [`TriangleGroup`] by triangle and point indices
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OwnedTriangleGroup {
+    triangles: Vec<usize>,
+    edges: Vec<Vec<[usize; 2]>>,
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnedTopologyError {
+    #[error("Two triangles on the same side of a edge: {},{}",.0[0],.0[1])]
+    DuplicateNeighborEntry([usize; 2]),
+    #[error("Triangle {0} has no neighbor at one edge")]
+    MissingNeighborError(usize),
+    #[error("Invalid plane found")]
+    InvalidPlane(#[from] InvalidPlane),
+}
+
+impl<P: Point3d> From<TopologyError<'_, P>> for OwnedTopologyError {
+    fn from(value: TopologyError<'_, P>) -> Self {
+        match value {
+            TopologyError::DuplicateNeighborEntry(triangles) => {
+                OwnedTopologyError::DuplicateNeighborEntry(triangles.map(|t| t.idx()))
+            }
+            TopologyError::MissingNeighborError(t) => {
+                OwnedTopologyError::MissingNeighborError(t.idx())
+            }
+            TopologyError::InvalidPlane(error) => OwnedTopologyError::InvalidPlane(error),
+        }
+    }
+}
+
+impl<P: Point3d> From<&TriangleGroup<'_, P>> for OwnedTriangleGroup {
+    fn from(group: &TriangleGroup<'_, P>) -> Self {
+        Self {
+            triangles: group.triangles().iter().map(|t| t.idx()).collect(),
+            edges: group
+                .edges()
+                .iter()
+                .map(|outline| {
+                    outline
+                        .iter()
+                        .map(|line| [line.p1().idx(), line.p2().idx()])
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+impl OwnedTriangleGroup {
+    /// indices of the triangles in the triangle list
+    pub fn triangles(&self) -> &Vec<usize> {
+        &self.triangles
+    }
+    /// closed outlines as point index pairs
+    pub fn edges(&self) -> &Vec<Vec<[usize; 2]>> {
+        &self.edges
+    }
+}
+
+impl<P: Point3d> OwnedTriangleTopology<P> {
+    pub fn new(triangle_list: Arc<IndexedTriangleList<P>>) -> Result<Self, OwnedTopologyError> {
+        let (edge_neighbors, triangles_of_plane, plane_groups) = {
+            let topology = TriangleTopology::new(&triangle_list)?;
+            let edge_neighbors = topology
+                .edge_neighbors()
+                .iter()
+                .map(|(edge, [forward, backward])| {
+                    (
+                        [edge.p1().idx(), edge.p2().idx()],
+                        [forward.idx(), backward.idx()],
+                    )
+                })
+                .collect();
+            let triangles_of_plane: HashMap<Plane3d, OwnedTriangleGroup> = topology
+                .triangles_of_plane()
+                .iter()
+                .map(|(plane, group)| (*plane, group.into()))
+                .collect();
+            (
+                edge_neighbors,
+                triangles_of_plane,
+                topology.plane_groups().clone(),
+            )
+        };
+        Ok(Self {
+            triangle_list,
+            edge_neighbors,
+            triangles_of_plane,
+            plane_groups,
+            picking: OnceLock::new(),
+        })
+    }
+
+    pub fn triangle_list(&self) -> &Arc<IndexedTriangleList<P>> {
+        &self.triangle_list
+    }
+
+    /// edges from the lower to the higher point index with the triangles using them in this
+    /// direction and in the opposite direction
+    pub fn edge_neighbors(&self) -> &HashMap<[usize; 2], [usize; 2]> {
+        &self.edge_neighbors
+    }
+
+    /// the triangle on the other side of the edge from `p1` to `p2`
+    pub fn other_neighbor(&self, p1: usize, p2: usize) -> Option<usize> {
+        if p1 < p2 {
+            self.edge_neighbors.get(&[p1, p2]).map(|t| t[1])
+        } else {
+            self.edge_neighbors.get(&[p2, p1]).map(|t| t[0])
+        }
+    }
+
+    pub fn plane_groups(&self) -> &HashMap<Plane3d, Vec<Plane3d>> {
+        &self.plane_groups
+    }
+    pub fn triangles_of_plane(&self) -> &HashMap<Plane3d, OwnedTriangleGroup> {
+        &self.triangles_of_plane
+    }
+
+    /// same as [`TriangleTopology::find_first_intersection`], returns the index of the triangle
+    pub fn find_first_intersection<L: Line3d<Pt>, Pt: Point3d>(
+        &self,
+        sight_line: &L,
+    ) -> Option<(Vector3d, &Plane3d, usize)> {
+        self.picking
+            .get_or_init(|| PlanePicking::new(&self.triangle_list))
+            .find_first_intersection(sight_line)
+    }
+}
+
+impl PlanePicking {
+    /// groups the triangles by their plane, degenerated triangles cannot be picked
+    pub(crate) fn new<P: Point3d>(triangle_list: &IndexedTriangleList<P>) -> Self {
+        let mut triangles_of_plane = HashMap::<_, Vec<usize>>::new();
+        for triangle in triangle_list.triangles() {
+            if let Ok(plane) = triangle.calculate_plane() {
+                triangles_of_plane
+                    .entry(plane)
+                    .or_default()
+                    .push(triangle.idx());
+            }
+        }
+        Self {
+            plane_indices: triangles_of_plane
+                .iter()
+                .map(|(plane, triangles)| (*plane, plane_index(triangle_list, plane, triangles)))
+                .collect(),
+        }
+    }
+
+    /// the closest triangle hit in front of the start of the sight line
+    pub(crate) fn find_first_intersection<L: Line3d<Pt>, Pt: Point3d>(
+        &self,
+        sight_line: &L,
+    ) -> Option<(Vector3d, &Plane3d, usize)> {
+        let camera_pos = sight_line.p1();
+        let mut found_planes = Vec::with_capacity(self.plane_indices.len());
+        for (plane, index) in self.plane_indices.iter() {
+            let p = plane.intersect_line_at(sight_line);
+            if p > Number::zero() && plane.is_in_front(&camera_pos) {
+                found_planes.push((p, plane, index));
+            }
+        }
+        found_planes.sort_by_key(|(p, _, _)| *p);
+        for (p, plane, (ids, index)) in found_planes {
+            let project = PlaneProjection::new(plane);
+            let point_on_plane = sight_line.point_at(p);
+            let click_point_2d = project.project_point(&point_on_plane).coordinates();
+            if let Some(found) = index.locate(&click_point_2d) {
+                return Some((point_on_plane, plane, ids[found]));
+            }
+        }
+        None
+    }
+}
+
+fn plane_index<P: Point3d>(
+    triangle_list: &IndexedTriangleList<P>,
+    plane: &Plane3d,
+    triangle_ids: &[usize],
+) -> PlaneIndex {
+    let project = PlaneProjection::new(plane);
+    let mut ids = Vec::with_capacity(triangle_ids.len());
+    let mut triangles = Vec::with_capacity(triangle_ids.len());
+    for idx in triangle_ids {
+        let Some(triangle) = triangle_list.get_triangle(*idx) else {
+            continue;
+        };
+        let [p1, p2, p3] = triangle
+            .points()
+            .map(|p| project.project_point(p).coordinates());
+        ids.push(*idx);
+        triangles.push(StaticTriangle2d::new(p1, p2, p3));
+    }
+    (ids, TriangleIndex2d::new(triangles))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::geometry3d::triangles::topology::owned::{
+        OwnedTopologyError, OwnedTriangleTopology,
+    };
+    use crate::prelude::{
+        point_3d, IndexedTriangleList, Line3d, StaticLine3d, TriangleTopology, Vector3d,
+    };
+    use crate::test::{create_cube, load_schublade_as_triangles};
+
+    #[test]
+    fn test_same_as_borrowed() {
+        let triangles = Arc::new(load_schublade_as_triangles());
+        let owned = OwnedTriangleTopology::new(triangles.clone()).expect("Topology error");
+        let borrowed = TriangleTopology::new(&triangles).expect("Topology error");
+        assert_eq!(
+            borrowed.edge_neighbors().len(),
+            owned.edge_neighbors().len()
+        );
+        for (edge, [forward, backward]) in borrowed.edge_neighbors() {
+            let [p1, p2] = [edge.p1().idx(), edge.p2().idx()];
+            assert_eq!(Some(backward.idx()), owned.other_neighbor(p1, p2));
+            assert_eq!(Some(forward.idx()), owned.other_neighbor(p2, p1));
+        }
+        // the grouping itself depends on the hash map order, but all planes have to be covered
+        for topology_planes in [
+            owned
+                .plane_groups()
+                .values()
+                .flatten()
+                .collect::<HashSet<_>>(),
+            borrowed.plane_groups().values().flatten().collect(),
+        ] {
+            assert_eq!(owned.triangles_of_plane().len(), topology_planes.len());
+        }
+        for (plane, group) in borrowed.triangles_of_plane() {
+            let owned_group = &owned.triangles_of_plane()[plane];
+            let triangles: Vec<_> = group.triangles().iter().map(|t| t.idx()).collect();
+            assert_eq!(&triangles, owned_group.triangles());
+            let edges: Vec<Vec<[usize; 2]>> = group
+                .edges()
+                .iter()
+                .map(|outline| {
+                    outline
+                        .iter()
+                        .map(|line| [line.p1().idx(), line.p2().idx()])
+                        .collect()
+                })
+                .collect();
+            assert_eq!(&edges, owned_group.edges());
+        }
+    }
+
+    #[test]
+    fn test_send_to_thread() {
+        let cube = Arc::new(create_cube(2));
+        let topology = OwnedTriangleTopology::new(cube).expect("Topology error");
+        let line = StaticLine3d::new(point_3d(0.3, 0.2, 5.0), point_3d(0.0, 0.0, -1.0));
+        let (point, plane, triangle) = thread::spawn(move || {
+            topology
+                .find_first_intersection(&line)
+                .map(|(point, plane, triangle)| (point, *plane, triangle))
+        })
+        .join()
+        .expect("Thread failed")
+        .expect("No intersection");
+        assert_eq!(point_3d(0.3, 0.2, 1.0), point);
+        assert_eq!(point_3d(0.0, 0.0, 1.0), plane.normal());
+        // the top triangle from (0, 0, 1) over (0.5, 0, 1) to (0.5, 0.5, 1)
+        assert_eq!(40, triangle);
+    }
+
+    #[test]
+    fn test_open_mesh() {
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)] {
+            builder.append_point(point_3d(x, y, 0.0));
+        }
+        builder.append_indexed_triangle(0, 1, 2);
+        let triangle = Arc::new(builder.build());
+        assert_eq!(
+            Some(OwnedTopologyError::MissingNeighborError(0)),
+            OwnedTriangleTopology::new(triangle).err()
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashSet;
+
+use crate::geometry3d::line::Line3d;
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::triangle::Triangle3d;
+use crate::geometry3d::triangles::topology::triangle_group::TriangleGroup;
+use crate::geometry3d::triangles::topology::{
+    collect_line_neighbors, LineNeighbors, TopologyError,
+};
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::primitives::Number;
+
+/// Topological invariants of a triangle list, a cheap fingerprint to compare two versions of a part
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MeshStatistics {
+    vertices: usize,
+    edges: usize,
+    faces: usize,
+    shells: Vec<ShellStatistics>,
+}
+
+/// Invariants of one edge connected part of the mesh
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShellStatistics {
+    vertices: usize,
+    edges: usize,
+    faces: usize,
+    boundary_loops: Vec<Number>,
+}
+
+impl MeshStatistics {
+    /// number of points used by at least one triangle
+    pub fn vertices(&self) -> usize {
+        self.vertices
+    }
+    pub fn edges(&self) -> usize {
+        self.edges
+    }
+    pub fn faces(&self) -> usize {
+        self.faces
+    }
+    /// V - E + F
+    pub fn euler_characteristic(&self) -> isize {
+        self.vertices as isize - self.edges as isize + self.faces as isize
+    }
+    /// shells ordered by their first triangle
+    pub fn shells(&self) -> &[ShellStatistics] {
+        &self.shells
+    }
+    pub fn boundary_loop_count(&self) -> usize {
+        self.shells.iter().map(|s| s.boundary_loops.len()).sum()
+    }
+}
+
+impl ShellStatistics {
+    pub fn vertices(&self) -> usize {
+        self.vertices
+    }
+    pub fn edges(&self) -> usize {
+        self.edges
+    }
+    pub fn faces(&self) -> usize {
+        self.faces
+    }
+    /// V - E + F
+    pub fn euler_characteristic(&self) -> isize {
+        self.vertices as isize - self.edges as isize + self.faces as isize
+    }
+    /// number of handles, from χ = 2 - 2g - b
+    ///
+    /// Only meaningful for orientable manifold shells, otherwise it may be negative.
+    pub fn genus(&self) -> isize {
+        (2 - self.euler_characteristic() - self.boundary_loops.len() as isize) / 2
+    }
+    /// lengths of the open outlines, longest first
+    pub fn boundary_loops(&self) -> &[Number] {
+        &self.boundary_loops
+    }
+}
+
+impl<P: Point3d> IndexedTriangleList<P> {
+    /// Counts vertices, edges and faces and splits the mesh into shells of triangles connected
+    /// by edges. Open meshes are fine, only edges used twice in the same direction fail.
+    pub fn statistics(&self) -> Result<MeshStatistics, TopologyError<'_, P>> {
+        let triangles = self.triangles();
+        let neighbors = collect_line_neighbors(&triangles)?;
+
+        let mut shell_of_triangle: Vec<usize> = (0..triangles.len()).collect();
+        for neighbor in neighbors.values() {
+            if let LineNeighbors::Both { forward, backward } = neighbor {
+                let first = find_root(&mut shell_of_triangle, forward.idx());
+                let second = find_root(&mut shell_of_triangle, backward.idx());
+                let (root, child) = (first.min(second), first.max(second));
+                shell_of_triangle[child] = root;
+            }
+        }
+        // the root of every shell is its lowest triangle index
+        let mut shell_triangles = Vec::<Vec<_>>::new();
+        let mut shell_idx = vec![usize::MAX; triangles.len()];
+        for triangle in triangles.iter() {
+            let root = find_root(&mut shell_of_triangle, triangle.idx());
+            if root == triangle.idx() {
+                shell_idx[root] = shell_triangles.len();
+                shell_triangles.push(Vec::new());
+            }
+            shell_triangles[shell_idx[root]].push(triangle.clone());
+        }
+
+        let shells = shell_triangles
+            .into_iter()
+            .map(|triangles| {
+                let mut points = HashSet::new();
+                let mut edges = HashSet::new();
+                for triangle in triangles.iter() {
+                    for side in triangle.sides() {
+                        let (p1, p2) = (side.p1().idx(), side.p2().idx());
+                        points.insert(p1);
+                        edges.insert((p1.min(p2), p1.max(p2)));
+                    }
+                }
+                let faces = triangles.len();
+                let group = TriangleGroup::new(triangles);
+                let mut boundary_loops: Vec<_> = group
+                    .edges()
+                    .iter()
+                    .map(|edge_loop| edge_loop.iter().map(|l| l.direction().magnitude()).sum())
+                    .collect();
+                boundary_loops.sort_by(|a: &Number, b| b.cmp(a));
+                ShellStatistics {
+                    vertices: points.len(),
+                    edges: edges.len(),
+                    faces,
+                    boundary_loops,
+                }
+            })
+            .collect();
+
+        let vertices = self
+            .triangles
+            .iter()
+            .flat_map(|t| t.points())
+            .collect::<HashSet<_>>()
+            .len();
+        Ok(MeshStatistics {
+            vertices,
+            edges: neighbors.len(),
+            faces: triangles.len(),
+            shells,
+        })
+    }
+}
+
+fn find_root(parents: &mut [usize], mut idx: usize) -> usize {
+    while parents[idx] != idx {
+        parents[idx] = parents[parents[idx]];
+        idx = parents[idx];
+    }
+    idx
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{point_3d, IndexedTriangleList, Number, Point3d, Triangle3d, Vector3d};
+    use crate::test::{create_cube, create_grid, load_schublade_as_triangles};
+
+    #[test]
+    fn test_cube() {
+        let statistics = create_cube(2).statistics().expect("Topology error");
+        assert_eq!(2, statistics.euler_characteristic());
+        assert_eq!(0, statistics.boundary_loop_count());
+        assert_eq!(1, statistics.shells().len());
+        assert_eq!(0, statistics.shells()[0].genus());
+        assert_eq!(6 * 8, statistics.faces());
+    }
+
+    #[test]
+    fn test_open_shells() {
+        let cube = create_cube(1);
+        let moved = create_cube(1).transform_points(|p| *p + point_3d(3.0, 0.0, 0.0));
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        for p in cube.points().iter().chain(moved.points()) {
+            builder.append_point(*p);
+        }
+        // the first cube loses its two top triangles
+        for t in cube.triangles() {
+            let [p1, p2, p3] = t.points().map(|p| p.coordinates());
+            if [p1, p2, p3].iter().all(|p| p.z.0 == 1.0) {
+                continue;
+            }
+            let [p1, p2, p3] = t.points().map(|p| p.idx());
+            builder.append_indexed_triangle(p1, p2, p3);
+        }
+        for t in moved.triangles() {
+            let [p1, p2, p3] = t.points().map(|p| p.idx() + cube.points().len());
+            builder.append_indexed_triangle(p1, p2, p3);
+        }
+        let statistics = builder.build().statistics().expect("Topology error");
+        assert_eq!(22, statistics.faces());
+        assert_eq!(16, statistics.vertices());
+        assert_eq!(2, statistics.shells().len());
+        let open = &statistics.shells()[0];
+        assert_eq!(1, open.euler_characteristic());
+        assert_eq!(&[Number::from(4.0)], open.boundary_loops());
+        assert_eq!(0, open.genus());
+        let closed = &statistics.shells()[1];
+        assert_eq!(2, closed.euler_characteristic());
+        assert!(closed.boundary_loops().is_empty());
+        assert_eq!(3, statistics.euler_characteristic());
+    }
+
+    #[test]
+    fn test_holes_touching_in_a_point() {
+        // the squares (1, 1) and (2, 2) of the grid are removed, both holes share the point (2, 2)
+        let grid = create_grid(4);
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        for p in grid.points() {
+            builder.append_point(*p);
+        }
+        for t in grid.triangles() {
+            let square = t.idx() / 2;
+            if square == 5 || square == 10 {
+                continue;
+            }
+            let [p1, p2, p3] = t.points().map(|p| p.idx());
+            builder.append_indexed_triangle(p1, p2, p3);
+        }
+        let holes = builder.build();
+        // the loops must not depend on the hash order
+        for _ in 0..20 {
+            let statistics = holes.statistics().expect("Topology error");
+            let shell = &statistics.shells()[0];
+            assert_eq!(&[16.0, 4.0, 4.0].map(Number::from), shell.boundary_loops());
+            assert_eq!(-1, shell.euler_characteristic());
+            assert_eq!(0, shell.genus());
+        }
+    }
+
+    #[test]
+    fn test_schublade() {
+        let triangles = load_schublade_as_triangles();
+        let statistics = triangles.statistics().expect("Topology error");
+        assert_eq!(0, statistics.boundary_loop_count());
+        assert_eq!(1, statistics.shells().len());
+        let shell = &statistics.shells()[0];
+        assert_eq!(
+            (152, 456, 304),
+            (shell.vertices(), shell.edges(), shell.faces())
+        );
+        // a closed surface with one hole through it
+        assert_eq!(0, shell.euler_characteristic());
+        assert_eq!(1, shell.genus());
+    }
+}
@@ -1,8 +1,16 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use crate::geometry2d::polygon::Polygon2d;
+use crate::geometry2d::refine::{refine_delaunay, RefinementOptions};
+use crate::geometry2d::triangle::mesh_cut::{cut_triangles, MeshCut};
 use crate::geometry3d::line::static_line::PointLine3d;
+use crate::geometry3d::plane::projection::PlaneProjection;
 use crate::geometry3d::triangles::indexed_point::IndexedPoint;
-use crate::prelude::{Line3d, Point3d, ReferencedTriangle, Triangle3d};
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::geometry3d::Vector3d;
+use crate::prelude::{
+    Line3d, Point2d, Point3d, ReferencedTriangle, StaticPoint2d, Triangle3d, TriangulationFailed,
+};
 
 #[derive(Debug, Clone, Default)]
 pub struct TriangleGroup<'a, P: Point3d> {
@@ -21,26 +29,21 @@ impl<'a, P: Point3d> TriangleGroup<'a, P> {
                 }
             }
         }
-        let mut edges = Vec::with_capacity(1);
-        while let Some(start_line) = outer_edges.iter().next().cloned() {
-            let mut segments = Vec::with_capacity(outer_edges.len());
-            outer_edges.remove(&start_line);
-            let mut next_pt = start_line.p2();
-            let start_pt = start_line.p1();
-            segments.push(start_line);
-            while next_pt != start_pt {
-                let next_line = outer_edges
-                    .iter()
-                    .find(|line| line.p1() == next_pt)
-                    .expect("Invalid Topology")
-                    .clone();
-                next_pt = next_line.p2().clone();
-                outer_edges.remove(&next_line);
-                segments.push(next_line);
-            }
-            segments.shrink_to_fit();
-            edges.push(segments);
-        }
+        let mut outer_edges: Vec<_> = outer_edges.into_iter().collect();
+        outer_edges.sort_by_key(|line| [line.p1().idx(), line.p2().idx()]);
+        let indices: Vec<[usize; 2]> = outer_edges
+            .iter()
+            .map(|line| [line.p1().idx(), line.p2().idx()])
+            .collect();
+        let edges = closed_loops(&indices)
+            .into_iter()
+            .map(|edge_loop| {
+                edge_loop
+                    .into_iter()
+                    .map(|idx| outer_edges[idx].clone())
+                    .collect()
+            })
+            .collect();
 
         Self { triangles, edges }
     }
@@ -51,13 +54,117 @@ impl<'a, P: Point3d> TriangleGroup<'a, P> {
     pub fn edges(&self) -> &Vec<Vec<PointLine3d<IndexedPoint<'a, P>>>> {
         &self.edges
     }
+
+    /// Delaunay refinement of the area inside the edges, all triangles have to lie in one plane
+    pub fn refine(
+        &self,
+        options: &RefinementOptions,
+    ) -> Result<IndexedTriangleList<Vector3d>, TriangulationFailed> {
+        let plane = self
+            .triangles
+            .iter()
+            .find_map(|triangle| triangle.calculate_plane().ok())
+            .ok_or_else(|| TriangulationFailed("No triangle spans a plane".to_string()))?;
+        let projection = PlaneProjection::new(&plane);
+        let rings: Vec<Vec<StaticPoint2d>> = self
+            .edges
+            .iter()
+            .map(|ring| {
+                ring.iter()
+                    .map(|line| projection.project_point(&line.p1()).coordinates())
+                    .collect()
+            })
+            .collect();
+        Ok(refine_delaunay(&rings, options)?.lift(&projection, plane.distance()))
+    }
+
+    /// Cuts all triangles of the group by a polygon in the coordinates of the projection, see
+    /// [`cut_triangles`]. Mesh points refer to the indices in the triangle list and the sources
+    /// to the triangles of the group.
+    pub fn cut_by_polygon<Pt: Point2d, Poly: Polygon2d<Pt>>(
+        &self,
+        projection: &PlaneProjection,
+        cut_polygon: &Poly,
+    ) -> Result<MeshCut, TriangulationFailed> {
+        let mut indices = Vec::new();
+        let mut local_idx = HashMap::new();
+        let mut points = Vec::new();
+        let triangles: Vec<[usize; 3]> = self
+            .triangles
+            .iter()
+            .map(|triangle| {
+                triangle.points().map(|p| {
+                    *local_idx.entry(p.idx()).or_insert_with(|| {
+                        indices.push(p.idx());
+                        points.push(projection.project_point(p).coordinates());
+                        points.len() - 1
+                    })
+                })
+            })
+            .collect();
+        Ok(cut_triangles(&points, &triangles, cut_polygon)?.with_mesh_indices(&indices))
+    }
+}
+
+/// Splits directed edges into closed loops of edge indices, independent of the input order.
+///
+/// Every loop starts at its lowest edge, at points with several outgoing edges the one with the
+/// lowest end point is taken. A walk passing the same point twice is split there, so outlines
+/// touching each other in a point result in separate loops.
+pub(crate) fn closed_loops(edges: &[[usize; 2]]) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    order.sort_by_key(|idx| edges[*idx]);
+    // the lowest edge last, to pop it first
+    let mut outgoing = HashMap::<usize, Vec<usize>>::new();
+    for idx in order.iter().rev() {
+        outgoing.entry(edges[*idx][0]).or_default().push(*idx);
+    }
+    let mut used = vec![false; edges.len()];
+    let mut loops = Vec::with_capacity(1);
+    for start in order {
+        if used[start] {
+            continue;
+        }
+        let mut path = Vec::new();
+        // position in the path of the edge leaving the point
+        let mut position = HashMap::new();
+        let mut edge = start;
+        loop {
+            used[edge] = true;
+            let [from, to] = edges[edge];
+            position.insert(from, path.len());
+            path.push(edge);
+            if let Some(begin) = position.get(&to).copied() {
+                let edge_loop = path.split_off(begin);
+                for idx in &edge_loop {
+                    position.remove(&edges[*idx][0]);
+                }
+                loops.push(edge_loop);
+                if path.is_empty() {
+                    break;
+                }
+            }
+            let candidates = outgoing.get_mut(&to).expect("Invalid Topology");
+            edge = loop {
+                let next = candidates.pop().expect("Invalid Topology");
+                if !used[next] {
+                    break next;
+                }
+            };
+        }
+    }
+    loops
 }
 
 #[cfg(test)]
 mod test {
+    use crate::geometry2d::triangle::mesh_cut::CutPointOrigin;
     use crate::geometry3d::triangles::topology::triangle_group::TriangleGroup;
-    use crate::prelude::{Line3d, TriangleTopology};
-    use crate::test::load_schublade_as_triangles;
+    use crate::prelude::{
+        point_3d, Line3d, PlaneProjection, Point2d, Point3d, RefinementOptions, StaticPoint2d,
+        StaticTriangle2d, Triangle2d, Triangle3d, TriangleTopology,
+    };
+    use crate::test::{create_cube, load_schublade_as_triangles};
 
     #[test]
     fn test_create_triangle_groups() {
@@ -72,4 +179,72 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_cut_group() {
+        let cube = create_cube(2);
+        let topology = TriangleTopology::new(&cube).expect("Topology error");
+        let (plane, group) = topology
+            .triangles_of_plane()
+            .iter()
+            .find(|(plane, _)| plane.normal().z.0 > 0.5)
+            .expect("Missing top side");
+        let projection = PlaneProjection::new(plane);
+        let center = projection
+            .project_point(&point_3d(0.5, 0.5, 1.0))
+            .coordinates();
+        let diamond: Vec<StaticPoint2d> = [(0.3, 0.0), (0.0, 0.3), (-0.3, 0.0), (0.0, -0.3)]
+            .into_iter()
+            .map(|(x, y)| StaticPoint2d::from((center.x.0 + x, center.y.0 + y)))
+            .collect();
+        let cut = group
+            .cut_by_polygon(&projection, &diamond)
+            .expect("Cut failed");
+        let area = |triangles: &[[usize; 3]]| -> f64 {
+            triangles
+                .iter()
+                .map(|t| {
+                    StaticTriangle2d::new(
+                        cut.points()[t[0]],
+                        cut.points()[t[1]],
+                        cut.points()[t[2]],
+                    )
+                    .area()
+                    .0
+                })
+                .sum()
+        };
+        assert!((area(cut.inside()) - 0.18).abs() < 1e-9);
+        assert!((area(cut.outside()) - 0.82).abs() < 1e-9);
+        // the center point of the top side is a mesh point in the list
+        let center_idx = cube
+            .points()
+            .iter()
+            .position(|p| *p == point_3d(0.5, 0.5, 1.0))
+            .expect("Missing center");
+        assert!(cut.origins().contains(&CutPointOrigin::Mesh(center_idx)));
+    }
+
+    #[test]
+    fn test_refine_group() {
+        let cube = create_cube(1);
+        let topology = TriangleTopology::new(&cube).expect("Topology error");
+        let options = RefinementOptions::new(25.0.into()).with_max_area(0.05.into());
+        for (plane, group) in topology.triangles_of_plane() {
+            let refined = group.refine(&options).expect("Refinement failed");
+            assert!(refined.triangles().len() >= 20);
+            let mut area = 0.0;
+            for triangle in refined.triangles() {
+                // the triangles stay on the face and keep its orientation
+                for p in triangle.points() {
+                    assert!(plane.point_distance(p).abs() < 1e-9);
+                }
+                let normal = triangle.calculate_plane().expect("Degenerated").normal();
+                assert!((normal - plane.normal()).magnitude().0 < 1e-9);
+                let [a, b, c] = triangle.points().map(|p| p.coordinates());
+                area += (b - a).cross(c - a).magnitude().0 / 2.0;
+            }
+            assert!((area - 1.0).abs() < 1e-9);
+        }
+    }
 }
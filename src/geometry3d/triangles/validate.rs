@@ -0,0 +1,676 @@
+use std::collections::HashMap;
+
+use vek::Vec3;
+
+use crate::geometry3d::point::Point3d;
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::primitives::Number;
+
+/// An edge by its point indices (lower index first) and the triangles using it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeReport {
+    points: [usize; 2],
+    triangles: Vec<usize>,
+}
+
+impl EdgeReport {
+    pub fn points(&self) -> [usize; 2] {
+        self.points
+    }
+    pub fn triangles(&self) -> &[usize] {
+        &self.triangles
+    }
+}
+
+/// Result of [`IndexedTriangleList::validate`], all entries reference triangle and point indices
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ValidationReport {
+    boundary_edges: Vec<EdgeReport>,
+    non_manifold_edges: Vec<EdgeReport>,
+    inconsistent_edges: Vec<EdgeReport>,
+    non_manifold_points: Vec<usize>,
+    degenerated_triangles: Vec<usize>,
+    duplicate_triangles: Vec<[usize; 2]>,
+    self_intersections: Vec<[usize; 2]>,
+    volume: Option<Number>,
+}
+
+impl ValidationReport {
+    /// edges with only one triangle
+    pub fn boundary_edges(&self) -> &[EdgeReport] {
+        &self.boundary_edges
+    }
+    /// edges with more than two triangles
+    pub fn non_manifold_edges(&self) -> &[EdgeReport] {
+        &self.non_manifold_edges
+    }
+    /// edges used by two triangles in the same direction
+    pub fn inconsistent_edges(&self) -> &[EdgeReport] {
+        &self.inconsistent_edges
+    }
+    /// points whose triangles do not form a single fan
+    pub fn non_manifold_points(&self) -> &[usize] {
+        &self.non_manifold_points
+    }
+    /// triangles without area
+    pub fn degenerated_triangles(&self) -> &[usize] {
+        &self.degenerated_triangles
+    }
+    /// pairs of triangles on the same positions, even if the points are not shared
+    pub fn duplicate_triangles(&self) -> &[[usize; 2]] {
+        &self.duplicate_triangles
+    }
+    /// pairs of triangles which touch or cross each other apart from their common points
+    pub fn self_intersections(&self) -> &[[usize; 2]] {
+        &self.self_intersections
+    }
+    /// signed volume, only available for closed and consistently oriented meshes
+    pub fn volume(&self) -> Option<Number> {
+        self.volume
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.boundary_edges.is_empty()
+    }
+    pub fn is_edge_manifold(&self) -> bool {
+        self.non_manifold_edges.is_empty()
+    }
+    pub fn is_vertex_manifold(&self) -> bool {
+        self.non_manifold_points.is_empty()
+    }
+    pub fn is_consistently_oriented(&self) -> bool {
+        self.inconsistent_edges.is_empty()
+    }
+    /// the normals point outwards if the enclosed volume is positive
+    pub fn is_outward(&self) -> bool {
+        self.volume.is_some_and(|volume| volume.0 > 0.0)
+    }
+    pub fn is_valid(&self) -> bool {
+        self.is_closed()
+            && self.is_edge_manifold()
+            && self.is_vertex_manifold()
+            && self.is_consistently_oriented()
+            && self.is_outward()
+            && self.degenerated_triangles.is_empty()
+            && self.duplicate_triangles.is_empty()
+            && self.self_intersections.is_empty()
+    }
+}
+
+impl<P: Point3d> IndexedTriangleList<P> {
+    /// Checks the mesh for everything a printable solid needs: closed, manifold, consistently
+    /// oriented with outward normals and free of degenerated, duplicate or intersecting triangles.
+    pub fn validate(&self) -> ValidationReport {
+        let triangles: Vec<_> = self.triangles.iter().map(|t| t.points()).collect();
+        let positions: Vec<_> = self
+            .points
+            .iter()
+            .map(|p| p.coordinates().map(|c| c.0))
+            .collect();
+        let mut report = ValidationReport::default();
+
+        // forward: the triangle uses the edge from the lower to the higher index
+        let mut edges = HashMap::<[usize; 2], Vec<(usize, bool)>>::new();
+        for (idx, triangle) in triangles.iter().enumerate() {
+            for corner in 0..3 {
+                let (p1, p2) = (triangle[corner], triangle[(corner + 1) % 3]);
+                if p1 == p2 {
+                    continue;
+                }
+                edges
+                    .entry([p1.min(p2), p1.max(p2)])
+                    .or_default()
+                    .push((idx, p1 < p2));
+            }
+        }
+        let mut sorted_edges: Vec<_> = edges.iter().collect();
+        sorted_edges.sort_by_key(|(edge, _)| **edge);
+        for (edge, users) in sorted_edges {
+            let entry = EdgeReport {
+                points: *edge,
+                triangles: users.iter().map(|(t, _)| *t).collect(),
+            };
+            match users[..] {
+                [_] => report.boundary_edges.push(entry),
+                [(_, first), (_, second)] if first == second => {
+                    report.inconsistent_edges.push(entry)
+                }
+                [_, _] => {}
+                _ => report.non_manifold_edges.push(entry),
+            }
+        }
+
+        report.non_manifold_points = non_manifold_points(&triangles, self.points.len());
+
+        for (idx, triangle) in triangles.iter().enumerate() {
+            let [a, b, c] = triangle.map(|p| positions[p]);
+            if (b - a).cross(c - a).magnitude_squared() == 0.0 {
+                report.degenerated_triangles.push(idx);
+            }
+        }
+
+        (report.self_intersections, report.duplicate_triangles) =
+            overlapping_pairs(&triangles, &positions);
+
+        if report.is_closed() && report.is_edge_manifold() && report.is_consistently_oriented() {
+            let volume: f64 = triangles
+                .iter()
+                .map(|t| {
+                    let [a, b, c] = t.map(|p| positions[p]);
+                    a.dot(b.cross(c)) / 6.0
+                })
+                .sum();
+            report.volume = Some(volume.into());
+        }
+        report
+    }
+}
+
+fn non_manifold_points(triangles: &[[usize; 3]], point_count: usize) -> Vec<usize> {
+    let mut point_triangles = vec![Vec::new(); point_count];
+    for (idx, triangle) in triangles.iter().enumerate() {
+        for point in triangle {
+            point_triangles[*point].push(idx);
+        }
+    }
+    let mut result = Vec::new();
+    for (point, around) in point_triangles.iter().enumerate() {
+        if around.len() < 2 {
+            continue;
+        }
+        // walk over the edges at the point, all triangles have to be reached
+        let mut reached = vec![false; around.len()];
+        let mut pending = vec![0];
+        reached[0] = true;
+        while let Some(current) = pending.pop() {
+            let current_triangle = triangles[around[current]];
+            for (other, other_reached) in reached.iter_mut().enumerate() {
+                if *other_reached {
+                    continue;
+                }
+                let other_triangle = triangles[around[other]];
+                let shares_edge = current_triangle
+                    .iter()
+                    .any(|p| *p != point && other_triangle.contains(p));
+                if shares_edge {
+                    *other_reached = true;
+                    pending.push(other);
+                }
+            }
+        }
+        if reached.iter().any(|r| !r) {
+            result.push(point);
+        }
+    }
+    result
+}
+
+/// Sweep over the x range of the triangles, then exact tests for overlapping bounding boxes.
+///
+/// Returns the intersecting and the duplicate pairs, duplicates are not reported as intersections.
+fn overlapping_pairs(
+    triangles: &[[usize; 3]],
+    positions: &[Vec3<f64>],
+) -> (Vec<[usize; 2]>, Vec<[usize; 2]>) {
+    let bounds: Vec<_> = triangles
+        .iter()
+        .map(|t| {
+            let corners = t.map(|p| positions[p]);
+            let min = corners[0]
+                .map2(corners[1], f64::min)
+                .map2(corners[2], f64::min);
+            let max = corners[0]
+                .map2(corners[1], f64::max)
+                .map2(corners[2], f64::max);
+            (min, max)
+        })
+        .collect();
+    let scale = bounds
+        .iter()
+        .map(|(min, max)| (*max - *min).reduce_partial_max())
+        .fold(0.0, f64::max);
+    let epsilon = scale * 1e-12;
+    // triangles repeating an index are reported as degenerated already
+    let mut order: Vec<_> = (0..triangles.len())
+        .filter(|t| {
+            let [p1, p2, p3] = triangles[*t];
+            p1 != p2 && p2 != p3 && p1 != p3
+        })
+        .collect();
+    order.sort_by(|a, b| bounds[*a].0.x.total_cmp(&bounds[*b].0.x));
+
+    let mut intersections = Vec::new();
+    let mut duplicates = Vec::new();
+    for (position, first) in order.iter().enumerate() {
+        let (min1, max1) = bounds[*first];
+        for second in order[position + 1..].iter() {
+            let (min2, max2) = bounds[*second];
+            if min2.x > max1.x + epsilon {
+                break;
+            }
+            if min2.y > max1.y + epsilon
+                || min1.y > max2.y + epsilon
+                || min2.z > max1.z + epsilon
+                || min1.z > max2.z + epsilon
+            {
+                continue;
+            }
+            let pair = [(*first).min(*second), (*first).max(*second)];
+            let t1 = triangles[*first];
+            let t2 = triangles[*second];
+            let corners1 = t1.map(|p| positions[p]);
+            let corners2 = t2.map(|p| positions[p]);
+            if same_corners(corners1, corners2, epsilon) {
+                duplicates.push(pair);
+                continue;
+            }
+            let shared: Vec<_> = (0..3).filter(|i| t2.contains(&t1[*i])).collect();
+            let intersect = match shared[..] {
+                [] => triangles_intersect(corners1, corners2, epsilon),
+                [corner] => match (0..3).position(|i| t2[i] == t1[corner]) {
+                    Some(other) => intersect_at_point(
+                        rotate(corners1, corner),
+                        rotate(corners2, other),
+                        epsilon,
+                    ),
+                    None => false,
+                },
+                [first_shared, second_shared] => match (0..3).find(|i| !t1.contains(&t2[*i])) {
+                    Some(third) => fold_at_edge(
+                        corners1,
+                        [corners1[first_shared], corners1[second_shared]],
+                        corners2[third],
+                        epsilon,
+                    ),
+                    None => false,
+                },
+                // the same points are found as duplicates
+                _ => false,
+            };
+            if intersect {
+                intersections.push(pair);
+            }
+        }
+    }
+    intersections.sort();
+    duplicates.sort();
+    (intersections, duplicates)
+}
+
+/// every corner of one triangle lies on a corner of the other one
+fn same_corners(t1: [Vec3<f64>; 3], t2: [Vec3<f64>; 3], epsilon: f64) -> bool {
+    let matches = |a: [Vec3<f64>; 3], b: [Vec3<f64>; 3]| {
+        a.iter()
+            .all(|p| b.iter().any(|q| (*p - *q).magnitude() <= epsilon))
+    };
+    matches(t1, t2) && matches(t2, t1)
+}
+
+fn rotate(corners: [Vec3<f64>; 3], first: usize) -> [Vec3<f64>; 3] {
+    [0, 1, 2].map(|i| corners[(first + i) % 3])
+}
+
+/// Triangles with the common first corner. They intersect if the opposite edge of one triangle
+/// hits the other one, in the same plane if the angles at the common corner overlap.
+fn intersect_at_point(t1: [Vec3<f64>; 3], t2: [Vec3<f64>; 3], epsilon: f64) -> bool {
+    let normal = (t1[1] - t1[0]).cross(t1[2] - t1[0]);
+    let length = normal.magnitude();
+    if length == 0.0 || (t2[1] - t2[0]).cross(t2[2] - t2[0]).magnitude() == 0.0 {
+        return false;
+    }
+    let coplanar = t2[1..]
+        .iter()
+        .all(|p| (normal.dot(*p - t1[0]) / length).abs() <= epsilon);
+    if !coplanar {
+        return segment_hits_triangle(t1[1], t1[2], t2, epsilon)
+            || segment_hits_triangle(t2[1], t2[2], t1, epsilon);
+    }
+    let axis = dropped_axis(normal);
+    let directions = |t: [Vec3<f64>; 3]| {
+        let [first, second] = [t[1], t[2]].map(|p| {
+            let [x, y] = project(axis, p - t[0]);
+            let length = x.hypot(y);
+            [x / length, y / length]
+        });
+        let bisector = [first[0] + second[0], first[1] + second[1]];
+        [first, second, bisector]
+    };
+    let inside = |[first, second, _]: [[f64; 2]; 3], direction: [f64; 2]| {
+        let orientation = cross_2d([0.0; 2], first, second).signum();
+        let length = direction[0].hypot(direction[1]);
+        cross_2d([0.0; 2], first, direction) * orientation > 1e-12 * length
+            && cross_2d([0.0; 2], direction, second) * orientation > 1e-12 * length
+    };
+    let (directions1, directions2) = (directions(t1), directions(t2));
+    directions2.iter().any(|d| inside(directions1, *d))
+        || directions1.iter().any(|d| inside(directions2, *d))
+}
+
+/// Triangles with a common edge only intersect if they are folded onto each other: in the same
+/// plane with the third corners on the same side of the edge.
+fn fold_at_edge(
+    t1: [Vec3<f64>; 3],
+    [start, end]: [Vec3<f64>; 2],
+    third: Vec3<f64>,
+    epsilon: f64,
+) -> bool {
+    let normal = (t1[1] - t1[0]).cross(t1[2] - t1[0]);
+    let length = normal.magnitude();
+    if length == 0.0 || (normal.dot(third - start) / length).abs() > epsilon {
+        return false;
+    }
+    let edge = end - start;
+    let side = |p: Vec3<f64>| edge.cross(p - start).dot(normal);
+    // the corners on the edge have no side, only the third corner of the triangle counts
+    let own_side: f64 = t1.iter().map(|p| side(*p)).sum();
+    let third_side = side(third);
+    third_side.abs() > epsilon * edge.magnitude() * length && own_side * third_side > 0.0
+}
+
+fn triangles_intersect(t1: [Vec3<f64>; 3], t2: [Vec3<f64>; 3], epsilon: f64) -> bool {
+    let normal = (t1[1] - t1[0]).cross(t1[2] - t1[0]);
+    let length = normal.magnitude();
+    if length == 0.0 {
+        return false;
+    }
+    let coplanar = t2
+        .iter()
+        .all(|p| (normal.dot(*p - t1[0]) / length).abs() <= epsilon);
+    if coplanar {
+        return coplanar_triangles_intersect(t1, t2, normal, epsilon);
+    }
+    (0..3).any(|i| segment_hits_triangle(t1[i], t1[(i + 1) % 3], t2, epsilon))
+        || (0..3).any(|i| segment_hits_triangle(t2[i], t2[(i + 1) % 3], t1, epsilon))
+}
+
+/// Möller–Trumbore, including the borders of the triangle and the end points of the segment
+fn segment_hits_triangle(
+    start: Vec3<f64>,
+    end: Vec3<f64>,
+    triangle: [Vec3<f64>; 3],
+    epsilon: f64,
+) -> bool {
+    let direction = end - start;
+    let edge1 = triangle[1] - triangle[0];
+    let edge2 = triangle[2] - triangle[0];
+    let p = direction.cross(edge2);
+    let det = edge1.dot(p);
+    let scale = direction.magnitude() * edge1.magnitude() * edge2.magnitude();
+    if det.abs() <= scale * 1e-12 {
+        return false;
+    }
+    let to_start = start - triangle[0];
+    let u = to_start.dot(p) / det;
+    let q = to_start.cross(edge1);
+    let v = direction.dot(q) / det;
+    let t = edge2.dot(q) / det;
+    let tolerance = epsilon / direction.magnitude().max(epsilon);
+    u >= -tolerance
+        && v >= -tolerance
+        && u + v <= 1.0 + tolerance
+        && t >= -tolerance
+        && t <= 1.0 + tolerance
+}
+
+fn coplanar_triangles_intersect(
+    t1: [Vec3<f64>; 3],
+    t2: [Vec3<f64>; 3],
+    normal: Vec3<f64>,
+    epsilon: f64,
+) -> bool {
+    let axis = dropped_axis(normal);
+    let a = t1.map(|p| project(axis, p));
+    let b = t2.map(|p| project(axis, p));
+    for i in 0..3 {
+        for j in 0..3 {
+            if segments_intersect(a[i], a[(i + 1) % 3], b[j], b[(j + 1) % 3], epsilon) {
+                return true;
+            }
+        }
+    }
+    point_in_triangle(a[0], b, epsilon) || point_in_triangle(b[0], a, epsilon)
+}
+
+/// the coordinate with the largest normal component
+fn dropped_axis(normal: Vec3<f64>) -> usize {
+    if normal.x.abs() >= normal.y.abs() && normal.x.abs() >= normal.z.abs() {
+        0
+    } else if normal.y.abs() >= normal.z.abs() {
+        1
+    } else {
+        2
+    }
+}
+
+/// keeps the orientation of the triangles for every axis
+fn project(axis: usize, p: Vec3<f64>) -> [f64; 2] {
+    match axis {
+        0 => [p.y, p.z],
+        1 => [p.z, p.x],
+        _ => [p.x, p.y],
+    }
+}
+
+fn cross_2d(o: [f64; 2], a: [f64; 2], b: [f64; 2]) -> f64 {
+    (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+}
+
+fn segments_intersect(
+    p1: [f64; 2],
+    p2: [f64; 2],
+    q1: [f64; 2],
+    q2: [f64; 2],
+    epsilon: f64,
+) -> bool {
+    let d1 = cross_2d(q1, q2, p1);
+    let d2 = cross_2d(q1, q2, p2);
+    let d3 = cross_2d(p1, p2, q1);
+    let d4 = cross_2d(p1, p2, q2);
+    let tolerance = epsilon * epsilon;
+    (d1 * d2 <= tolerance) && (d3 * d4 <= tolerance) && {
+        // collinear segments have to overlap
+        let overlap = |a: f64, b: f64, c: f64, d: f64| {
+            a.min(b) <= c.max(d) + epsilon && c.min(d) <= a.max(b) + epsilon
+        };
+        overlap(p1[0], p2[0], q1[0], q2[0]) && overlap(p1[1], p2[1], q1[1], q2[1])
+    }
+}
+
+fn point_in_triangle(p: [f64; 2], triangle: [[f64; 2]; 3], epsilon: f64) -> bool {
+    let d = [0, 1, 2].map(|i| cross_2d(triangle[i], triangle[(i + 1) % 3], p));
+    let tolerance = epsilon * epsilon;
+    d.iter().all(|d| *d >= -tolerance) || d.iter().all(|d| *d <= tolerance)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{point_3d, IndexedTriangleList, Point3d, Triangle3d, Vector3d};
+    use crate::test::{create_cube, load_schublade_as_triangles};
+
+    /// all triangles of the meshes in one list, the points are not merged
+    fn combine(meshes: &[IndexedTriangleList<Vector3d>]) -> IndexedTriangleList<Vector3d> {
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        for mesh in meshes {
+            let offset = builder.point_count();
+            for p in mesh.points() {
+                builder.append_point(*p);
+            }
+            for t in mesh.triangles() {
+                let [p1, p2, p3] = t.points().map(|p| p.idx() + offset);
+                builder.append_indexed_triangle(p1, p2, p3);
+            }
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_valid() {
+        let report = create_cube(3).validate();
+        assert!(report.is_valid(), "{report:?}");
+        assert!(report.volume().is_some_and(|v| (v.0 - 1.0).abs() < 1e-12));
+        let report = load_schublade_as_triangles().validate();
+        assert!(report.is_valid(), "{report:?}");
+    }
+
+    #[test]
+    fn test_open_and_flipped() {
+        let cube = create_cube(1);
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        for p in cube.points() {
+            builder.append_point(*p);
+        }
+        let triangles = cube.triangles();
+        for (idx, t) in triangles.iter().enumerate() {
+            let [p1, p2, p3] = t.points().map(|p| p.idx());
+            match idx {
+                // missing triangle
+                0 => {}
+                // flipped triangle
+                5 => {
+                    builder.append_indexed_triangle(p1, p3, p2);
+                }
+                _ => {
+                    builder.append_indexed_triangle(p1, p2, p3);
+                }
+            }
+        }
+        let report = builder.build().validate();
+        assert!(!report.is_closed());
+        assert_eq!(3, report.boundary_edges().len());
+        assert_eq!(3, report.inconsistent_edges().len());
+        assert!(report
+            .inconsistent_edges()
+            .iter()
+            .all(|e| e.triangles().contains(&4)));
+        assert_eq!(None, report.volume());
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_inverted() {
+        let inverted = create_cube(1).transform_points(|p| point_3d(p.x, p.z, p.y));
+        let report = inverted.validate();
+        assert!(report.is_closed() && report.is_consistently_oriented());
+        assert!(!report.is_outward());
+    }
+
+    #[test]
+    fn test_intersections_and_duplicates() {
+        let cube = create_cube(1);
+        let moved = create_cube(1).transform_points(|p| *p + point_3d(0.5, 0.25, 0.25));
+        let report = combine(&[cube.clone(), moved]).validate();
+        assert!(report.is_closed() && report.is_edge_manifold());
+        assert!(!report.self_intersections().is_empty());
+        for [first, second] in report.self_intersections() {
+            assert!(*first < 12 && *second >= 12);
+        }
+
+        let report = combine(&[cube.clone(), cube.clone()]).validate();
+        // the copies do not share points, but all triangles lie on each other
+        let expected: Vec<_> = (0..12).map(|idx| [idx, idx + 12]).collect();
+        assert_eq!(expected, report.duplicate_triangles());
+        assert!(!report.self_intersections().is_empty());
+
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        for p in cube.points() {
+            builder.append_point(*p);
+        }
+        for t in cube.triangles().iter().chain(cube.triangles().first()) {
+            let [p1, p2, p3] = t.points().map(|p| p.idx());
+            builder.append_indexed_triangle(p1, p2, p3);
+        }
+        let report = builder.build().validate();
+        assert_eq!(&[[0, 12]], report.duplicate_triangles());
+        assert_eq!(3, report.non_manifold_edges().len());
+    }
+
+    fn triangles(points: &[(f64, f64, f64)], triangles: &[[usize; 3]]) -> Vec<[usize; 2]> {
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        for (x, y, z) in points {
+            builder.append_point(point_3d(*x, *y, *z));
+        }
+        for [p1, p2, p3] in triangles {
+            builder.append_indexed_triangle(*p1, *p2, *p3);
+        }
+        builder.build().validate().self_intersections().to_vec()
+    }
+
+    #[test]
+    fn test_intersections_at_common_points() {
+        let points = [
+            (0.0, 0.0, 0.0),
+            (1.0, 0.0, 0.0),
+            (0.0, 1.0, 0.0),
+            (0.2, 0.2, -1.0),
+            (0.2, 0.2, 1.0),
+            (-1.0, 0.0, 1.0),
+            (0.5, 0.3, 0.0),
+            (1.0, 1.0, 0.0),
+        ];
+        // a triangle piercing another one at the common corner
+        assert_eq!(vec![[0, 1]], triangles(&points, &[[0, 1, 2], [0, 3, 4]]));
+        // only the corner is common
+        assert!(triangles(&points, &[[0, 1, 2], [0, 5, 3]]).is_empty());
+        // in the same plane with overlapping angles at the corner
+        assert_eq!(vec![[0, 1]], triangles(&points, &[[0, 1, 2], [0, 6, 7]]));
+        // folded onto each other at the common edge
+        assert_eq!(vec![[0, 1]], triangles(&points, &[[0, 1, 2], [1, 0, 6]]));
+        // a regular neighbour at the common edge
+        assert!(triangles(&points, &[[0, 1, 6], [1, 7, 6]]).is_empty());
+    }
+
+    #[test]
+    fn test_repeated_index() {
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        builder.append_point(point_3d(0.0, 0.0, 0.0));
+        builder.append_point(point_3d(1.0, 0.0, 0.0));
+        builder.append_point(point_3d(0.0, 1.0, 0.0));
+        builder.append_indexed_triangle(0, 1, 2);
+        builder.append_indexed_triangle(0, 0, 1);
+        let report = builder.build().validate();
+        assert_eq!(&[1], report.degenerated_triangles());
+        assert!(report.self_intersections().is_empty());
+    }
+
+    #[test]
+    fn test_touching_corner() {
+        let cube = create_cube(1);
+        let corner = cube
+            .points()
+            .iter()
+            .position(|p| p.coordinates() == point_3d(1.0, 1.0, 1.0))
+            .unwrap();
+        let moved = cube
+            .clone()
+            .transform_points(|p| *p + point_3d(1.0, 1.0, 1.0));
+        // glue the second cube to the corner point of the first
+        let mut builder = IndexedTriangleList::<Vector3d>::builder();
+        for p in cube.points().iter().chain(moved.points()) {
+            builder.append_point(*p);
+        }
+        let offset = cube.points().len();
+        let origin = moved
+            .points()
+            .iter()
+            .position(|p| p.coordinates() == point_3d(1.0, 1.0, 1.0))
+            .unwrap();
+        for t in cube.triangles() {
+            let [p1, p2, p3] = t.points().map(|p| p.idx());
+            builder.append_indexed_triangle(p1, p2, p3);
+        }
+        for t in moved.triangles() {
+            let [p1, p2, p3] = t.points().map(|p| {
+                if p.idx() == origin {
+                    corner
+                } else {
+                    p.idx() + offset
+                }
+            });
+            builder.append_indexed_triangle(p1, p2, p3);
+        }
+        let report = builder.build().validate();
+        assert!(report.is_closed() && report.is_edge_manifold());
+        assert_eq!(&[corner], report.non_manifold_points());
+        assert!(report.self_intersections().is_empty());
+    }
+}
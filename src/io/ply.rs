@@ -0,0 +1,753 @@
+use std::io::{BufRead, Read, Write};
+
+use thiserror::Error;
+
+use crate::geometry2d::vector::Vector2d;
+use crate::geometry3d::point::{point_3d, Point3d};
+use crate::geometry3d::triangle::Triangle3d;
+use crate::geometry3d::triangles::attributes::Color;
+use crate::geometry3d::triangles::IndexedTriangleList;
+use crate::geometry3d::Vector3d;
+use crate::primitives::Number;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+}
+
+#[derive(Error, Debug)]
+pub enum PlyError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid header: {0}")]
+    InvalidHeader(String),
+    #[error("Invalid data: {0}")]
+    InvalidData(String),
+}
+
+/// Writes points, triangles and all attributes of the list.
+///
+/// Group names are stored as `comment group <index> <name>` lines in the header, backslashes and
+/// line breaks in the names are escaped.
+pub fn write_ply<P: Point3d, W: Write>(
+    list: &IndexedTriangleList<P>,
+    format: PlyFormat,
+    mut writer: W,
+) -> Result<(), PlyError> {
+    let vertex_attributes = list.vertex_attributes();
+    let face_attributes = list.face_attributes();
+    let normals = vertex_attributes.normals();
+    let uvs = vertex_attributes.uvs();
+    let colors = vertex_attributes.colors();
+    let materials = face_attributes.materials();
+    let groups = face_attributes.groups();
+
+    writeln!(writer, "ply")?;
+    match format {
+        PlyFormat::Ascii => writeln!(writer, "format ascii 1.0")?,
+        PlyFormat::BinaryLittleEndian => writeln!(writer, "format binary_little_endian 1.0")?,
+    }
+    for (idx, name) in face_attributes.group_names().iter().enumerate() {
+        writeln!(writer, "comment group {idx} {}", escape_group_name(name))?;
+    }
+    writeln!(writer, "element vertex {}", list.points().len())?;
+    for name in ["x", "y", "z"] {
+        writeln!(writer, "property double {name}")?;
+    }
+    if normals.is_some() {
+        for name in ["nx", "ny", "nz"] {
+            writeln!(writer, "property double {name}")?;
+        }
+    }
+    if uvs.is_some() {
+        for name in ["s", "t"] {
+            writeln!(writer, "property double {name}")?;
+        }
+    }
+    if colors.is_some() {
+        for name in ["red", "green", "blue"] {
+            writeln!(writer, "property uchar {name}")?;
+        }
+    }
+    let triangles = list.triangles();
+    writeln!(writer, "element face {}", triangles.len())?;
+    writeln!(writer, "property list uchar uint vertex_indices")?;
+    if materials.is_some() {
+        writeln!(writer, "property uint material_index")?;
+    }
+    if groups.is_some() {
+        writeln!(writer, "property uint group_index")?;
+    }
+    writeln!(writer, "end_header")?;
+
+    let mut values = ValueWriter {
+        writer: &mut writer,
+        format,
+        first: true,
+    };
+    for (idx, point) in list.points().iter().enumerate() {
+        let mut doubles = point.coordinates().into_array().to_vec();
+        if let Some(normals) = normals {
+            doubles.extend(normals[idx].into_array());
+        }
+        if let Some(uvs) = uvs {
+            doubles.extend(uvs[idx].into_array());
+        }
+        for value in doubles {
+            values.double(value.0)?;
+        }
+        if let Some(colors) = colors {
+            for value in colors[idx].into_array() {
+                values.uchar(value)?;
+            }
+        }
+        values.end_line()?;
+    }
+    for (idx, triangle) in triangles.iter().enumerate() {
+        values.uchar(3)?;
+        for point in triangle.points() {
+            values.uint(point.idx() as u32)?;
+        }
+        if let Some(materials) = materials {
+            values.uint(materials[idx])?;
+        }
+        if let Some(groups) = groups {
+            values.uint(groups[idx])?;
+        }
+        values.end_line()?;
+    }
+    Ok(())
+}
+
+/// Reads an ascii or binary little endian ply file.
+///
+/// Polygons with more than three corners are split into triangle fans, faces repeating a corner
+/// are rejected and unknown elements and properties are skipped. Groups without a `comment group` line get an empty name.
+pub fn read_ply<R: BufRead>(mut reader: R) -> Result<IndexedTriangleList<Vector3d>, PlyError> {
+    let header = Header::read(&mut reader)?;
+    let mut values = ValueReader::new(reader, header.format)?;
+    let mut builder = IndexedTriangleList::<Vector3d>::builder();
+    let mut group_names = header.group_names;
+
+    for element in header.elements.iter() {
+        let position = |name: &str| element.properties.iter().position(|p| p.name == name);
+        let position_any = |names: &[&str]| names.iter().find_map(|name| position(name));
+        match element.name.as_str() {
+            "vertex" => {
+                let coordinates = [position("x"), position("y"), position("z")];
+                let [Some(x), Some(y), Some(z)] = coordinates else {
+                    return Err(PlyError::InvalidHeader("Missing vertex coordinates".into()));
+                };
+                let normal = [position("nx"), position("ny"), position("nz")];
+                let uv = [
+                    position_any(&["s", "u", "texture_u"]),
+                    position_any(&["t", "v", "texture_v"]),
+                ];
+                let color = [position("red"), position("green"), position("blue")];
+                for _ in 0..element.count {
+                    let row = values.read_row(element)?;
+                    let scalar = |idx: usize| scalar(&row, element, idx);
+                    let idx = builder.append_point(point_3d(scalar(x)?, scalar(y)?, scalar(z)?));
+                    if let [Some(nx), Some(ny), Some(nz)] = normal {
+                        builder
+                            .attributes
+                            .set_normal(idx, point_3d(scalar(nx)?, scalar(ny)?, scalar(nz)?));
+                    }
+                    if let [Some(u), Some(v)] = uv {
+                        let uv = Vector2d::new(Number::from(scalar(u)?), Number::from(scalar(v)?));
+                        builder.attributes.set_uv(idx, uv);
+                    }
+                    if let [Some(r), Some(g), Some(b)] = color {
+                        let channel = |position: usize| -> Result<u8, PlyError> {
+                            Ok(if element.properties[position].data_type.is_float() {
+                                (scalar(position)? * 255.0).round() as u8
+                            } else {
+                                scalar(position)? as u8
+                            })
+                        };
+                        let color = Color::new(channel(r)?, channel(g)?, channel(b)?);
+                        builder.attributes.set_color(idx, color);
+                    }
+                }
+            }
+            "face" => {
+                let Some(indices) = position_any(&["vertex_indices", "vertex_index"]) else {
+                    return Err(PlyError::InvalidHeader("Missing vertex indices".into()));
+                };
+                let material = position("material_index");
+                let group = position("group_index");
+                let point_count = builder.point_count();
+                for _ in 0..element.count {
+                    let row = values.read_row(element)?;
+                    let corners = row[indices]
+                        .iter()
+                        .map(|c| to_index(*c))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if corners.len() < 3 || corners.iter().any(|c| *c >= point_count) {
+                        return Err(PlyError::InvalidData(format!("Invalid face {corners:?}")));
+                    }
+                    let repeated = corners
+                        .iter()
+                        .enumerate()
+                        .any(|(idx, c)| corners[idx + 1..].contains(c));
+                    if repeated {
+                        return Err(PlyError::InvalidData(format!(
+                            "Degenerated face {corners:?}"
+                        )));
+                    }
+                    let material = material
+                        .map(|material| scalar(&row, element, material).and_then(to_index))
+                        .transpose()?;
+                    let group = group
+                        .map(|group| scalar(&row, element, group).and_then(to_index))
+                        .transpose()?;
+                    if let Some(group) = group {
+                        if group_names.len() <= group {
+                            group_names.resize(group + 1, String::new());
+                        }
+                    }
+                    for idx in 1..corners.len() - 1 {
+                        let triangle = builder.append_indexed_triangle(
+                            corners[0],
+                            corners[idx],
+                            corners[idx + 1],
+                        );
+                        if let Some(material) = material {
+                            builder.attributes.set_material(triangle, material as u32);
+                        }
+                        if let Some(group) = group {
+                            builder.attributes.set_group_index(triangle, group as u32);
+                        }
+                    }
+                }
+            }
+            _ => {
+                for _ in 0..element.count {
+                    values.read_row(element)?;
+                }
+            }
+        }
+    }
+    builder.attributes.set_group_names(group_names);
+    let list = builder.build();
+    list.face_attributes()
+        .check(list.triangles().len())
+        .map_err(|e| PlyError::InvalidData(e.to_string()))?;
+    Ok(list)
+}
+
+/// keeps a group name on one header line
+fn escape_group_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// reverts `escape_group_name`, unknown escapes are kept
+fn unescape_group_name(name: &str) -> String {
+    let mut unescaped = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// first value of a property, lists without entries are rejected
+fn scalar(row: &[Vec<f64>], element: &Element, idx: usize) -> Result<f64, PlyError> {
+    row[idx].first().copied().ok_or_else(|| {
+        PlyError::InvalidData(format!("Empty list {}", element.properties[idx].name))
+    })
+}
+
+/// indices must be non negative integers, indices of triangles are stored as u32
+fn to_index(value: f64) -> Result<usize, PlyError> {
+    if value < 0.0 || value.fract() != 0.0 || value > u32::MAX as f64 {
+        return Err(PlyError::InvalidData(format!("Invalid index {value}")));
+    }
+    Ok(value as usize)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum DataType {
+    Char,
+    UChar,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Float,
+    Double,
+}
+
+impl DataType {
+    fn parse(name: &str) -> Result<Self, PlyError> {
+        Ok(match name {
+            "char" | "int8" => DataType::Char,
+            "uchar" | "uint8" => DataType::UChar,
+            "short" | "int16" => DataType::Short,
+            "ushort" | "uint16" => DataType::UShort,
+            "int" | "int32" => DataType::Int,
+            "uint" | "uint32" => DataType::UInt,
+            "float" | "float32" => DataType::Float,
+            "double" | "float64" => DataType::Double,
+            _ => return Err(PlyError::InvalidHeader(format!("Unknown type {name}"))),
+        })
+    }
+    fn is_float(&self) -> bool {
+        matches!(self, DataType::Float | DataType::Double)
+    }
+}
+
+#[derive(Debug)]
+struct Property {
+    name: String,
+    /// type of the counter for list properties
+    count_type: Option<DataType>,
+    data_type: DataType,
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+#[derive(Debug)]
+struct Header {
+    format: PlyFormat,
+    elements: Vec<Element>,
+    group_names: Vec<String>,
+}
+
+impl Header {
+    fn read<R: BufRead>(reader: &mut R) -> Result<Self, PlyError> {
+        let mut format = None;
+        let mut elements: Vec<Element> = Vec::new();
+        let mut group_names = Vec::new();
+        let mut line = String::new();
+        let mut first_line = true;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(PlyError::InvalidHeader("Missing end_header".into()));
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            if first_line {
+                if line != "ply" {
+                    return Err(PlyError::InvalidHeader("Not a ply file".into()));
+                }
+                first_line = false;
+                continue;
+            }
+            let invalid = || PlyError::InvalidHeader(line.to_string());
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("format") => {
+                    format = Some(match words.next() {
+                        Some("ascii") => PlyFormat::Ascii,
+                        Some("binary_little_endian") => PlyFormat::BinaryLittleEndian,
+                        _ => return Err(invalid()),
+                    })
+                }
+                Some("comment") => {
+                    // the name is taken verbatim, it may contain any whitespace
+                    if let Some(group) = line.strip_prefix("comment group ") {
+                        let (idx, name) = group.split_once(' ').unwrap_or((group, ""));
+                        let idx: usize = idx.parse().map_err(|_| invalid())?;
+                        let name = unescape_group_name(name);
+                        if group_names.len() <= idx {
+                            group_names.resize(idx + 1, String::new());
+                        }
+                        group_names[idx] = name;
+                    }
+                }
+                Some("element") => {
+                    let name = words.next().ok_or_else(invalid)?.to_string();
+                    let count = words
+                        .next()
+                        .and_then(|count| count.parse().ok())
+                        .ok_or_else(invalid)?;
+                    elements.push(Element {
+                        name,
+                        count,
+                        properties: Vec::new(),
+                    });
+                }
+                Some("property") => {
+                    let element = elements.last_mut().ok_or_else(invalid)?;
+                    let property = match words.next() {
+                        Some("list") => {
+                            let count_type = DataType::parse(words.next().ok_or_else(invalid)?)?;
+                            let data_type = DataType::parse(words.next().ok_or_else(invalid)?)?;
+                            Property {
+                                name: words.next().ok_or_else(invalid)?.to_string(),
+                                count_type: Some(count_type),
+                                data_type,
+                            }
+                        }
+                        Some(data_type) => Property {
+                            data_type: DataType::parse(data_type)?,
+                            count_type: None,
+                            name: words.next().ok_or_else(invalid)?.to_string(),
+                        },
+                        None => return Err(invalid()),
+                    };
+                    element.properties.push(property);
+                }
+                Some("end_header") => break,
+                Some("obj_info") | None => {}
+                Some(_) => return Err(invalid()),
+            }
+        }
+        Ok(Self {
+            format: format.ok_or_else(|| PlyError::InvalidHeader("Missing format".into()))?,
+            elements,
+            group_names,
+        })
+    }
+}
+
+enum ValueReader<R: BufRead> {
+    Ascii(std::vec::IntoIter<String>),
+    Binary(R),
+}
+
+impl<R: BufRead> ValueReader<R> {
+    fn new(mut reader: R, format: PlyFormat) -> Result<Self, PlyError> {
+        Ok(match format {
+            PlyFormat::Ascii => {
+                let mut data = String::new();
+                reader.read_to_string(&mut data)?;
+                let tokens: Vec<_> = data.split_whitespace().map(str::to_string).collect();
+                ValueReader::Ascii(tokens.into_iter())
+            }
+            PlyFormat::BinaryLittleEndian => ValueReader::Binary(reader),
+        })
+    }
+
+    /// all values of one element, scalar properties as a list with one entry
+    fn read_row(&mut self, element: &Element) -> Result<Vec<Vec<f64>>, PlyError> {
+        element
+            .properties
+            .iter()
+            .map(|property| match property.count_type {
+                Some(count_type) => {
+                    let count = self.read_value(count_type)? as usize;
+                    (0..count)
+                        .map(|_| self.read_value(property.data_type))
+                        .collect()
+                }
+                None => Ok(vec![self.read_value(property.data_type)?]),
+            })
+            .collect()
+    }
+
+    fn read_value(&mut self, data_type: DataType) -> Result<f64, PlyError> {
+        match self {
+            ValueReader::Ascii(tokens) => {
+                let token = tokens
+                    .next()
+                    .ok_or_else(|| PlyError::InvalidData("Unexpected end of file".into()))?;
+                token
+                    .parse()
+                    .map_err(|_| PlyError::InvalidData(format!("Invalid number {token}")))
+            }
+            ValueReader::Binary(reader) => {
+                let mut buffer = [0; 8];
+                Ok(match data_type {
+                    DataType::Char => read_bytes::<1, _>(reader, &mut buffer)
+                        .map(|b| i8::from_le_bytes(b) as f64)?,
+                    DataType::UChar => read_bytes::<1, _>(reader, &mut buffer)
+                        .map(|b| u8::from_le_bytes(b) as f64)?,
+                    DataType::Short => read_bytes::<2, _>(reader, &mut buffer)
+                        .map(|b| i16::from_le_bytes(b) as f64)?,
+                    DataType::UShort => read_bytes::<2, _>(reader, &mut buffer)
+                        .map(|b| u16::from_le_bytes(b) as f64)?,
+                    DataType::Int => read_bytes::<4, _>(reader, &mut buffer)
+                        .map(|b| i32::from_le_bytes(b) as f64)?,
+                    DataType::UInt => read_bytes::<4, _>(reader, &mut buffer)
+                        .map(|b| u32::from_le_bytes(b) as f64)?,
+                    DataType::Float => read_bytes::<4, _>(reader, &mut buffer)
+                        .map(|b| f32::from_le_bytes(b) as f64)?,
+                    DataType::Double => {
+                        read_bytes::<8, _>(reader, &mut buffer).map(f64::from_le_bytes)?
+                    }
+                })
+            }
+        }
+    }
+}
+
+fn read_bytes<const N: usize, R: Read>(
+    reader: &mut R,
+    buffer: &mut [u8; 8],
+) -> Result<[u8; N], PlyError> {
+    reader.read_exact(&mut buffer[..N])?;
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(&buffer[..N]);
+    Ok(bytes)
+}
+
+struct ValueWriter<'a, W: Write> {
+    writer: &'a mut W,
+    format: PlyFormat,
+    first: bool,
+}
+
+impl<'a, W: Write> ValueWriter<'a, W> {
+    fn double(&mut self, value: f64) -> Result<(), PlyError> {
+        match self.format {
+            PlyFormat::Ascii => self.ascii(value),
+            PlyFormat::BinaryLittleEndian => Ok(self.writer.write_all(&value.to_le_bytes())?),
+        }
+    }
+    fn uint(&mut self, value: u32) -> Result<(), PlyError> {
+        match self.format {
+            PlyFormat::Ascii => self.ascii(value),
+            PlyFormat::BinaryLittleEndian => Ok(self.writer.write_all(&value.to_le_bytes())?),
+        }
+    }
+    fn uchar(&mut self, value: u8) -> Result<(), PlyError> {
+        match self.format {
+            PlyFormat::Ascii => self.ascii(value),
+            PlyFormat::BinaryLittleEndian => Ok(self.writer.write_all(&[value])?),
+        }
+    }
+    fn ascii<D: std::fmt::Display>(&mut self, value: D) -> Result<(), PlyError> {
+        if !self.first {
+            write!(self.writer, " ")?;
+        }
+        self.first = false;
+        Ok(write!(self.writer, "{value}")?)
+    }
+    fn end_line(&mut self) -> Result<(), PlyError> {
+        if self.format == PlyFormat::Ascii {
+            writeln!(self.writer)?;
+        }
+        self.first = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use crate::geometry2d::vector::Vector2d;
+    use crate::geometry3d::triangles::attributes::{Color, FaceAttributes, VertexAttributes};
+    use crate::io::ply::{read_ply, write_ply, PlyError, PlyFormat};
+    use crate::prelude::{point_3d, IndexedTriangleList, Number, Vector3d};
+    use crate::test::create_cube;
+
+    fn tagged_cube() -> IndexedTriangleList<Vector3d> {
+        let cube = create_cube(1);
+        let point_count = cube.points().len();
+        let triangle_count = cube.triangles().len();
+        let normals: Vec<_> = cube
+            .points()
+            .iter()
+            .map(|p| (*p - point_3d(0.5, 0.5, 0.5)).normalized())
+            .collect();
+        let uvs: Vec<_> = (0..point_count)
+            .map(|idx| Vector2d::new(Number::from(idx as f64 / 8.0), Number::from(0.5)))
+            .collect();
+        let colors: Vec<_> = (0..point_count)
+            .map(|idx| Color::new(idx as u8 * 30, 255, 0))
+            .collect();
+        let materials: Vec<_> = (0..triangle_count).map(|idx| idx as u32 % 3).collect();
+        let groups: Vec<_> = (0..triangle_count).map(|idx| idx as u32 / 6).collect();
+        cube.with_vertex_attributes(
+            VertexAttributes::default()
+                .with_normals(normals)
+                .with_uvs(uvs)
+                .with_colors(colors),
+        )
+        .and_then(|cube| {
+            cube.with_face_attributes(
+                FaceAttributes::default()
+                    .with_materials(materials)
+                    .with_groups(
+                        groups,
+                        vec!["Drilling".to_string(), "Edge banding".to_string()],
+                    ),
+            )
+        })
+        .expect("Invalid attributes")
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cube = tagged_cube();
+        for format in [PlyFormat::Ascii, PlyFormat::BinaryLittleEndian] {
+            let mut data = Vec::new();
+            write_ply(&cube, format, &mut data).expect("Cannot write");
+            let loaded = read_ply(Cursor::new(data)).expect("Cannot read");
+            assert_eq!(cube, loaded);
+            assert_eq!(
+                Some("Edge banding"),
+                loaded.face_attributes().group_name(11)
+            );
+        }
+    }
+
+    #[test]
+    fn test_transform_keeps_attributes() {
+        let cube = tagged_cube();
+        let moved = cube
+            .clone()
+            .transform_points(|p| *p + point_3d(1.0, 2.0, 3.0));
+        assert_eq!(cube.vertex_attributes(), moved.vertex_attributes());
+        assert_eq!(cube.face_attributes(), moved.face_attributes());
+    }
+
+    #[test]
+    fn test_read_polygons() {
+        let data = "ply
+format ascii 1.0
+comment made by hand
+element vertex 4
+property float x
+property float y
+property float z
+property float red
+property float green
+property float blue
+element face 1
+property list uchar int vertex_index
+property int flags
+end_header
+0 0 0 1 0 0
+1 0 0 1 0 0
+1 1 0 0 1 0
+0 1 0 0 0 1
+4 0 1 2 3 7
+";
+        let square = read_ply(Cursor::new(data)).expect("Cannot read");
+        assert_eq!(4, square.points().len());
+        assert_eq!(2, square.triangles().len());
+        let colors = square.vertex_attributes().colors().expect("Missing colors");
+        assert_eq!(Color::new(0, 0, 255), colors[3]);
+        assert!(square.face_attributes().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_index() {
+        for face in ["3 0 1 2", "3 0 -1 0", "3 0 0.5 0", "0", "3 0 0 0 0"] {
+            let data = format!(
+                "ply
+format ascii 1.0
+element vertex 1
+property double x
+property double y
+property double z
+element face 1
+property list uchar uint vertex_indices
+property list uchar uint material_index
+end_header
+0 0 0
+{face} 1 0
+"
+            );
+            assert!(
+                matches!(read_ply(Cursor::new(data)), Err(PlyError::InvalidData(_))),
+                "Face {face} accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn test_repeated_corner() {
+        for face in ["3 0 0 1", "4 0 1 2 1"] {
+            let data = format!(
+                "ply
+format ascii 1.0
+element vertex 3
+property double x
+property double y
+property double z
+element face 1
+property list uchar uint vertex_indices
+end_header
+0 0 0
+1 0 0
+0 1 0
+{face}
+"
+            );
+            assert!(
+                matches!(read_ply(Cursor::new(data)), Err(PlyError::InvalidData(_))),
+                "Face {face} accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn test_group_names_round_trip() {
+        let names = [
+            " Drilling ",
+            "Edge  banding",
+            "Line\nbreak\r",
+            "Back\\slash\\n",
+            "",
+        ];
+        let cube = create_cube(1);
+        let groups: Vec<_> = (0..cube.triangles().len())
+            .map(|idx| (idx % names.len()) as u32)
+            .collect();
+        let cube = cube
+            .with_face_attributes(
+                FaceAttributes::default()
+                    .with_groups(groups, names.iter().map(|n| n.to_string()).collect()),
+            )
+            .expect("Invalid attributes");
+        let mut data = Vec::new();
+        write_ply(&cube, PlyFormat::Ascii, &mut data).expect("Cannot write");
+        let loaded = read_ply(Cursor::new(data)).expect("Cannot read");
+        assert_eq!(&names[..], loaded.face_attributes().group_names());
+    }
+
+    #[test]
+    fn test_unnamed_groups() {
+        let data = "ply
+format ascii 1.0
+element vertex 3
+property double x
+property double y
+property double z
+element face 1
+property list uchar uint vertex_indices
+property uint group_index
+end_header
+0 0 0
+1 0 0
+0 1 0
+3 0 1 2 2
+";
+        let triangle = read_ply(Cursor::new(data)).expect("Cannot read");
+        assert_eq!(Some(&[2][..]), triangle.face_attributes().groups());
+        assert_eq!(Some(""), triangle.face_attributes().group_name(0));
+    }
+}
@@ -7,22 +7,59 @@ pub mod test;
 pub mod prelude {
     pub use crate::{
         geometry2d::{
+            delaunay::{constrained_delaunay, DelaunayTriangulation},
+            hull::convex_hull_2d,
             line::Line2d,
             point::{
                 bounding_box::{BoundingBox2d, BoundingBox2dValues},
                 Point2d, StaticPoint2d,
             },
-            polygon::{cut::PolygonPath, AnyPolygon, Polygon2d},
-            triangle::{static_triangle::StaticTriangle2d, Triangle2d},
+            polygon::{
+                boolean::{boolean_operation, BooleanOperation},
+                cut::PolygonPath,
+                offset::{offset_polygon, JoinType, MAX_ARC_STEPS},
+                with_holes::{PolygonWithHoles, TriangulationFailed},
+                AnyPolygon, FillRule, Orientation, Polygon2d,
+            },
+            refine::{refine_delaunay, RefinementOptions},
+            rtree::TriangleIndex2d,
+            sweep::{segment_intersections, SegmentIntersection},
+            triangle::{
+                mesh_cut::{cut_triangles, CutPointOrigin, MeshCut},
+                static_triangle::StaticTriangle2d,
+                Triangle2d, TriangulationMethod,
+            },
+            vector::Vector2d,
         },
         geometry3d::{
+            assembly::{Assembly, AssemblyError, AssemblyHit, Instance},
+            hull::{convex_hull_3d, ConvexHullError},
             line::{static_line::StaticLine3d, Line3d},
             plane::{projection::PlaneProjection, Plane3d},
-            point::{point_3d, Point3d},
+            point::{
+                bounding_box::{BoundingBox3d, BoundingBox3dValues},
+                kd_tree::KdTree3d,
+                point_3d, Point3d,
+            },
+            transform::Transform3d,
             triangle::Triangle3d,
-            triangles::{topology::TriangleTopology, IndexedTriangleList, ReferencedTriangle},
+            triangles::{
+                attributes::{AttributeError, Color, FaceAttributes, VertexAttributes},
+                compare::{MeshComparison, MeshDeviation},
+                decimate::DecimationTarget,
+                half_edge::{FaceId, HalfEdgeId, HalfEdgeMesh, VertexId},
+                properties::MeshProperties,
+                topology::{
+                    owned::OwnedTriangleTopology,
+                    statistics::{MeshStatistics, ShellStatistics},
+                    TriangleTopology,
+                },
+                validate::{EdgeReport, ValidationReport},
+                IndexedTriangleList, ReferencedTriangle,
+            },
             Vector3d,
         },
+        io::ply::{read_ply, write_ply, PlyError, PlyFormat},
         primitives::{Float, Number},
     };
 }
@@ -1,8 +1,9 @@
+use std::collections::HashMap;
 use std::io::Cursor;
 
 use stl_io::{IndexedMesh, Vector};
 
-use crate::prelude::IndexedTriangleList;
+use crate::prelude::{point_3d, IndexedTriangleList, Vector3d};
 
 pub fn load_schublade() -> IndexedMesh {
     let bytes = include_bytes!("Schublade - Front.stl");
@@ -13,3 +14,67 @@ pub fn load_schublade() -> IndexedMesh {
 pub fn load_schublade_as_triangles() -> IndexedTriangleList<Vector<f32>> {
     load_schublade().into()
 }
+
+/// axis aligned unit cube where every face is split into `divisions` x `divisions` squares
+pub fn create_cube(divisions: usize) -> IndexedTriangleList<Vector3d> {
+    assert!(divisions > 0);
+    let mut builder = IndexedTriangleList::<Vector3d>::builder::<Vector3d>();
+    let mut point_indices = HashMap::new();
+    let mut point_idx = |coordinates: [usize; 3]| {
+        *point_indices.entry(coordinates).or_insert_with(|| {
+            let [x, y, z] = coordinates.map(|c| c as f64 / divisions as f64);
+            builder.append_point(point_3d(x, y, z))
+        })
+    };
+    let mut triangles = Vec::new();
+    for axis in 0..3 {
+        let u_axis = (axis + 1) % 3;
+        let v_axis = (axis + 2) % 3;
+        for (layer, outward) in [(0, false), (divisions, true)] {
+            for u in 0..divisions {
+                for v in 0..divisions {
+                    let mut corner = |du: usize, dv: usize| {
+                        let mut coordinates = [0; 3];
+                        coordinates[axis] = layer;
+                        coordinates[u_axis] = u + du;
+                        coordinates[v_axis] = v + dv;
+                        point_idx(coordinates)
+                    };
+                    let p00 = corner(0, 0);
+                    let p10 = corner(1, 0);
+                    let p11 = corner(1, 1);
+                    let p01 = corner(0, 1);
+                    if outward {
+                        triangles.push([p00, p10, p11]);
+                        triangles.push([p00, p11, p01]);
+                    } else {
+                        triangles.push([p00, p11, p10]);
+                        triangles.push([p00, p01, p11]);
+                    }
+                }
+            }
+        }
+    }
+    for [p1, p2, p3] in triangles {
+        builder.append_indexed_triangle(p1, p2, p3);
+    }
+    builder.build()
+}
+
+/// flat grid of `size` x `size` unit squares in the xy plane, two triangles per square
+pub fn create_grid(size: usize) -> IndexedTriangleList<Vector3d> {
+    let mut builder = IndexedTriangleList::<Vector3d>::builder::<Vector3d>();
+    for y in 0..=size {
+        for x in 0..=size {
+            builder.append_point(point_3d(x as f64, y as f64, 0.0));
+        }
+    }
+    for y in 0..size {
+        for x in 0..size {
+            let p = y * (size + 1) + x;
+            builder.append_indexed_triangle(p, p + 1, p + size + 2);
+            builder.append_indexed_triangle(p, p + size + 2, p + size + 1);
+        }
+    }
+    builder.build()
+}